@@ -5,4 +5,5 @@ mod benchmarks;
 criterion_main! {
 	benchmarks::parse_single::benches,
 	benchmarks::parse_compare::benches,
+	benchmarks::parse_lazy::benches,
 }
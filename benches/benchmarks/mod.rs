@@ -1,2 +1,3 @@
 pub(crate) mod parse_compare;
+pub(crate) mod parse_lazy;
 pub(crate) mod parse_single;
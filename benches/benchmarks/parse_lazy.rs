@@ -0,0 +1,33 @@
+use std::str::FromStr;
+
+use criterion::{criterion_group, BenchmarkId, Criterion, Throughput};
+use tdtxt::Task;
+
+const INPUT: &str = "x (A) 2016-05-20 2016-04-30 measure space for \
+                     +chapelShelving @chapel due:2016-05-30";
+
+/// Compares parsing alone (components are located lazily) with parsing and
+/// accessing the components.
+fn parse_lazy(c: &mut Criterion) {
+	let mut group = c.benchmark_group("Parse Lazy");
+	group.throughput(Throughput::Bytes(INPUT.len() as u64));
+
+	group.bench_with_input(BenchmarkId::new("parse", INPUT), INPUT, |b, s| {
+		b.iter(|| Task::from_str(s))
+	});
+
+	group.bench_with_input(
+		BenchmarkId::new("parse_and_index", INPUT),
+		INPUT,
+		|b, s| {
+			b.iter(|| {
+				Task::from_str(s)
+					.map(|task| task.description.projects().count())
+			})
+		},
+	);
+
+	group.finish();
+}
+
+criterion_group!(benches, parse_lazy);
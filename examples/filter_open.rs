@@ -1,4 +1,3 @@
-use std::cmp::Ordering;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::str::FromStr;
@@ -18,11 +17,11 @@ macro_rules! abort {
 }
 
 fn main() {
-	let args = std::env::args_os();
+	let mut args = std::env::args_os();
 
 	if args.len() == 2 {
 		// Get the path from the arguments
-		let path = args.skip(1).next().unwrap_or_else(|| print_help_exit());
+		let path = args.nth(1).unwrap_or_else(|| print_help_exit());
 
 		// Create a buffered reader to read line by line
 		let buf_reader =
@@ -38,8 +37,8 @@ fn main() {
 			.filter(|task| task.state == State::Open)
 			.collect::<Vec<_>>();
 
-		// Order by priority and the creation date
-		open_tasks.sort_by(task_ord);
+		// Order by priority, the creation date and the description
+		open_tasks.sort();
 
 		// Print open tasks
 		println!("=== OPEN TASKS ===");
@@ -56,23 +55,3 @@ fn print_help_exit() -> ! {
 	println!("cargo run --example filter_open -- PATH");
 	std::process::exit(1);
 }
-
-/// Orders tasks by priority (highest to lowest) and then by creation date (older to newest/none).
-fn task_ord(a: &Task, b: &Task) -> Ordering {
-	// Invert order, so that the highest priority is on top
-	let prio_cmp = b.priority().cmp(&a.priority());
-
-	if prio_cmp == Ordering::Equal {
-		match (
-			a.date_compound().map(|dc| dc.date_created()),
-			b.date_compound().map(|dc| dc.date_created()),
-		) {
-			(Some(adc), Some(bdc)) => adc.cmp(bdc),
-			(None, Some(_)) => Ordering::Greater,
-			(Some(_), None) => Ordering::Less,
-			(None, None) => Ordering::Equal,
-		}
-	} else {
-		prio_cmp
-	}
-}
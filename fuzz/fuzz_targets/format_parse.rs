@@ -0,0 +1,9 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use tdtxt::{check_format_parse_identity, Task};
+
+fuzz_target!(|task: Task| {
+	if let Err(err) = check_format_parse_identity(&task) {
+		panic!("{}", err);
+	}
+});
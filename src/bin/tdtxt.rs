@@ -0,0 +1,6 @@
+fn main() {
+	if let Err(err) = tdtxt::cli::run(std::env::args_os().skip(1)) {
+		eprintln!("ERROR: {}", err);
+		std::process::exit(1);
+	}
+}
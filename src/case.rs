@@ -0,0 +1,27 @@
+/// Returns `true` if `a` and `b` are equal when ignoring their case, e.g.
+/// `GarageSale` and `garagesale`.
+///
+/// Without the feature `caseless` only ASCII letters are compared
+/// case-insensitively. With the feature full unicode case folding is used,
+/// e.g. `Straße` equals `STRASSE`.
+///
+/// # Examples
+///
+/// ```rust
+/// use tdtxt::eq_ignore_case;
+///
+/// assert!(eq_ignore_case("GarageSale", "garagesale"));
+/// assert!(!eq_ignore_case("GarageSale", "Garage"));
+/// ```
+#[cfg_attr(not(feature = "caseless"), allow(clippy::missing_const_for_fn))]
+pub fn eq_ignore_case(a: &str, b: &str) -> bool {
+	#[cfg(feature = "caseless")]
+	{
+		caseless::default_caseless_match_str(a, b)
+	}
+
+	#[cfg(not(feature = "caseless"))]
+	{
+		a.eq_ignore_ascii_case(b)
+	}
+}
@@ -0,0 +1,274 @@
+//! A minimal command line interface for todo.txt files.
+//!
+//! This module is only available with the feature `cli` and serves as a
+//! reference integration of the crate. The binary `tdtxt` is a thin wrapper
+//! around [`run`].
+//!
+//! # Usage
+//!
+//! ```text
+//! tdtxt PATH list [QUERY...]   Lists all open tasks matching QUERY
+//! tdtxt PATH add TEXT...       Adds a new task
+//! tdtxt PATH do NUMBER...      Marks the tasks as done
+//! tdtxt PATH archive [DONE]    Moves all done tasks to DONE (default: done.txt)
+//! ```
+//!
+//! Tasks are numbered by their position in the file, starting with `1`. The
+//! syntax of queries is described in [`Filter::parse`].
+
+use std::ffi::OsString;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::clock::{Clock, SystemClock};
+use crate::file::{ParseTodoFileError, TodoFile};
+use crate::filter::Filter;
+use crate::sort::SortKey;
+use crate::task::{CompletionPolicy, Task};
+
+/// The usage text printed for invalid invocations.
+pub const USAGE: &str = "\
+usage: tdtxt PATH COMMAND [ARGS...]
+
+commands:
+    list [QUERY...]   Lists all open tasks matching QUERY
+    add TEXT...       Adds a new task
+    do NUMBER...      Marks the tasks as done
+    archive [DONE]    Moves all done tasks to DONE (default: done.txt)";
+
+/// This enum represents all errors which can occur while running the cli.
+#[derive(Debug)]
+pub enum CliError {
+	/// The arguments were invalid.
+	Usage(String),
+
+	/// Reading or writing a file failed.
+	Io(io::Error),
+
+	/// The todo file could not be parsed.
+	Parse(ParseTodoFileError),
+}
+
+impl fmt::Display for CliError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Usage(msg) => write!(f, "{}\n\n{}", msg, USAGE),
+			Self::Io(err) => write!(f, "io error: {}", err),
+			Self::Parse(err) => fmt::Display::fmt(err, f),
+		}
+	}
+}
+
+impl std::error::Error for CliError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Self::Usage(_) => None,
+			Self::Io(err) => Some(err),
+			Self::Parse(err) => Some(err),
+		}
+	}
+}
+
+impl From<io::Error> for CliError {
+	fn from(value: io::Error) -> Self {
+		Self::Io(value)
+	}
+}
+
+impl From<ParseTodoFileError> for CliError {
+	fn from(value: ParseTodoFileError) -> Self {
+		Self::Parse(value)
+	}
+}
+
+/// Runs the cli with `args` (without the program name) and writes all output
+/// to stdout.
+pub fn run<I, T>(args: I) -> Result<(), CliError>
+where
+	I: IntoIterator<Item = T>,
+	T: Into<OsString>,
+{
+	run_with_output(args, &mut io::stdout().lock())
+}
+
+/// Runs the cli with `args` (without the program name) and writes all output
+/// to `out`.
+pub fn run_with_output<I, T, W>(args: I, out: &mut W) -> Result<(), CliError>
+where
+	I: IntoIterator<Item = T>,
+	T: Into<OsString>,
+	W: Write,
+{
+	run_with_clock(args, out, SystemClock)
+}
+
+/// Runs the cli like [`run_with_output`] but takes the current date (e.g.
+/// for completion dates) from `clock`.
+pub(crate) fn run_with_clock<I, T, W, C>(
+	args: I,
+	out: &mut W,
+	clock: C,
+) -> Result<(), CliError>
+where
+	I: IntoIterator<Item = T>,
+	T: Into<OsString>,
+	W: Write,
+	C: Clock,
+{
+	let args = args
+		.into_iter()
+		.map(|arg| {
+			arg.into().into_string().map_err(|arg| {
+				CliError::Usage(format!("invalid argument `{:?}`", arg))
+			})
+		})
+		.collect::<Result<Vec<_>, _>>()?;
+
+	let (path, command, args) = match args.as_slice() {
+		[path, command, args @ ..] => (Path::new(path), command, args),
+		_ => return Err(CliError::Usage("missing arguments".into())),
+	};
+
+	match command.as_str() {
+		"list" => list(path, args, out),
+		"add" => add(path, args, out),
+		"do" => done(path, args, out, &CompletionPolicy::new(clock)),
+		"archive" => archive(path, args, out),
+		command => {
+			Err(CliError::Usage(format!("unknown command `{}`", command)))
+		}
+	}
+}
+
+fn read(path: &Path) -> Result<TodoFile, CliError> {
+	match fs::read_to_string(path) {
+		Ok(content) => Ok(TodoFile::from_str(&content)?),
+		Err(err) if err.kind() == io::ErrorKind::NotFound => {
+			Ok(TodoFile::new())
+		}
+		Err(err) => Err(err.into()),
+	}
+}
+
+fn write(path: &Path, todo: &TodoFile) -> Result<(), CliError> {
+	todo.save_atomic(path)?;
+	Ok(())
+}
+
+fn list<W: Write>(
+	path: &Path,
+	args: &[String],
+	out: &mut W,
+) -> Result<(), CliError> {
+	let filter = Filter::parse(&args.join(" "))
+		.map_err(|err| CliError::Usage(err.to_string()))?
+		.and(Filter::not_done());
+
+	let todo = read(path)?;
+
+	let mut open_tasks = todo.filter(&filter).collect::<Vec<_>>();
+
+	open_tasks.sort_by(|(_, a), (_, b)| {
+		SortKey::compare_all(&[SortKey::Priority, SortKey::CreatedDate], a, b)
+	});
+
+	for (idx, task) in open_tasks {
+		writeln!(out, "{} {}", idx + 1, task)?;
+	}
+
+	Ok(())
+}
+
+fn add<W: Write>(
+	path: &Path,
+	args: &[String],
+	out: &mut W,
+) -> Result<(), CliError> {
+	let line = args.join(" ");
+
+	let task = Task::from_str(&line)
+		.map_err(|_| CliError::Usage(format!("invalid task `{}`", line)))?;
+
+	let mut todo = read(path)?;
+	let idx = todo.add(task);
+	write(path, &todo)?;
+
+	writeln!(out, "{} {}", idx + 1, &todo.tasks()[idx])?;
+
+	Ok(())
+}
+
+fn done<W: Write, C: Clock>(
+	path: &Path,
+	args: &[String],
+	out: &mut W,
+	policy: &CompletionPolicy<C>,
+) -> Result<(), CliError> {
+	if args.is_empty() {
+		return Err(CliError::Usage("`do` requires a task number".into()));
+	}
+
+	let mut todo = read(path)?;
+
+	for arg in args {
+		let idx = arg
+			.parse::<usize>()
+			.ok()
+			.and_then(|nr| nr.checked_sub(1))
+			.filter(|&idx| idx < todo.len())
+			.ok_or_else(|| {
+				CliError::Usage(format!("invalid task number `{}`", arg))
+			})?;
+
+		let _ = todo.modify(idx, |task| {
+			let _ = task.complete(policy.clock.today(), policy.priority);
+		});
+		writeln!(out, "{} {}", idx + 1, &todo.tasks()[idx])?;
+	}
+
+	write(path, &todo)
+}
+
+fn archive<W: Write>(
+	path: &Path,
+	args: &[String],
+	out: &mut W,
+) -> Result<(), CliError> {
+	let done_path = match args {
+		[] => path.with_file_name("done.txt"),
+		[done_path] => PathBuf::from(done_path),
+		_ => {
+			return Err(CliError::Usage(
+				"`archive` takes at most one argument".into(),
+			))
+		}
+	};
+
+	let mut todo = read(path)?;
+	let archived = todo.drain_done();
+
+	if archived.is_empty() {
+		return Ok(());
+	}
+
+	let mut done_file =
+		OpenOptions::new().create(true).append(true).open(&done_path)?;
+
+	for task in &archived {
+		writeln!(done_file, "{}", task)?;
+	}
+
+	write(path, &todo)?;
+
+	writeln!(
+		out,
+		"archived {} task(s) to {}",
+		archived.len(),
+		done_path.display()
+	)?;
+
+	Ok(())
+}
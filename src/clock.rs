@@ -0,0 +1,63 @@
+use crate::date::Date;
+
+/// Supplies the current date for operations which depend on "today", e.g.
+/// stamping completion dates (see
+/// [`CompletionPolicy`](`crate::CompletionPolicy`)).
+///
+/// Functions which only compare dates (e.g.
+/// [`Task::is_overdue`](`crate::Task::is_overdue`)) take the current date as
+/// argument, which can be obtained with [`Clock::today`]. Use a
+/// [`FixedClock`] to make them deterministic, e.g. in tests.
+///
+/// The trait is implemented for all closures with the signature
+/// `Fn() -> Date`.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::str::FromStr;
+///
+/// use tdtxt::{Clock, Date, FixedClock, Task};
+///
+/// let clock = FixedClock(Date::from_ymd(2021, 8, 1));
+/// let task = Task::from_str("Pay rent due:2021-07-31").unwrap();
+///
+/// assert!(task.is_overdue(clock.today()));
+/// assert_eq!(
+///     Date::parse_relative("tomorrow", clock.today()),
+///     Some(Date::from_ymd(2021, 8, 2))
+/// );
+/// ```
+pub trait Clock {
+	/// Returns the current date.
+	fn today(&self) -> Date;
+}
+
+impl<F> Clock for F
+where
+	F: Fn() -> Date,
+{
+	fn today(&self) -> Date {
+		self()
+	}
+}
+
+/// A [`Clock`] returning the date of the system (see [`Date::today`]).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+	fn today(&self) -> Date {
+		Date::today()
+	}
+}
+
+/// A [`Clock`] which always returns the same date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FixedClock(pub Date);
+
+impl Clock for FixedClock {
+	fn today(&self) -> Date {
+		self.0
+	}
+}
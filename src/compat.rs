@@ -0,0 +1,43 @@
+//! Conversions between [`Task`] and [`todo_txt::Task`].
+//!
+//! The conversions format the task and parse it with the parser of the
+//! target crate. This way each crate stays in charge of its own
+//! interpretation of the todo.txt format.
+//!
+//! # Notes
+//!
+//! The conversions are not necessarily lossless, e.g. `todo_txt` orders
+//! custom tags by their key and only keeps the last value for each key.
+
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+use crate::task::{ParseTaskError, Task};
+
+impl From<&Task> for todo_txt::Task {
+	fn from(value: &Task) -> Self {
+		todo_txt::parser::task(&value.to_string())
+	}
+}
+
+impl From<Task> for todo_txt::Task {
+	fn from(value: Task) -> Self {
+		Self::from(&value)
+	}
+}
+
+impl TryFrom<&todo_txt::Task> for Task {
+	type Error = ParseTaskError;
+
+	fn try_from(value: &todo_txt::Task) -> Result<Self, Self::Error> {
+		Self::from_str(&value.to_string())
+	}
+}
+
+impl TryFrom<todo_txt::Task> for Task {
+	type Error = ParseTaskError;
+
+	fn try_from(value: todo_txt::Task) -> Result<Self, Self::Error> {
+		Self::try_from(&value)
+	}
+}
@@ -1,10 +1,25 @@
+use std::cmp::Ordering;
+use std::convert::TryFrom;
 use std::fmt;
-use std::ops::Deref;
+use std::ops::{Bound, Deref, RangeBounds, Sub};
 
 use crate::parse::{Parse, Parser};
 
-/// A very basic date type used when feature `chrono` is not active.
-#[cfg(not(feature = "chrono"))]
+/// Returns the number of days of the one-indexed `month` in `year`.
+const fn days_in_month(year: i16, month: u8) -> u8 {
+	match month {
+		2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+		2 => 28,
+		4 | 6 | 9 | 11 => 30,
+		_ => 31,
+	}
+}
+
+/// A very basic date type used when neither feature `chrono` nor `time` is
+/// active.
+///
+/// It is available with every backend and can be converted from and into
+/// [`Date`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct SimpleDate {
 	/// Year of the date.
@@ -17,7 +32,6 @@ pub struct SimpleDate {
 	day: u8,
 }
 
-#[cfg(not(feature = "chrono"))]
 impl SimpleDate {
 	/// Creates a new date.
 	///
@@ -26,10 +40,14 @@ impl SimpleDate {
 	/// Can panic if the month/day are not within the limits.
 	///
 	/// - Month: 1-12
-	/// - Day:   1-31
+	/// - Day:   1-28/29/30/31, depending on the month and whether the year
+	///   is a leap year
 	pub fn from_ymd(year: i16, month: u8, day: u8) -> Self {
 		assert!((1..=12).contains(&month), "month must be between 1-12");
-		assert!((1..=31).contains(&day), "day must be between 1-31");
+		assert!(
+			(1..=days_in_month(year, month)).contains(&day),
+			"day must be within the month"
+		);
 
 		Self { year, month, day }
 	}
@@ -38,8 +56,20 @@ impl SimpleDate {
 	/// failed.
 	///
 	/// For more information about what could fail see: [`Self::from_ymd`].
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::SimpleDate;
+	///
+	/// assert!(SimpleDate::from_ymd_opt(2020, 2, 29).is_some());
+	/// assert!(SimpleDate::from_ymd_opt(2021, 2, 29).is_none());
+	/// assert!(SimpleDate::from_ymd_opt(2021, 4, 31).is_none());
+	/// ```
 	pub fn from_ymd_opt(year: i16, month: u8, day: u8) -> Option<Self> {
-		if (1..=12).contains(&month) && (1..=31).contains(&day) {
+		if (1..=12).contains(&month)
+			&& (1..=days_in_month(year, month)).contains(&day)
+		{
 			Some(Self { year, month, day })
 		} else {
 			None
@@ -60,9 +90,100 @@ impl SimpleDate {
 	pub const fn day(&self) -> u8 {
 		self.day
 	}
+
+	/// Returns the number of days since `1970-01-01`.
+	fn to_days(self) -> i64 {
+		// See: http://howardhinnant.github.io/date_algorithms.html
+		let month = i64::from(self.month);
+		let year = i64::from(self.year) - i64::from(month <= 2);
+		let era = year.div_euclid(400);
+		let yoe = year.rem_euclid(400);
+		let doy = (153 * ((month + 9) % 12) + 2) / 5 + i64::from(self.day) - 1;
+		let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+
+		era * 146_097 + doe - 719_468
+	}
+
+	/// Creates a date from the number of days since `1970-01-01`.
+	///
+	/// Returns `None` if the year does not fit.
+	fn from_days(days: i64) -> Option<Self> {
+		// See: http://howardhinnant.github.io/date_algorithms.html
+		let days = days.checked_add(719_468)?;
+		let era = days.div_euclid(146_097);
+		let doe = days.rem_euclid(146_097);
+		let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+		let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+		let mp = (5 * doy + 2) / 153;
+		let day = doy - (153 * mp + 2) / 5 + 1;
+		let month = if mp < 10 { mp + 3 } else { mp - 9 };
+		let year = yoe + era * 400 + i64::from(month <= 2);
+
+		Some(Self {
+			year: i16::try_from(year).ok()?,
+			month: month as u8,
+			day: day as u8,
+		})
+	}
+
+	/// Adds `days` to the date. Returns `None` if the year of the resulting
+	/// date would be out of range.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::SimpleDate;
+	///
+	/// let date = SimpleDate::from_ymd(2020, 2, 28);
+	///
+	/// assert_eq!(date.add_days(1), Some(SimpleDate::from_ymd(2020, 2, 29)));
+	/// assert_eq!(date.add_days(2), Some(SimpleDate::from_ymd(2020, 3, 1)));
+	/// assert_eq!(date.add_days(-59), Some(SimpleDate::from_ymd(2019, 12, 31)));
+	/// ```
+	pub fn add_days(self, days: i64) -> Option<Self> {
+		Self::from_days(self.to_days().checked_add(days)?)
+	}
+
+	/// Subtracts `days` from the date. Returns `None` if the year of the
+	/// resulting date would be out of range.
+	pub fn sub_days(self, days: i64) -> Option<Self> {
+		Self::from_days(self.to_days().checked_sub(days)?)
+	}
+
+	/// Returns the following day. Returns `None` if the year of the
+	/// resulting date would be out of range.
+	pub fn succ(self) -> Option<Self> {
+		self.add_days(1)
+	}
+
+	/// Returns the previous day. Returns `None` if the year of the resulting
+	/// date would be out of range.
+	pub fn pred(self) -> Option<Self> {
+		self.sub_days(1)
+	}
+}
+
+/// Returns the number of days from `rhs` to `self`, which is negative if
+/// `rhs` is after `self`.
+///
+/// # Examples
+///
+/// ```rust
+/// use tdtxt::SimpleDate;
+///
+/// let date = SimpleDate::from_ymd(2021, 3, 1);
+///
+/// assert_eq!(date - SimpleDate::from_ymd(2021, 2, 1), 28);
+/// assert_eq!(date - SimpleDate::from_ymd(2022, 3, 1), -365);
+/// ```
+impl Sub for SimpleDate {
+	type Output = i64;
+
+	fn sub(self, rhs: Self) -> Self::Output {
+		self.to_days() - rhs.to_days()
+	}
 }
 
-#[cfg(not(feature = "chrono"))]
 impl fmt::Display for SimpleDate {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 		write!(f, "{:04}-{:02}-{:02}", &self.year, &self.month, &self.day)
@@ -72,9 +193,23 @@ impl fmt::Display for SimpleDate {
 /// A simple date structure, which represents the date in the format
 /// `yyyy-mm-dd`.
 ///
+/// Dates are ordered chronologically, independent of the backing type.
+///
 /// # Notes
 ///
-/// The inner/backing type is depended on the feature `chrono`.
+/// The inner/backing type is depended on the features `chrono` and `time`:
+///
+#[cfg_attr(feature = "chrono", doc = " - `chrono`: [`chrono::NaiveDate`]")]
+#[cfg_attr(not(feature = "chrono"), doc = " - `chrono`: `chrono::NaiveDate`")]
+#[cfg_attr(
+	feature = "time",
+	doc = " - `time` (without `chrono`): [`time::Date`]"
+)]
+#[cfg_attr(
+	not(feature = "time"),
+	doc = " - `time` (without `chrono`): `time::Date`"
+)]
+/// - otherwise: [`SimpleDate`]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Date {
 	/// Inner backing type.
@@ -82,7 +217,11 @@ pub struct Date {
 	inner: chrono::NaiveDate,
 
 	/// Inner backing type.
-	#[cfg(not(feature = "chrono"))]
+	#[cfg(all(feature = "time", not(feature = "chrono")))]
+	inner: time::Date,
+
+	/// Inner backing type.
+	#[cfg(not(any(feature = "chrono", feature = "time")))]
 	inner: SimpleDate,
 }
 
@@ -98,20 +237,38 @@ impl Date {
 	/// Can panic if the date is invalid.
 	/// For more information see the relevant backing implementation:
 	#[cfg_attr(feature = "chrono", doc = " [`chrono::NaiveDate::from_ymd`]")]
-	#[cfg_attr(not(feature = "chrono"), doc = " [`SimpleDate::from_ymd`]")]
+	#[cfg_attr(
+		all(feature = "time", not(feature = "chrono")),
+		doc = " [`time::Date::from_calendar_date`]"
+	)]
+	#[cfg_attr(
+		not(any(feature = "chrono", feature = "time")),
+		doc = " [`SimpleDate::from_ymd`]"
+	)]
+	#[cfg_attr(
+		any(feature = "chrono", feature = "time"),
+		allow(clippy::missing_const_for_fn)
+	)]
 	pub fn from_ymd(year: i16, month: u8, day: u8) -> Self {
 		#[cfg(feature = "chrono")]
 		{
 			Self {
-				inner: chrono::NaiveDate::from_ymd(
+				inner: chrono::NaiveDate::from_ymd_opt(
 					year as i32,
 					month as u32,
 					day as u32,
-				),
+				)
+				.expect("invalid or out-of-range date"),
 			}
 		}
 
-		#[cfg(not(feature = "chrono"))]
+		#[cfg(all(feature = "time", not(feature = "chrono")))]
+		{
+			Self::from_ymd_opt(year, month, day)
+				.expect("invalid or out-of-range date")
+		}
+
+		#[cfg(not(any(feature = "chrono", feature = "time")))]
 		{
 			Self { inner: SimpleDate::from_ymd(year, month, day) }
 		}
@@ -127,7 +284,14 @@ impl Date {
 		feature = "chrono",
 		doc = " [`chrono::NaiveDate::from_ymd_opt`]"
 	)]
-	#[cfg_attr(not(feature = "chrono"), doc = " [`SimpleDate::from_ymd_opt`]")]
+	#[cfg_attr(
+		all(feature = "time", not(feature = "chrono")),
+		doc = " [`time::Date::from_calendar_date`]"
+	)]
+	#[cfg_attr(
+		not(any(feature = "chrono", feature = "time")),
+		doc = " [`SimpleDate::from_ymd_opt`]"
+	)]
 	pub fn from_ymd_opt(year: i16, month: u8, day: u8) -> Option<Self> {
 		#[cfg(feature = "chrono")]
 		{
@@ -140,19 +304,987 @@ impl Date {
 			Some(Self { inner: date })
 		}
 
-		#[cfg(not(feature = "chrono"))]
+		#[cfg(all(feature = "time", not(feature = "chrono")))]
+		{
+			let month = time::Month::try_from(month).ok()?;
+			let date =
+				time::Date::from_calendar_date(i32::from(year), month, day)
+					.ok()?;
+
+			Some(Self { inner: date })
+		}
+
+		#[cfg(not(any(feature = "chrono", feature = "time")))]
 		{
 			Some(Self { inner: SimpleDate::from_ymd_opt(year, month, day)? })
 		}
 	}
 
 	/// Returns a `Date` which corresponds to the current date.
-	#[cfg(feature = "chrono")]
+	///
+	/// The current date is determined by the backend:
+	///
+	/// - `chrono`: the local date
+	/// - `time`: the local date, or the date in UTC if the local offset can
+	///   not be determined
+	/// - otherwise: the date in UTC, as there is no timezone support
+	///
+	/// # Panics
+	///
+	/// Without the features `chrono` and `time` this panics if the current
+	/// year does not fit into `i16`.
 	pub fn today() -> Self {
-		Self { inner: chrono::Local::today().naive_utc() }
+		#[cfg(feature = "chrono")]
+		{
+			Self { inner: chrono::Local::now().date_naive() }
+		}
+
+		#[cfg(all(feature = "time", not(feature = "chrono")))]
+		{
+			let now = time::OffsetDateTime::now_local()
+				.unwrap_or_else(|_| time::OffsetDateTime::now_utc());
+
+			Self { inner: now.date() }
+		}
+
+		#[cfg(not(any(feature = "chrono", feature = "time")))]
+		{
+			use std::time::{SystemTime, UNIX_EPOCH};
+
+			const SECONDS_PER_DAY: i64 = 86_400;
+
+			let seconds = match SystemTime::now().duration_since(UNIX_EPOCH) {
+				Ok(since) => since.as_secs() as i64,
+				Err(err) => {
+					let before = err.duration();
+
+					-(before.as_secs() as i64)
+						- i64::from(before.subsec_nanos() > 0)
+				}
+			};
+
+			Self {
+				inner: SimpleDate::from_days(
+					seconds.div_euclid(SECONDS_PER_DAY),
+				)
+				.expect("current year out of range"),
+			}
+		}
+	}
+
+	/// Parses a date from `s`, accepting alternative formats besides the
+	/// canonical `yyyy-mm-dd`.
+	///
+	/// The following formats are accepted:
+	///
+	/// - `yyyy-m-d` with `-`, `/` or `.` as separator and optionally
+	///   zero-padded month and day (e.g. `2021/8/1` or `2021.08.01`)
+	/// - `yyyymmdd` (e.g. `20210801`)
+	///
+	/// Returns `None` if `s` is in none of the formats or the date is
+	/// invalid.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::Date;
+	///
+	/// let date = Date::from_ymd(2021, 8, 1);
+	///
+	/// assert_eq!(Date::parse_lenient("2021-08-01"), Some(date));
+	/// assert_eq!(Date::parse_lenient("2021/8/1"), Some(date));
+	/// assert_eq!(Date::parse_lenient("20210801"), Some(date));
+	/// assert_eq!(Date::parse_lenient("2021/13/1"), None);
+	/// ```
+	pub fn parse_lenient(s: &str) -> Option<Self> {
+		fn number<T: std::str::FromStr>(s: &str, len: &[usize]) -> Option<T> {
+			if len.contains(&s.len()) && s.bytes().all(|b| b.is_ascii_digit())
+			{
+				s.parse().ok()
+			} else {
+				None
+			}
+		}
+
+		let (year, month, day) = match s.find(['-', '/', '.']) {
+			Some(idx) => {
+				let mut parts = s.split(char::from(s.as_bytes()[idx]));
+
+				let year = parts.next()?;
+				let month = parts.next()?;
+				let day = parts.next()?;
+
+				if parts.next().is_some() {
+					return None;
+				}
+
+				(year, month, day)
+			}
+			None if s.len() == 8 => (s.get(..4)?, s.get(4..6)?, s.get(6..)?),
+			None => return None,
+		};
+
+		Self::from_ymd_opt(
+			number(year, &[4])?,
+			number(month, &[1, 2])?,
+			number(day, &[1, 2])?,
+		)
+	}
+
+	/// Parses a date relative to `today`, e.g. from user input.
+	///
+	/// The following formats are accepted (case-insensitive):
+	///
+	/// - `today`, `tomorrow` and `yesterday`
+	/// - the name of a weekday, full or abbreviated to three letters (e.g.
+	///   `mon` or `friday`), for the next such day after `today`
+	/// - an offset of days, weeks, months or years with an optional sign
+	///   (e.g. `+3d`, `2w`, `-1m` or `+1y`)
+	/// - every format accepted by [`Self::parse_lenient`]
+	///
+	/// Returns `None` if `s` is in none of the formats or the date would be
+	/// out of range.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::Date;
+	///
+	/// // A sunday
+	/// let today = Date::from_ymd(2021, 8, 1);
+	///
+	/// assert_eq!(
+	///     Date::parse_relative("tomorrow", today),
+	///     Some(Date::from_ymd(2021, 8, 2))
+	/// );
+	/// assert_eq!(
+	///     Date::parse_relative("Fri", today),
+	///     Some(Date::from_ymd(2021, 8, 6))
+	/// );
+	/// assert_eq!(
+	///     Date::parse_relative("+2w", today),
+	///     Some(Date::from_ymd(2021, 8, 15))
+	/// );
+	/// assert_eq!(
+	///     Date::parse_relative("2021-09-01", today),
+	///     Some(Date::from_ymd(2021, 9, 1))
+	/// );
+	/// assert_eq!(Date::parse_relative("someday", today), None);
+	/// ```
+	pub fn parse_relative(s: &str, today: Self) -> Option<Self> {
+		let lower = s.trim().to_ascii_lowercase();
+
+		match lower.as_str() {
+			"today" => return Some(today),
+			"tomorrow" => return today.checked_add_days(Days(1)),
+			"yesterday" => return today.checked_add_days(Days(-1)),
+			_ => {}
+		}
+
+		if let Some(weekday) = Weekday::ALL.iter().find(|weekday| {
+			let name = weekday.name().to_ascii_lowercase();
+
+			lower == name || lower == name[..3]
+		}) {
+			let days = (i64::from(weekday.days_from_monday())
+				- i64::from(today.weekday().days_from_monday()))
+			.rem_euclid(7);
+
+			// Always the next such day, never `today`
+			let days = if days == 0 { 7 } else { days };
+
+			return today.checked_add_days(Days(days));
+		}
+
+		let unsigned = lower.trim_start_matches(['+', '-']);
+		let unit_idx = unsigned.len().checked_sub(1)?;
+
+		if lower.len() - unsigned.len() <= 1
+			&& unit_idx > 0
+			&& unsigned.as_bytes()[..unit_idx].iter().all(u8::is_ascii_digit)
+		{
+			let amount = unsigned[..unit_idx].parse::<i64>().ok()?;
+			let amount = if lower.starts_with('-') { -amount } else { amount };
+
+			return match unsigned.as_bytes()[unit_idx] {
+				b'd' => today.checked_add_days(Days(amount)),
+				b'w' => today.checked_add_days(Days(amount.checked_mul(7)?)),
+				b'm' => today.checked_add_months(i32::try_from(amount).ok()?),
+				b'y' => today.checked_add_months(
+					i32::try_from(amount.checked_mul(12)?).ok()?,
+				),
+				_ => None,
+			};
+		}
+
+		Self::parse_lenient(s.trim())
+	}
+
+	/// Parses a date relative to the current date of the system (see
+	/// [`Self::parse_relative`] and [`Self::today`]).
+	///
+	/// Use [`Self::parse_relative`] with [`Clock::today`](`crate::Clock`)
+	/// for other clocks.
+	pub fn parse_relative_today(s: &str) -> Option<Self> {
+		Self::parse_relative(s, Self::today())
+	}
+
+	/// Adds `days` to the date. Returns `None` if the resulting date would be
+	/// out of range.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::{Date, Days};
+	///
+	/// let date = Date::from_ymd(2021, 12, 30);
+	///
+	/// assert_eq!(
+	///     date.checked_add_days(Days(3)),
+	///     Some(Date::from_ymd(2022, 1, 2))
+	/// );
+	/// assert_eq!(
+	///     date.checked_add_days(Days(-30)),
+	///     Some(Date::from_ymd(2021, 11, 30))
+	/// );
+	/// ```
+	pub fn checked_add_days(self, days: Days) -> Option<Self> {
+		#[cfg(feature = "chrono")]
+		{
+			let delta = chrono::Days::new(days.0.unsigned_abs());
+			let inner = if days.0 < 0 {
+				self.inner.checked_sub_days(delta)?
+			} else {
+				self.inner.checked_add_days(delta)?
+			};
+
+			// Keep the same limits as `from_ymd`
+			i16::try_from(chrono::Datelike::year(&inner)).ok()?;
+
+			Some(Self { inner })
+		}
+
+		#[cfg(all(feature = "time", not(feature = "chrono")))]
+		{
+			let julian_day =
+				i64::from(self.inner.to_julian_day()).checked_add(days.0)?;
+			let inner =
+				time::Date::from_julian_day(i32::try_from(julian_day).ok()?)
+					.ok()?;
+
+			// Keep the same limits as `from_ymd`
+			i16::try_from(inner.year()).ok()?;
+
+			Some(Self { inner })
+		}
+
+		#[cfg(not(any(feature = "chrono", feature = "time")))]
+		{
+			let inner = SimpleDate::from_days(
+				self.inner.to_days().checked_add(days.0)?,
+			)?;
+
+			Some(Self { inner })
+		}
+	}
+
+	/// Adds `months` to the date. Returns `None` if the resulting date would
+	/// be out of range.
+	///
+	/// If the day does not exist in the resulting month, the last day of the
+	/// month is used instead.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::Date;
+	///
+	/// let date = Date::from_ymd(2021, 1, 31);
+	///
+	/// assert_eq!(
+	///     date.checked_add_months(1),
+	///     Some(Date::from_ymd(2021, 2, 28))
+	/// );
+	/// assert_eq!(
+	///     date.checked_add_months(-2),
+	///     Some(Date::from_ymd(2020, 11, 30))
+	/// );
+	/// ```
+	pub fn checked_add_months(self, months: i32) -> Option<Self> {
+		#[cfg(feature = "chrono")]
+		{
+			let delta = chrono::Months::new(months.unsigned_abs());
+			let inner = if months < 0 {
+				self.inner.checked_sub_months(delta)?
+			} else {
+				self.inner.checked_add_months(delta)?
+			};
+
+			// Keep the same limits as `from_ymd`
+			i16::try_from(chrono::Datelike::year(&inner)).ok()?;
+
+			Some(Self { inner })
+		}
+
+		#[cfg(all(feature = "time", not(feature = "chrono")))]
+		{
+			let months = i64::from(self.inner.year()) * 12
+				+ i64::from(u8::from(self.inner.month()) - 1)
+				+ i64::from(months);
+			let year = i16::try_from(months.div_euclid(12)).ok()?;
+			let month = months.rem_euclid(12) as u8 + 1;
+			let day = self.inner.day().min(days_in_month(year, month));
+
+			Self::from_ymd_opt(year, month, day)
+		}
+
+		#[cfg(not(any(feature = "chrono", feature = "time")))]
+		{
+			let months = i64::from(self.inner.year) * 12
+				+ i64::from(self.inner.month - 1)
+				+ i64::from(months);
+			let year = i16::try_from(months.div_euclid(12)).ok()?;
+			let month = months.rem_euclid(12) as u8 + 1;
+			let day = self.inner.day.min(days_in_month(year, month));
+
+			Some(Self { inner: SimpleDate::from_ymd_opt(year, month, day)? })
+		}
+	}
+
+	/// Returns the number of days from the date until `other`, which is
+	/// negative if `other` is before the date.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::Date;
+	///
+	/// let date = Date::from_ymd(2021, 2, 1);
+	///
+	/// assert_eq!(date.days_until(&Date::from_ymd(2021, 3, 1)), 28);
+	/// assert_eq!(date.days_until(&Date::from_ymd(2020, 2, 1)), -366);
+	/// assert_eq!(date.days_until(&date), 0);
+	/// ```
+	pub fn days_until(&self, other: &Self) -> i64 {
+		other.to_days() - self.to_days()
+	}
+
+	/// Returns the number of days since `1970-01-01`.
+	pub(crate) fn to_days(self) -> i64 {
+		#[cfg(feature = "chrono")]
+		{
+			// Days from `0001-01-01` to `1970-01-01`
+			const EPOCH_DAYS_FROM_CE: i64 = 719_163;
+
+			i64::from(chrono::Datelike::num_days_from_ce(&self.inner))
+				- EPOCH_DAYS_FROM_CE
+		}
+
+		#[cfg(all(feature = "time", not(feature = "chrono")))]
+		{
+			// Julian day of `1970-01-01`
+			const EPOCH_JULIAN_DAY: i64 = 2_440_588;
+
+			i64::from(self.inner.to_julian_day()) - EPOCH_JULIAN_DAY
+		}
+
+		#[cfg(not(any(feature = "chrono", feature = "time")))]
+		{
+			self.inner.to_days()
+		}
+	}
+
+	/// Returns the year of the date.
+	///
+	/// Unlike [`Self::from_ymd`] this returns an `i32`, as dates converted
+	/// from a backing type can have years outside of the range of `i16`.
+	// Can only be `const` for some backends
+	#[allow(clippy::missing_const_for_fn)]
+	pub fn year(&self) -> i32 {
+		#[cfg(feature = "chrono")]
+		{
+			chrono::Datelike::year(&self.inner)
+		}
+
+		#[cfg(all(feature = "time", not(feature = "chrono")))]
+		{
+			self.inner.year()
+		}
+
+		#[cfg(not(any(feature = "chrono", feature = "time")))]
+		{
+			i32::from(self.inner.year())
+		}
+	}
+
+	/// Returns the one-indexed month of the date.
+	// Can only be `const` for some backends
+	#[allow(clippy::missing_const_for_fn)]
+	pub fn month(&self) -> u8 {
+		#[cfg(feature = "chrono")]
+		{
+			chrono::Datelike::month(&self.inner) as u8
+		}
+
+		#[cfg(all(feature = "time", not(feature = "chrono")))]
+		{
+			u8::from(self.inner.month())
+		}
+
+		#[cfg(not(any(feature = "chrono", feature = "time")))]
+		{
+			self.inner.month()
+		}
+	}
+
+	/// Returns the one-indexed day of the month of the date.
+	// Can only be `const` for some backends
+	#[allow(clippy::missing_const_for_fn)]
+	pub fn day(&self) -> u8 {
+		#[cfg(feature = "chrono")]
+		{
+			chrono::Datelike::day(&self.inner) as u8
+		}
+
+		#[cfg(all(feature = "time", not(feature = "chrono")))]
+		{
+			self.inner.day()
+		}
+
+		#[cfg(not(any(feature = "chrono", feature = "time")))]
+		{
+			self.inner.day()
+		}
+	}
+
+	/// Returns the day of the week of the date.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::{Date, Weekday};
+	///
+	/// let date = Date::from_ymd(2021, 8, 1);
+	///
+	/// assert_eq!(date.weekday(), Weekday::Sunday);
+	/// assert_eq!(date.ordinal(), 213);
+	/// assert_eq!((date.year(), date.month(), date.day()), (2021, 8, 1));
+	/// ```
+	pub fn weekday(&self) -> Weekday {
+		// `1970-01-01` was a thursday
+		Weekday::from_monday((self.to_days() + 3).rem_euclid(7) as u8)
+	}
+
+	/// Returns the one-indexed day of the year of the date.
+	// Can only be `const` for some backends
+	#[allow(clippy::missing_const_for_fn)]
+	pub fn ordinal(&self) -> u16 {
+		#[cfg(feature = "chrono")]
+		{
+			chrono::Datelike::ordinal(&self.inner) as u16
+		}
+
+		#[cfg(all(feature = "time", not(feature = "chrono")))]
+		{
+			self.inner.ordinal()
+		}
+
+		#[cfg(not(any(feature = "chrono", feature = "time")))]
+		{
+			let first = SimpleDate::from_ymd(self.inner.year(), 1, 1);
+
+			(self.inner.to_days() - first.to_days()) as u16 + 1
+		}
+	}
+
+	/// Returns the ISO 8601 week of the date.
+	///
+	/// ISO weeks start on monday and the first week of a year is the one
+	/// containing its first thursday, so the first and last days of a year
+	/// can belong to a week of the adjacent year.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::{Date, IsoWeek};
+	///
+	/// let date = Date::from_ymd(2021, 1, 3);
+	///
+	/// assert_eq!(date.iso_week(), IsoWeek { year: 2020, week: 53 });
+	/// assert_eq!(date.iso_week().to_string(), "2020-W53");
+	/// assert_eq!(date.week_start(), Some(Date::from_ymd(2020, 12, 28)));
+	/// assert_eq!(date.week_end(), Some(date));
+	/// ```
+	pub fn iso_week(&self) -> IsoWeek {
+		let year = self.year();
+		let weekday = i32::from(self.weekday().days_from_monday()) + 1;
+		let week = (i32::from(self.ordinal()) - weekday + 10) / 7;
+
+		if week < 1 {
+			IsoWeek { year: year - 1, week: IsoWeek::weeks_in_year(year - 1) }
+		} else if week > i32::from(IsoWeek::weeks_in_year(year)) {
+			IsoWeek { year: year + 1, week: 1 }
+		} else {
+			IsoWeek { year, week: week as u8 }
+		}
+	}
+
+	/// Returns the monday of the week of the date. Returns `None` if the
+	/// date would be out of range.
+	pub fn week_start(&self) -> Option<Self> {
+		let days = i64::from(self.weekday().days_from_monday());
+
+		self.checked_add_days(Days(-days))
+	}
+
+	/// Returns the sunday of the week of the date. Returns `None` if the
+	/// date would be out of range.
+	pub fn week_end(&self) -> Option<Self> {
+		let days = i64::from(self.weekday().days_from_monday());
+
+		self.checked_add_days(Days(6 - days))
+	}
+
+	/// Returns the date relative to `reference` (e.g. today) for display,
+	/// e.g. `in 3 days` or `2 weeks ago`.
+	///
+	/// The returned value implements [`fmt::Display`] and uses
+	/// [`EnglishPhrasing`] by default, other phrasings can be used with
+	/// [`RelativeDate::with_phrasing`].
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::Date;
+	///
+	/// let today = Date::from_ymd(2021, 8, 1);
+	///
+	/// let relative = |y, m, d| Date::from_ymd(y, m, d).relative_to(today);
+	///
+	/// assert_eq!(relative(2021, 8, 2).to_string(), "tomorrow");
+	/// assert_eq!(relative(2021, 8, 4).to_string(), "in 3 days");
+	/// assert_eq!(relative(2021, 7, 18).to_string(), "2 weeks ago");
+	///
+	/// let short = relative(2021, 8, 4).with_phrasing(
+	///     |days: i64, f: &mut std::fmt::Formatter<'_>| write!(f, "{:+}d", days),
+	/// );
+	/// assert_eq!(short.to_string(), "+3d");
+	/// ```
+	pub fn relative_to(self, reference: Self) -> RelativeDate {
+		RelativeDate {
+			days: reference.days_until(&self),
+			phrasing: EnglishPhrasing,
+		}
+	}
+
+	/// Returns the date formatted according to `pattern` for display, e.g.
+	/// `dd.mm.yyyy`.
+	///
+	/// The returned value implements [`fmt::Display`], [`Date`] itself is
+	/// always displayed as `yyyy-mm-dd` as required by the todo.txt format.
+	///
+	/// The pattern consists of the following placeholders, all other
+	/// characters are written as they are:
+	///
+	/// | Placeholder | Output                                  |
+	/// |-------------|-----------------------------------------|
+	/// | `yyyy`      | year, at least four digits (`2021`)     |
+	/// | `yy`        | last two digits of the year (`21`)      |
+	/// | `mmmm`      | english name of the month (`August`)    |
+	/// | `mmm`       | abbreviated name of the month (`Aug`)   |
+	/// | `mm`        | month, two digits (`08`)                |
+	/// | `m`         | month (`8`)                             |
+	/// | `dddd`      | english name of the weekday (`Sunday`)  |
+	/// | `ddd`       | abbreviated name of the weekday (`Sun`) |
+	/// | `dd`        | day, two digits (`01`)                  |
+	/// | `d`         | day (`1`)                               |
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::Date;
+	///
+	/// let date = Date::from_ymd(2021, 8, 1);
+	///
+	/// assert_eq!(date.format_with("dd.mm.yyyy").to_string(), "01.08.2021");
+	/// assert_eq!(date.format_with("m/d/yy").to_string(), "8/1/21");
+	/// assert_eq!(date.format_with("ddd, d mmm").to_string(), "Sun, 1 Aug");
+	/// assert_eq!(date.to_string(), "2021-08-01");
+	/// ```
+	pub const fn format_with(self, pattern: &str) -> FormattedDate<'_> {
+		FormattedDate { date: self, pattern }
+	}
+}
+
+/// Phrases the distance between two dates (see [`Date::relative_to`]).
+///
+/// The trait is implemented for all closures with the signature
+/// `Fn(i64, &mut fmt::Formatter<'_>) -> fmt::Result`.
+pub trait RelativePhrasing {
+	/// Writes the phrase for a date `days` days after the reference date
+	/// (negative if it is before the reference date).
+	fn fmt_relative(
+		&self,
+		days: i64,
+		f: &mut fmt::Formatter<'_>,
+	) -> fmt::Result;
+}
+
+impl<F> RelativePhrasing for F
+where
+	F: Fn(i64, &mut fmt::Formatter<'_>) -> fmt::Result,
+{
+	fn fmt_relative(
+		&self,
+		days: i64,
+		f: &mut fmt::Formatter<'_>,
+	) -> fmt::Result {
+		self(days, f)
+	}
+}
+
+/// Phrases relative dates in english, e.g. `today`, `in 3 days` or `2 weeks
+/// ago`.
+///
+/// Distances are rounded down to whole days (below a week), weeks (below
+/// 30 days), months of 30 days (below a year) or years of 365 days.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EnglishPhrasing;
+
+impl RelativePhrasing for EnglishPhrasing {
+	fn fmt_relative(
+		&self,
+		days: i64,
+		f: &mut fmt::Formatter<'_>,
+	) -> fmt::Result {
+		let (amount, unit) = match days.unsigned_abs() {
+			0 => return f.write_str("today"),
+			1 if days > 0 => return f.write_str("tomorrow"),
+			1 => return f.write_str("yesterday"),
+			n @ 2..=6 => (n, "day"),
+			n @ 7..=29 => (n / 7, "week"),
+			n @ 30..=364 => (n / 30, "month"),
+			n => (n / 365, "year"),
+		};
+		let plural = if amount == 1 { "" } else { "s" };
+
+		if days > 0 {
+			write!(f, "in {} {}{}", amount, unit, plural)
+		} else {
+			write!(f, "{} {}{} ago", amount, unit, plural)
+		}
+	}
+}
+
+/// A date relative to a reference date, which can be displayed with a
+/// [`RelativePhrasing`] (see [`Date::relative_to`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RelativeDate<P = EnglishPhrasing> {
+	/// Days from the reference date to the date.
+	days: i64,
+
+	/// The phrasing used for displaying.
+	phrasing: P,
+}
+
+impl<P> RelativeDate<P> {
+	/// Returns the number of days from the reference date to the date,
+	/// which is negative if the date is before the reference date.
+	pub const fn days(&self) -> i64 {
+		self.days
+	}
+
+	/// Uses `phrasing` for displaying the date.
+	pub fn with_phrasing<Q>(self, phrasing: Q) -> RelativeDate<Q>
+	where
+		Q: RelativePhrasing,
+	{
+		RelativeDate { days: self.days, phrasing }
+	}
+}
+
+impl<P> fmt::Display for RelativeDate<P>
+where
+	P: RelativePhrasing,
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		self.phrasing.fmt_relative(self.days, f)
 	}
 }
 
+/// A date formatted with a custom pattern (see [`Date::format_with`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FormattedDate<'a> {
+	/// The formatted date.
+	date: Date,
+
+	/// The pattern used for formatting.
+	pattern: &'a str,
+}
+
+impl FormattedDate<'_> {
+	/// The english names of the months.
+	const MONTHS: [&'static str; 12] = [
+		"January",
+		"February",
+		"March",
+		"April",
+		"May",
+		"June",
+		"July",
+		"August",
+		"September",
+		"October",
+		"November",
+		"December",
+	];
+}
+
+impl fmt::Display for FormattedDate<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let date = &self.date;
+		let month = Self::MONTHS[usize::from(date.month() - 1)];
+		let weekday = date.weekday().name();
+		let mut rest = self.pattern;
+
+		while let Some(c) = rest.chars().next() {
+			let run = match c {
+				'y' | 'm' | 'd' => {
+					rest.len() - rest.trim_start_matches(c).len()
+				}
+				_ => c.len_utf8(),
+			};
+
+			match (c, run) {
+				('y', 4) => write!(f, "{:04}", date.year())?,
+				('y', 2) => write!(f, "{:02}", date.year().rem_euclid(100))?,
+				('m', 4) => f.write_str(month)?,
+				('m', 3) => f.write_str(&month[..3])?,
+				('m', 2) => write!(f, "{:02}", date.month())?,
+				('m', 1) => write!(f, "{}", date.month())?,
+				('d', 4) => f.write_str(weekday)?,
+				('d', 3) => f.write_str(&weekday[..3])?,
+				('d', 2) => write!(f, "{:02}", date.day())?,
+				('d', 1) => write!(f, "{}", date.day())?,
+				_ => f.write_str(&rest[..run])?,
+			}
+
+			rest = &rest[run..];
+		}
+
+		Ok(())
+	}
+}
+
+/// A week according to ISO 8601 (see [`Date::iso_week`]).
+///
+/// Weeks are ordered chronologically, e.g. for bucketing tasks by week.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IsoWeek {
+	/// The ISO year, which differs from the calendar year for some days
+	/// around new year.
+	pub year: i32,
+
+	/// The one-indexed week of the ISO year (1-53).
+	pub week: u8,
+}
+
+impl IsoWeek {
+	/// Returns the number of ISO weeks (52 or 53) of the ISO year `year`.
+	pub const fn weeks_in_year(year: i32) -> u8 {
+		// Weekday of the 31st of december (`0` for sunday)
+		const fn last_weekday(year: i32) -> i32 {
+			(year + year.div_euclid(4) - year.div_euclid(100)
+				+ year.div_euclid(400))
+			.rem_euclid(7)
+		}
+
+		// Years ending on a thursday or starting on a thursday
+		if last_weekday(year) == 4 || last_weekday(year - 1) == 3 {
+			53
+		} else {
+			52
+		}
+	}
+}
+
+impl fmt::Display for IsoWeek {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{:04}-W{:02}", self.year, self.week)
+	}
+}
+
+/// A day of the week.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Weekday {
+	/// Monday.
+	Monday,
+
+	/// Tuesday.
+	Tuesday,
+
+	/// Wednesday.
+	Wednesday,
+
+	/// Thursday.
+	Thursday,
+
+	/// Friday.
+	Friday,
+
+	/// Saturday.
+	Saturday,
+
+	/// Sunday.
+	Sunday,
+}
+
+impl Weekday {
+	/// All days of the week, starting with [`Self::Monday`].
+	pub const ALL: [Self; 7] = [
+		Self::Monday,
+		Self::Tuesday,
+		Self::Wednesday,
+		Self::Thursday,
+		Self::Friday,
+		Self::Saturday,
+		Self::Sunday,
+	];
+
+	/// Returns the weekday `days` days after monday, wrapping around after
+	/// sunday.
+	pub const fn from_monday(days: u8) -> Self {
+		Self::ALL[(days % 7) as usize]
+	}
+
+	/// Returns the number of days since monday (`0` for monday, `6` for
+	/// sunday).
+	pub const fn days_from_monday(&self) -> u8 {
+		*self as u8
+	}
+
+	/// Returns the english name of the weekday (e.g. `Monday`).
+	pub const fn name(&self) -> &'static str {
+		match self {
+			Self::Monday => "Monday",
+			Self::Tuesday => "Tuesday",
+			Self::Wednesday => "Wednesday",
+			Self::Thursday => "Thursday",
+			Self::Friday => "Friday",
+			Self::Saturday => "Saturday",
+			Self::Sunday => "Sunday",
+		}
+	}
+}
+
+/// A range of dates with inclusive, exclusive or open bounds, e.g. for
+/// filtering tasks by date (see
+/// [`Filter::due_within`](`crate::Filter::due_within`)).
+///
+/// It can be created from all range types over [`Date`] (e.g. `a..b`,
+/// `a..=b` or `a..`) with [`DateRange::new`].
+///
+/// # Examples
+///
+/// ```rust
+/// use std::ops::Bound;
+///
+/// use tdtxt::{Date, DateRange};
+///
+/// let start = Date::from_ymd(2021, 8, 1);
+/// let end = Date::from_ymd(2021, 8, 31);
+///
+/// let august = DateRange::new(start..=end);
+/// assert!(august.contains(&end));
+/// assert!(!august.contains(&Date::from_ymd(2021, 9, 1)));
+///
+/// let before = DateRange::new(..start);
+/// assert!(!before.contains(&start));
+/// assert_eq!(before.end(), Bound::Excluded(start));
+///
+/// assert!(DateRange::all().contains(&start));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DateRange {
+	/// The lower bound.
+	start: Bound<Date>,
+
+	/// The upper bound.
+	end: Bound<Date>,
+}
+
+impl DateRange {
+	/// Creates a date range with the same bounds as `range`.
+	pub fn new<R>(range: R) -> Self
+	where
+		R: RangeBounds<Date>,
+	{
+		Self {
+			start: range.start_bound().cloned(),
+			end: range.end_bound().cloned(),
+		}
+	}
+
+	/// Creates a date range containing all dates.
+	pub const fn all() -> Self {
+		Self { start: Bound::Unbounded, end: Bound::Unbounded }
+	}
+
+	/// Returns the lower bound.
+	pub const fn start(&self) -> Bound<Date> {
+		self.start
+	}
+
+	/// Returns the upper bound.
+	pub const fn end(&self) -> Bound<Date> {
+		self.end
+	}
+
+	/// Returns `true` if `date` is within the range.
+	pub fn contains(&self, date: &Date) -> bool {
+		RangeBounds::contains(self, date)
+	}
+}
+
+impl Default for DateRange {
+	fn default() -> Self {
+		Self::all()
+	}
+}
+
+impl RangeBounds<Date> for DateRange {
+	fn start_bound(&self) -> Bound<&Date> {
+		self.start.as_ref()
+	}
+
+	fn end_bound(&self) -> Bound<&Date> {
+		self.end.as_ref()
+	}
+}
+
+/// A signed number of days, used to shift dates.
+#[derive(
+	Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(transparent)
+)]
+pub struct Days(pub i64);
+
+/// The key of the custom tag holding the due date of a task (e.g.
+/// `due:2021-08-01`).
+pub const DUE_TAG: &str = "due";
+
+/// The key of the custom tag holding the threshold date of a task (e.g.
+/// `t:2021-08-01`).
+///
+/// A task should not be worked on before its threshold date.
+pub const THRESHOLD_TAG: &str = "t";
+
 impl fmt::Display for Date {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 		#[cfg(feature = "chrono")]
@@ -160,7 +1292,18 @@ impl fmt::Display for Date {
 			f.write_str(&self.inner.format(Self::DATE_FORMAT).to_string())
 		}
 
-		#[cfg(not(feature = "chrono"))]
+		#[cfg(all(feature = "time", not(feature = "chrono")))]
+		{
+			write!(
+				f,
+				"{:04}-{:02}-{:02}",
+				self.inner.year(),
+				u8::from(self.inner.month()),
+				self.inner.day()
+			)
+		}
+
+		#[cfg(not(any(feature = "chrono", feature = "time")))]
 		{
 			fmt::Display::fmt(&self.inner, f)
 		}
@@ -181,21 +1324,95 @@ impl From<Date> for chrono::NaiveDate {
 	}
 }
 
-#[cfg(not(feature = "chrono"))]
+/// # Panics
+///
+/// Panics if the date is out of range for the backing type (only possible if
+/// feature `time` is enabled with its own feature `large-dates`).
+#[cfg(feature = "time")]
+impl From<time::Date> for Date {
+	fn from(value: time::Date) -> Self {
+		#[cfg(feature = "chrono")]
+		{
+			let date = chrono::NaiveDate::from_ymd_opt(
+				value.year(),
+				u32::from(u8::from(value.month())),
+				u32::from(value.day()),
+			)
+			.expect("date out of range for `chrono::NaiveDate`");
+
+			Self { inner: date }
+		}
+
+		#[cfg(not(feature = "chrono"))]
+		{
+			Self { inner: value }
+		}
+	}
+}
+
+/// # Panics
+///
+/// Panics if the date is out of range for `time::Date` (only possible for
+/// dates converted from a `chrono::NaiveDate`).
+#[cfg(feature = "time")]
+impl From<Date> for time::Date {
+	fn from(value: Date) -> Self {
+		#[cfg(feature = "chrono")]
+		{
+			let month = time::Month::try_from(value.month())
+				.expect("month is always valid");
+
+			Self::from_calendar_date(value.year(), month, value.day())
+				.expect("date out of range for `time::Date`")
+		}
+
+		#[cfg(not(feature = "chrono"))]
+		{
+			value.inner
+		}
+	}
+}
+
+/// # Panics
+///
+/// Panics if the date is out of range for the backing type (only possible
+/// for years above `9999` with feature `time`).
 impl From<SimpleDate> for Date {
 	fn from(value: SimpleDate) -> Self {
-		Self { inner: value }
+		#[cfg(any(feature = "chrono", feature = "time"))]
+		{
+			Self::from_ymd(value.year(), value.month(), value.day())
+		}
+
+		#[cfg(not(any(feature = "chrono", feature = "time")))]
+		{
+			Self { inner: value }
+		}
 	}
 }
 
-#[cfg(not(feature = "chrono"))]
+/// # Panics
+///
+/// Panics if the year of the date does not fit into an `i16` (only possible
+/// for dates converted from a backing type).
 impl From<Date> for SimpleDate {
 	fn from(value: Date) -> Self {
-		value.inner
+		#[cfg(any(feature = "chrono", feature = "time"))]
+		{
+			let year = i16::try_from(value.year())
+				.expect("year out of range for `SimpleDate`");
+
+			Self::from_ymd(year, value.month(), value.day())
+		}
+
+		#[cfg(not(any(feature = "chrono", feature = "time")))]
+		{
+			value.inner
+		}
 	}
 }
 
-#[cfg(not(feature = "chrono"))]
+#[cfg(not(any(feature = "chrono", feature = "time")))]
 impl AsRef<SimpleDate> for Date {
 	fn as_ref(&self) -> &SimpleDate {
 		&self.inner
@@ -209,11 +1426,20 @@ impl AsRef<chrono::NaiveDate> for Date {
 	}
 }
 
+#[cfg(all(feature = "time", not(feature = "chrono")))]
+impl AsRef<time::Date> for Date {
+	fn as_ref(&self) -> &time::Date {
+		&self.inner
+	}
+}
+
 impl Deref for Date {
-	#[cfg(not(feature = "chrono"))]
+	#[cfg(not(any(feature = "chrono", feature = "time")))]
 	type Target = SimpleDate;
 	#[cfg(feature = "chrono")]
 	type Target = chrono::NaiveDate;
+	#[cfg(all(feature = "time", not(feature = "chrono")))]
+	type Target = time::Date;
 
 	fn deref(&self) -> &Self::Target {
 		&self.inner
@@ -250,6 +1476,7 @@ impl Parse for Date {
 }
 
 crate::impl_fromstr!(Date);
+crate::impl_parse_prefix!(Date);
 
 #[cfg(feature = "serde")]
 impl serde::Serialize for Date {
@@ -290,15 +1517,61 @@ impl<'de> serde::de::Deserialize<'de> for Date {
 	}
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Date {
+	fn arbitrary(
+		u: &mut arbitrary::Unstructured<'a>,
+	) -> arbitrary::Result<Self> {
+		// Restricted to dates which are valid for every backend and can be
+		// represented with the `yyyy-mm-dd` format.
+		let year = u.int_in_range(0..=9999)?;
+		let month = u.int_in_range(1..=12)?;
+		let day = u.int_in_range(1..=28)?;
+
+		Ok(Self::from_ymd(year, month, day))
+	}
+}
+
 /// Represents the attached dates a [`Task`](`crate::Task`) can have.
 ///
 /// The dates must be given in the format `yyyy-mm-dd`.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+///
+/// # Ordering
+///
+/// Date compounds are ordered by their creation date and then by their
+/// completion date, where a missing completion date comes first.
+///
+/// ```rust
+/// use tdtxt::{Date, DateCompound};
+///
+/// let first = Date::from_ymd(2021, 8, 1);
+/// let second = Date::from_ymd(2021, 8, 2);
+///
+/// let mut compounds = vec![
+///     DateCompound::completed(second, second),
+///     DateCompound::completed(first, second),
+///     DateCompound::created(second),
+///     DateCompound::completed(first, first),
+/// ];
+/// compounds.sort();
+///
+/// assert_eq!(
+///     compounds,
+///     [
+///         DateCompound::completed(first, first),
+///         DateCompound::completed(first, second),
+///         DateCompound::created(second),
+///         DateCompound::completed(second, second),
+///     ]
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(
 	feature = "serde",
 	derive(serde::Serialize, serde::Deserialize),
 	serde(untagged)
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum DateCompound {
 	// NOTE: The order in which the variants are order matters (see: serde(untagged)).
 	/// Two dates, a completion date and a creation date.
@@ -340,6 +1613,58 @@ impl DateCompound {
 		}
 	}
 
+	/// Creates a new date compound for a created and completion date
+	/// ([`DateCompound::Completed`]), rejecting a completion date before
+	/// the creation date.
+	///
+	/// # Errors
+	///
+	/// Returns an error if `completed` is before `created`.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::{Date, DateCompound};
+	///
+	/// let created = Date::from_ymd(2021, 8, 1);
+	///
+	/// assert!(DateCompound::completed_checked(created, created).is_ok());
+	/// assert!(
+	///     DateCompound::completed_checked(created, Date::from_ymd(2021, 7, 31))
+	///         .is_err()
+	/// );
+	/// ```
+	pub fn completed_checked<A, B>(
+		created: A,
+		completed: B,
+	) -> Result<Self, DateOrderError>
+	where
+		A: Into<Date>,
+		B: Into<Date>,
+	{
+		let compound = Self::completed(created, completed);
+
+		compound.check_order().map(|()| compound)
+	}
+
+	/// Checks that the completion date (if any) is not before the creation
+	/// date.
+	///
+	/// Such date compounds can be parsed and created with
+	/// [`Self::completed`], but usually indicate corrupted data.
+	///
+	/// # Errors
+	///
+	/// Returns an error if the completion date is before the creation date.
+	pub fn check_order(&self) -> Result<(), DateOrderError> {
+		match *self {
+			Self::Completed { created, completed } if completed < created => {
+				Err(DateOrderError { created, completed })
+			}
+			_ => Ok(()),
+		}
+	}
+
 	/// Returns the creation date.
 	pub const fn date_created(&self) -> &Date {
 		match self {
@@ -359,12 +1684,26 @@ impl DateCompound {
 	}
 }
 
+impl PartialOrd for DateCompound {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Ord for DateCompound {
+	fn cmp(&self, other: &Self) -> Ordering {
+		self.date_created()
+			.cmp(other.date_created())
+			.then_with(|| self.date_completed().cmp(&other.date_completed()))
+	}
+}
+
 impl fmt::Display for DateCompound {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 		match self {
 			Self::Created { created } => f.write_str(&created.to_string()),
 			Self::Completed { created, completed } => {
-				write!(f, "{} {}", completed.to_string(), created.to_string(),)
+				write!(f, "{} {}", completed, created)
 			}
 		}
 	}
@@ -389,6 +1728,39 @@ where
 	}
 }
 
+/// An error which is returned if the completion date of a
+/// [`DateCompound`] is before its creation date (see
+/// [`DateCompound::check_order`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DateOrderError {
+	created: Date,
+	completed: Date,
+}
+
+impl DateOrderError {
+	/// Returns the creation date.
+	pub const fn created(&self) -> &Date {
+		&self.created
+	}
+
+	/// Returns the completion date.
+	pub const fn completed(&self) -> &Date {
+		&self.completed
+	}
+}
+
+impl fmt::Display for DateOrderError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"completion date {} is before creation date {}",
+			self.completed, self.created
+		)
+	}
+}
+
+impl std::error::Error for DateOrderError {}
+
 crate::parse_error!(ParseDateCompoundError: "date compound");
 
 impl Parse for DateCompound {
@@ -403,7 +1775,7 @@ impl Parse for DateCompound {
 		if p_copy.expect_whitespace().is_some() {
 			if let Some(date2) = Date::parse_opt(&mut p_copy) {
 				// Check if eof or white space; if not it is a single date
-				if p_copy.peek().map_or(true, |c| c.is_ascii_whitespace()) {
+				if p_copy.peek().is_none_or(|c| c.is_ascii_whitespace()) {
 					*parser = p_copy;
 
 					return Ok(Self::Completed {
@@ -419,3 +1791,4 @@ impl Parse for DateCompound {
 }
 
 crate::impl_fromstr!(DateCompound);
+crate::impl_parse_prefix!(DateCompound);
@@ -0,0 +1,120 @@
+use std::fmt;
+
+use crate::date::Date;
+use crate::parse::{Parse, Parser};
+
+/// A date with a time of day, as written by some clients into custom tags
+/// (e.g. `due:2016-05-30T14:00`).
+///
+/// The format is `yyyy-mm-ddThh:mm` with optional seconds (`:ss`). A value
+/// without a time is a plain [`Date`], which is why
+/// [`TagValue::parse`](`crate::TagValue::parse`) only returns a
+/// [`TagValue::DateTime`](`crate::TagValue::DateTime`) if a time is present.
+///
+/// As the time contains colons, such tags are only recognized in
+/// descriptions with
+/// [`DescriptionOptions::colons_in_values`](`crate::DescriptionOptions::colons_in_values`),
+/// which can be set when parsing tasks with
+/// [`DecodeOptions::description_options`](`crate::DecodeOptions::description_options`).
+///
+/// # Examples
+///
+/// ```rust
+/// use std::str::FromStr;
+///
+/// use tdtxt::{Date, DateTimeTag};
+///
+/// let datetime = DateTimeTag::from_str("2016-05-30T14:00").unwrap();
+///
+/// assert_eq!(datetime.date, Date::from_ymd(2016, 5, 30));
+/// assert_eq!((datetime.hour, datetime.minute, datetime.second), (14, 0, 0));
+/// assert_eq!(datetime.to_string(), "2016-05-30T14:00");
+///
+/// assert!(DateTimeTag::from_str("2016-05-30").is_err());
+/// assert!(DateTimeTag::from_str("2016-05-30T24:00").is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DateTimeTag {
+	/// The date.
+	pub date: Date,
+
+	/// The hour (0-23).
+	pub hour: u8,
+
+	/// The minute (0-59).
+	pub minute: u8,
+
+	/// The second (0-59).
+	pub second: u8,
+}
+
+impl DateTimeTag {
+	/// Creates a new date with a time of day. Returns `None` if the time is
+	/// invalid.
+	pub const fn new(
+		date: Date,
+		hour: u8,
+		minute: u8,
+		second: u8,
+	) -> Option<Self> {
+		if hour < 24 && minute < 60 && second < 60 {
+			Some(Self { date, hour, minute, second })
+		} else {
+			None
+		}
+	}
+}
+
+impl fmt::Display for DateTimeTag {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}T{:02}:{:02}", self.date, self.hour, self.minute)?;
+
+		// Seconds are optional and mostly omitted by clients
+		if self.second != 0 {
+			write!(f, ":{:02}", self.second)?;
+		}
+
+		Ok(())
+	}
+}
+
+crate::parse_error!(ParseDateTimeTagError: "date time");
+
+impl Parse for DateTimeTag {
+	type Error = ParseDateTimeTagError;
+
+	fn parse(parser: &mut Parser<'_>) -> Result<Self, Self::Error> {
+		fn two_digits(parser: &mut Parser<'_>) -> Option<u8> {
+			Some(parser.parse_digit()? * 10 + parser.parse_digit()?)
+		}
+
+		let date = Date::parse_opt(parser)
+			.ok_or_else(ParseDateTimeTagError::default)?;
+		let _ = parser
+			.expect_u8(b'T')
+			.ok_or_else(ParseDateTimeTagError::default)?;
+		let hour =
+			two_digits(parser).ok_or_else(ParseDateTimeTagError::default)?;
+		let _ = parser
+			.expect_u8(b':')
+			.ok_or_else(ParseDateTimeTagError::default)?;
+		let minute =
+			two_digits(parser).ok_or_else(ParseDateTimeTagError::default)?;
+
+		let mut p_copy = *parser;
+		let second = match p_copy.expect_u8(b':') {
+			Some(_) => {
+				let second = two_digits(&mut p_copy)
+					.ok_or_else(ParseDateTimeTagError::default)?;
+				*parser = p_copy;
+				second
+			}
+			None => 0,
+		};
+
+		Self::new(date, hour, minute, second)
+			.ok_or_else(ParseDateTimeTagError::default)
+	}
+}
+
+crate::impl_fromstr!(DateTimeTag);
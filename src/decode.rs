@@ -0,0 +1,170 @@
+use std::borrow::Cow;
+
+use crate::description::DescriptionOptions;
+
+/// The byte order mark (BOM) of UTF-8.
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Decides how invalid UTF-8 is handled when decoding raw bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InvalidUtf8Policy {
+	/// Fail with an error.
+	Error,
+
+	/// Replace invalid sequences with `U+FFFD REPLACEMENT CHARACTER`.
+	Replace,
+
+	/// Skip all lines containing invalid sequences.
+	SkipLine,
+}
+
+/// Options for decoding raw bytes with
+/// [`Task::from_bytes`](`crate::Task::from_bytes`) and
+/// [`TodoFile::from_bytes`](`crate::TodoFile::from_bytes`).
+///
+/// # Defaults
+///
+/// A leading BOM is skipped, invalid UTF-8 results in an error, dates are
+/// not validated and descriptions use the default [`DescriptionOptions`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DecodeOptions {
+	/// Skip a leading UTF-8 byte order mark.
+	pub skip_bom: bool,
+
+	/// How invalid UTF-8 is handled.
+	pub invalid_utf8: InvalidUtf8Policy,
+
+	/// Reject tasks whose completion date is before their creation date
+	/// (see [`DateCompound::check_order`](`crate::DateCompound::check_order`)).
+	pub validate_dates: bool,
+
+	/// The options used to locate the components of the parsed
+	/// descriptions, e.g. to allow colons within tag values.
+	pub description: DescriptionOptions,
+}
+
+impl DecodeOptions {
+	/// Creates the default options.
+	pub const fn new() -> Self {
+		Self {
+			skip_bom: true,
+			invalid_utf8: InvalidUtf8Policy::Error,
+			validate_dates: false,
+			description: DescriptionOptions::new(),
+		}
+	}
+
+	/// Sets whether a leading UTF-8 byte order mark is skipped.
+	pub const fn skip_bom(mut self, skip_bom: bool) -> Self {
+		self.skip_bom = skip_bom;
+		self
+	}
+
+	/// Sets how invalid UTF-8 is handled.
+	pub const fn invalid_utf8(mut self, policy: InvalidUtf8Policy) -> Self {
+		self.invalid_utf8 = policy;
+		self
+	}
+
+	/// Sets whether tasks whose completion date is before their creation
+	/// date are rejected.
+	pub const fn validate_dates(mut self, validate_dates: bool) -> Self {
+		self.validate_dates = validate_dates;
+		self
+	}
+
+	/// Sets the options used to locate the components of the parsed
+	/// descriptions.
+	pub const fn description_options(
+		mut self,
+		options: DescriptionOptions,
+	) -> Self {
+		self.description = options;
+		self
+	}
+
+	/// Decodes `bytes` according to the options.
+	///
+	/// On error the one-indexed number of the line with the first invalid
+	/// sequence is returned.
+	pub(crate) fn decode<'a>(
+		&self,
+		bytes: &'a [u8],
+	) -> Result<Cow<'a, str>, usize> {
+		self.decode_lines(bytes).map(|(s, _)| s)
+	}
+
+	/// Decodes `bytes` according to the options like [`Self::decode`] and
+	/// additionally returns the ascending one-indexed numbers of the lines
+	/// which were skipped (see [`InvalidUtf8Policy::SkipLine`]).
+	pub(crate) fn decode_lines<'a>(
+		&self,
+		bytes: &'a [u8],
+	) -> Result<(Cow<'a, str>, Vec<usize>), usize> {
+		let bytes = match bytes.strip_prefix(UTF8_BOM) {
+			Some(stripped) if self.skip_bom => stripped,
+			_ => bytes,
+		};
+
+		match self.invalid_utf8 {
+			InvalidUtf8Policy::Error => std::str::from_utf8(bytes)
+				.map(|s| (Cow::Borrowed(s), Vec::new()))
+				.map_err(|err| line_number(bytes, err.valid_up_to())),
+			InvalidUtf8Policy::Replace => {
+				let s = String::from_utf8_lossy(bytes);
+
+				if let Cow::Owned(_) = s {
+					crate::trace_event!(
+						debug,
+						"replaced invalid utf-8 sequences"
+					);
+				}
+
+				Ok((s, Vec::new()))
+			}
+			InvalidUtf8Policy::SkipLine => match std::str::from_utf8(bytes) {
+				Ok(s) => Ok((Cow::Borrowed(s), Vec::new())),
+				Err(_) => {
+					let mut s = String::with_capacity(bytes.len());
+					let mut skipped = Vec::new();
+
+					for (idx, line) in
+						bytes.split_inclusive(|&b| b == b'\n').enumerate()
+					{
+						match std::str::from_utf8(line) {
+							Ok(line) => s.push_str(line),
+							Err(_) => {
+								crate::trace_event!(
+									debug,
+									"skipped line with invalid utf-8"
+								);
+								skipped.push(idx + 1);
+							}
+						}
+					}
+
+					Ok((Cow::Owned(s), skipped))
+				}
+			},
+		}
+	}
+}
+
+impl Default for DecodeOptions {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// Maps the one-indexed number of a decoded `line` back to its number in
+/// the input, given the ascending numbers of the `skipped` input lines.
+pub fn original_line(line: usize, skipped: &[usize]) -> usize {
+	skipped
+		.iter()
+		.fold(line, |line, &skip| if skip <= line { line + 1 } else { line })
+}
+
+/// Returns the one-indexed line number of the byte at `index`.
+fn line_number(bytes: &[u8], index: usize) -> usize {
+	bytes[..index].iter().filter(|&&b| b == b'\n').count() + 1
+}
@@ -0,0 +1,293 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fmt;
+
+use crate::id::ID_TAG;
+use crate::task::Task;
+
+/// The key of the custom tag referencing the id of a task the task depends
+/// on (e.g. `dep:42`).
+pub const DEPENDENCY_TAG: &str = "dep";
+
+/// The key of the custom tag referencing the id of a parent task (e.g.
+/// `p:42`). It is treated the same as [`DEPENDENCY_TAG`].
+pub const PARENT_TAG: &str = "p";
+
+/// The dependencies between the tasks of a list.
+///
+/// A task with the tag `dep:<id>` or `p:<id>` depends on every task with the
+/// tag `id:<id>` (see [`ID_TAG`](`crate::ID_TAG`)). A task is blocked as long
+/// as any task it depends on is still open.
+///
+/// Tasks are identified by their index in the list the graph was built from.
+/// The graph is a snapshot, it must be rebuilt after the list was modified.
+/// References to unknown ids are ignored.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::str::FromStr;
+///
+/// use tdtxt::TodoFile;
+///
+/// let todo = TodoFile::from_str(
+///     "Buy paint id:1\n\
+///      x Buy brushes id:2\n\
+///      Paint fence dep:1 dep:2\n",
+/// )
+/// .unwrap();
+///
+/// let graph = todo.dependency_graph();
+///
+/// assert_eq!(graph.blockers_of(2), [0, 1]);
+/// assert_eq!(graph.dependents_of(0), [2]);
+/// assert!(graph.is_blocked(2));
+/// assert!(!graph.is_blocked(0));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DependencyGraph {
+	/// The indices of the tasks each task depends on.
+	blockers: Vec<Vec<usize>>,
+
+	/// The indices of the tasks depending on each task.
+	dependents: Vec<Vec<usize>>,
+
+	/// Whether each task is done.
+	done: Vec<bool>,
+}
+
+impl DependencyGraph {
+	/// Builds the dependency graph of `tasks`.
+	pub fn new(tasks: &[Task]) -> Self {
+		let mut ids: HashMap<&str, Vec<usize>> = HashMap::new();
+
+		for (idx, task) in tasks.iter().enumerate() {
+			for (key, value) in task.description.custom() {
+				if key == ID_TAG {
+					ids.entry(value).or_default().push(idx);
+				}
+			}
+		}
+
+		let mut blockers = vec![Vec::new(); tasks.len()];
+		let mut dependents = vec![Vec::new(); tasks.len()];
+
+		for (idx, task) in tasks.iter().enumerate() {
+			let references = task.description.custom().filter(|(key, _)| {
+				*key == DEPENDENCY_TAG || *key == PARENT_TAG
+			});
+
+			for (_, id) in references {
+				for &blocker in ids.get(id).into_iter().flatten() {
+					blockers[idx].push(blocker);
+					dependents[blocker].push(idx);
+				}
+			}
+		}
+
+		for edges in blockers.iter_mut().chain(dependents.iter_mut()) {
+			edges.sort_unstable();
+			edges.dedup();
+		}
+
+		let done = tasks.iter().map(|task| task.state.is_done()).collect();
+
+		Self { blockers, dependents, done }
+	}
+
+	/// Returns the number of tasks in the graph.
+	pub const fn len(&self) -> usize {
+		self.done.len()
+	}
+
+	/// Returns `true` if the graph contains no tasks.
+	pub const fn is_empty(&self) -> bool {
+		self.done.is_empty()
+	}
+
+	/// Returns the indices of all tasks the task at `idx` depends on, in
+	/// ascending order.
+	///
+	/// Returns an empty slice if `idx` is out of bounds.
+	pub fn blockers_of(&self, idx: usize) -> &[usize] {
+		self.blockers.get(idx).map_or(&[], Vec::as_slice)
+	}
+
+	/// Returns the indices of all tasks depending on the task at `idx`, in
+	/// ascending order.
+	///
+	/// Returns an empty slice if `idx` is out of bounds.
+	pub fn dependents_of(&self, idx: usize) -> &[usize] {
+		self.dependents.get(idx).map_or(&[], Vec::as_slice)
+	}
+
+	/// Returns `true` if the task at `idx` depends on any open task.
+	pub fn is_blocked(&self, idx: usize) -> bool {
+		self.blockers_of(idx).iter().any(|&blocker| !self.done[blocker])
+	}
+
+	/// Returns an iterator over the indices of all tasks in dependency
+	/// order (see [`TopologicalOrder`]).
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::str::FromStr;
+	///
+	/// use tdtxt::TodoFile;
+	///
+	/// let todo = TodoFile::from_str(
+	///     "Paint fence dep:1\n\
+	///      Buy paint id:1\n\
+	///      Clean brushes dep:2\n\
+	///      Admire fence id:2 dep:1\n",
+	/// )
+	/// .unwrap();
+	///
+	/// let order = todo
+	///     .dependency_graph()
+	///     .topological_order()
+	///     .collect::<Result<Vec<_>, _>>()
+	///     .unwrap();
+	/// assert_eq!(order, [1, 0, 3, 2]);
+	///
+	/// let todo = TodoFile::from_str("Chicken id:1 dep:2\nEgg id:2 dep:1\n")
+	///     .unwrap();
+	///
+	/// let cycle = todo
+	///     .dependency_graph()
+	///     .topological_order()
+	///     .collect::<Result<Vec<_>, _>>()
+	///     .unwrap_err();
+	/// assert_eq!(cycle.tasks(), [0, 1]);
+	/// ```
+	pub fn topological_order(&self) -> TopologicalOrder<'_> {
+		let pending = self.blockers.iter().map(Vec::len).collect::<Vec<_>>();
+		let ready = pending
+			.iter()
+			.enumerate()
+			.filter(|(_, &pending)| pending == 0)
+			.map(|(idx, _)| Reverse(idx))
+			.collect();
+
+		TopologicalOrder { graph: self, pending, ready, remaining: self.len() }
+	}
+}
+
+/// An iterator over the indices of tasks in dependency order, i.e. every task
+/// is yielded after all tasks it depends on.
+///
+/// Independent tasks are yielded in ascending order of their indices. If the
+/// remaining tasks contain a cycle, a [`DependencyCycle`] is yielded and the
+/// iteration ends.
+///
+/// It is returned by [`DependencyGraph::topological_order`].
+#[derive(Debug, Clone)]
+pub struct TopologicalOrder<'a> {
+	graph: &'a DependencyGraph,
+
+	/// The number of not yet yielded blockers of every task.
+	pending: Vec<usize>,
+
+	/// Tasks without pending blockers (reversed for a min-heap).
+	ready: BinaryHeap<Reverse<usize>>,
+
+	/// The number of not yet yielded tasks.
+	remaining: usize,
+}
+
+impl Iterator for TopologicalOrder<'_> {
+	type Item = Result<usize, DependencyCycle>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if let Some(Reverse(idx)) = self.ready.pop() {
+			self.remaining -= 1;
+
+			for &dependent in self.graph.dependents_of(idx) {
+				self.pending[dependent] -= 1;
+
+				if self.pending[dependent] == 0 {
+					self.ready.push(Reverse(dependent));
+				}
+			}
+
+			return Some(Ok(idx));
+		}
+
+		if self.remaining == 0 {
+			return None;
+		}
+
+		self.remaining = 0;
+
+		Some(Err(self.find_cycle()))
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(self.remaining.min(1), Some(self.remaining))
+	}
+}
+
+impl TopologicalOrder<'_> {
+	/// Finds a cycle within the tasks which could not be yielded.
+	fn find_cycle(&self) -> DependencyCycle {
+		let start = self
+			.pending
+			.iter()
+			.position(|&pending| pending > 0)
+			.expect("a task with pending blockers remains");
+
+		// Every remaining task has a remaining blocker, so following them
+		// must eventually revisit a task.
+		let mut path = vec![start];
+		let mut current = start;
+
+		loop {
+			current = *self
+				.graph
+				.blockers_of(current)
+				.iter()
+				.find(|&&blocker| self.pending[blocker] > 0)
+				.expect("a remaining task has a remaining blocker");
+
+			if let Some(pos) = path.iter().position(|&idx| idx == current) {
+				return DependencyCycle { tasks: path.split_off(pos) };
+			}
+
+			path.push(current);
+		}
+	}
+}
+
+/// This struct represents a cycle of dependencies between tasks, which is
+/// yielded by [`TopologicalOrder`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DependencyCycle {
+	tasks: Vec<usize>,
+}
+
+impl DependencyCycle {
+	/// Returns the indices of the tasks forming the cycle, each task depends
+	/// on the next one and the last task depends on the first one.
+	pub fn tasks(&self) -> &[usize] {
+		&self.tasks
+	}
+}
+
+impl fmt::Display for DependencyCycle {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("dependency cycle between the tasks ")?;
+
+		for (i, idx) in self.tasks.iter().enumerate() {
+			if i > 0 {
+				f.write_str(", ")?;
+			}
+
+			write!(f, "{}", idx)?;
+		}
+
+		Ok(())
+	}
+}
+
+impl std::error::Error for DependencyCycle {}
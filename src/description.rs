@@ -1,62 +1,94 @@
 use std::fmt;
-use std::ops::{Deref, Index};
+use std::iter::FromIterator;
+use std::ops::{Deref, Index, Range};
+use std::sync::OnceLock;
 
+use smallvec::SmallVec;
+
+use crate::case::eq_ignore_case;
+#[cfg(feature = "unicode-normalization")]
+use crate::normalization::{eq_normalized, NormalizationForm};
 use crate::parse::{Cursor, Parse, Parser};
+use crate::project::{is_under, ProjectPath};
 use crate::span::{BytePos, ByteSpan};
+use crate::tag::{DuplicateTagError, DuplicateTagPolicy, KnownTag, TagValue};
+use crate::task::SourceLine;
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// The location of a project (e.g. `+Family`) within the text of a
+/// [`Description`].
+///
+/// It is returned by [`Description::project_ranges`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct ProjectRange {
 	full: ByteSpan,
 	name: ByteSpan,
 }
 
 impl ProjectRange {
-	pub const fn new(full: ByteSpan) -> Self {
-		let name = full.offset_low(1);
+	pub(crate) const fn new(full: ByteSpan) -> Self {
+		// Skip the leading `+` or `@`
+		let name = full.with_low(BytePos::from_u32(full.low().as_u32() + 1));
 
 		Self { full, name }
 	}
 
+	/// Returns the span of the whole project (e.g. `+Family`).
 	pub const fn full(&self) -> &ByteSpan {
 		&self.full
 	}
 
+	/// Returns the span of the name of the project (e.g. `Family`).
 	pub const fn project(&self) -> &ByteSpan {
 		&self.name
 	}
 
-	pub fn index<'a, 'b>(&'a self, s: &'b str) -> &'b str {
+	/// Returns the name of the project within `s`, which must be the text
+	/// of the description the range was taken from.
+	pub(crate) fn index<'b>(&self, s: &'b str) -> &'b str {
 		Index::index(s, self.name)
 	}
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// The location of a context (e.g. `@phone`) within the text of a
+/// [`Description`].
+///
+/// It is returned by [`Description::context_ranges`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct ContextRange {
 	full: ByteSpan,
 	name: ByteSpan,
 }
 
 impl ContextRange {
-	pub const fn new(full: ByteSpan) -> Self {
-		let name = full.offset_low(1);
+	pub(crate) const fn new(full: ByteSpan) -> Self {
+		// Skip the leading `+` or `@`
+		let name = full.with_low(BytePos::from_u32(full.low().as_u32() + 1));
 
 		Self { full, name }
 	}
 
+	/// Returns the span of the whole context (e.g. `@phone`).
 	pub const fn full(&self) -> &ByteSpan {
 		&self.full
 	}
 
+	/// Returns the span of the name of the context (e.g. `phone`).
 	pub const fn context(&self) -> &ByteSpan {
 		&self.name
 	}
 
-	pub fn index<'a, 'b>(&'a self, s: &'b str) -> &'b str {
+	/// Returns the name of the context within `s`, which must be the text
+	/// of the description the range was taken from.
+	pub(crate) fn index<'b>(&self, s: &'b str) -> &'b str {
 		Index::index(s, self.name)
 	}
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// The location of a custom tag (e.g. `due:2021-08-01`) within the text of
+/// a [`Description`].
+///
+/// It is returned by [`Description::custom_ranges`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct CustomRange {
 	full: ByteSpan,
 	key: ByteSpan,
@@ -65,67 +97,302 @@ pub struct CustomRange {
 }
 
 impl CustomRange {
-	pub fn new(key: ByteSpan, separator: ByteSpan, value: ByteSpan) -> Self {
+	pub(crate) fn new(
+		key: ByteSpan,
+		separator: ByteSpan,
+		value: ByteSpan,
+	) -> Self {
 		let full = key.union(&value);
 
 		Self { full, key, separator, value }
 	}
 
+	/// Returns the span of the whole tag (e.g. `due:2021-08-01`).
 	pub const fn full(&self) -> &ByteSpan {
 		&self.full
 	}
 
+	/// Returns the span of the key of the tag (e.g. `due`).
 	pub const fn key(&self) -> &ByteSpan {
 		&self.key
 	}
 
+	/// Returns the span of the separator of the tag (`:`).
 	pub const fn separator(&self) -> &ByteSpan {
 		&self.separator
 	}
 
+	/// Returns the span of the value of the tag (e.g. `2021-08-01`).
 	pub const fn value(&self) -> &ByteSpan {
 		&self.value
 	}
 
-	pub fn index<'a, 'b>(&'a self, s: &'b str) -> (&'b str, &'b str) {
+	/// Returns the key and value of the tag within `s`, which must be the
+	/// text of the description the range was taken from.
+	pub(crate) fn index<'b>(&self, s: &'b str) -> (&'b str, &'b str) {
 		(Index::index(s, self.key), Index::index(s, self.value))
 	}
 }
 
+/// Options controlling how the components of a [`Description`] are located.
+///
+/// Parsed tasks use them through
+/// [`DecodeOptions::description_options`](`crate::DecodeOptions::description_options`)
+/// or [`TaskRef::parse_with_options`](`crate::TaskRef::parse_with_options`).
+///
+/// # Defaults
+///
+/// Custom tags follow the todo.txt format strictly, i.e. neither their key
+/// nor their value may contain a colon.
+#[derive(
+	Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default,
+)]
+pub struct DescriptionOptions {
+	/// Allow colons within the values of custom tags, e.g. for URLs like
+	/// `link:https://example.com`. Everything after the first colon of a word
+	/// is the value.
+	pub colons_in_values: bool,
+}
+
+impl DescriptionOptions {
+	/// Creates the default options.
+	pub const fn new() -> Self {
+		Self { colons_in_values: false }
+	}
+
+	/// Sets whether colons are allowed within the values of custom tags.
+	pub const fn colons_in_values(mut self, colons_in_values: bool) -> Self {
+		self.colons_in_values = colons_in_values;
+		self
+	}
+}
+
 /// Represents the description part of a [`Task`](`crate::Task`).
 ///
 /// Components like projects, contexts and custom tags are all implemented as
 /// byte range indices into the raw description text. This is done to avoid
 /// unnecessary allocations which in turn reduces the memory footprint.
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// The components are located lazily on first access, so descriptions which
+/// are only formatted or compared never pay for it.
+#[derive(Debug, Clone)]
 pub struct Description {
 	/// The whole text of the description.
 	raw: String,
 
-	/// Byte indices into [`Self::raw`] representing projects (e.g. `+project`);
-	projects: Vec<ProjectRange>,
+	/// The options used to locate the components.
+	options: DescriptionOptions,
+
+	/// The located components of [`Self::raw`].
+	index: OnceLock<ComponentIndex>,
+
+	/// The line of the task the description was parsed from, if it was not
+	/// formatted canonically.
+	source: Option<Box<SourceLine>>,
+}
+
+/// The located components of a [`Description`].
+///
+/// Descriptions without components (e.g. `Buy milk`) do not allocate. The
+/// ranges of all other descriptions share a single allocation as long as
+/// they only have a few components of each kind.
+#[derive(Debug, Clone, Default)]
+pub struct ComponentIndex {
+	/// The located ranges, `None` if the text has no components.
+	ranges: Option<Box<ComponentRanges>>,
+}
+
+/// The ranges of the components of a [`Description`].
+#[derive(Debug, Clone, Default)]
+struct ComponentRanges {
+	/// Byte indices into the text representing projects (e.g. `+project`);
+	projects: SmallVec<[ProjectRange; 2]>,
 
-	/// Byte indices into [`Self::raw`] representing contexts (e.g. `@context`);
-	contexts: Vec<ContextRange>,
+	/// Byte indices into the text representing contexts (e.g. `@context`);
+	contexts: SmallVec<[ContextRange; 2]>,
 
-	/// Byte indices into [`Self::raw`] representing custom tags (e.g.
+	/// Byte indices into the text representing custom tags (e.g.
 	/// `key:value`);
-	custom: Vec<CustomRange>,
+	custom: SmallVec<[CustomRange; 1]>,
+}
+
+impl ComponentIndex {
+	/// Locates all components of `raw`.
+	pub(crate) fn new(raw: &str, options: DescriptionOptions) -> Self {
+		let mut index = Self::default();
+		Description::index(raw, 0, options, &mut index);
+
+		index
+	}
+
+	/// Returns the locations of all projects.
+	pub(crate) fn projects(&self) -> &[ProjectRange] {
+		self.ranges.as_ref().map_or(&[], |ranges| &ranges.projects)
+	}
+
+	/// Returns the locations of all contexts.
+	pub(crate) fn contexts(&self) -> &[ContextRange] {
+		self.ranges.as_ref().map_or(&[], |ranges| &ranges.contexts)
+	}
+
+	/// Returns the locations of all custom tags.
+	pub(crate) fn custom(&self) -> &[CustomRange] {
+		self.ranges.as_ref().map_or(&[], |ranges| &ranges.custom)
+	}
+
+	/// Returns the ranges for adding components, allocating them if
+	/// necessary.
+	fn ranges_mut(&mut self) -> &mut ComponentRanges {
+		self.ranges.get_or_insert_with(Box::default)
+	}
+
+	/// Locates the components of `raw` again, starting at the word boundary
+	/// `start`. Components before `start` are kept.
+	fn reindex_from(
+		&mut self,
+		raw: &str,
+		start: usize,
+		options: DescriptionOptions,
+	) {
+		let start_pos = BytePos::from_usize(start);
+
+		if let Some(ranges) = &mut self.ranges {
+			ranges.projects.retain(|range| *range.full().low() < start_pos);
+			ranges.contexts.retain(|range| *range.full().low() < start_pos);
+			ranges.custom.retain(|range| *range.full().low() < start_pos);
+		}
+
+		Description::index(raw, start, options, self);
+	}
 }
 
 impl Description {
 	/// Creates a new description from `s`.
 	///
-	/// During this process all projects, contexts and custom tags will be
-	/// located.
+	/// All projects, contexts and custom tags are located on first access.
 	pub fn new<S>(s: S) -> Self
 	where
 		S: Into<String>,
 	{
-		let raw: String = s.into();
-		let (projects, contexts, custom) = Self::index(&raw);
+		Self::with_options(s, DescriptionOptions::default())
+	}
 
-		Self { raw, projects, contexts, custom }
+	/// Creates a new description from `s` and locates its components
+	/// according to `options`.
+	///
+	/// The options are kept when the description is modified.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::{Description, DescriptionOptions};
+	///
+	/// let text = "Read article link:https://example.com";
+	///
+	/// assert_eq!(Description::new(text).tag("link"), None);
+	///
+	/// let options = DescriptionOptions::new().colons_in_values(true);
+	/// let description = Description::with_options(text, options);
+	///
+	/// assert_eq!(description.tag("link"), Some("https://example.com"));
+	/// ```
+	pub fn with_options<S>(s: S, options: DescriptionOptions) -> Self
+	where
+		S: Into<String>,
+	{
+		Self { raw: s.into(), options, index: OnceLock::new(), source: None }
+	}
+
+	/// Creates a description from its parts, e.g. to keep already located
+	/// components.
+	pub(crate) const fn from_parts(
+		raw: String,
+		options: DescriptionOptions,
+		index: OnceLock<ComponentIndex>,
+	) -> Self {
+		Self { raw, options, index, source: None }
+	}
+
+	/// Returns the cell holding the located components.
+	pub(crate) const fn index_cell(&self) -> &OnceLock<ComponentIndex> {
+		&self.index
+	}
+
+	/// Returns the located components, locating them if necessary.
+	pub(crate) fn component_index(&self) -> &ComponentIndex {
+		self.index.get_or_init(|| ComponentIndex::new(&self.raw, self.options))
+	}
+
+	/// Returns the options used to locate the components.
+	pub const fn options(&self) -> DescriptionOptions {
+		self.options
+	}
+
+	/// Locates all components again according to `options`.
+	pub fn set_options(&mut self, options: DescriptionOptions) {
+		if options != self.options {
+			let raw = std::mem::take(&mut self.raw);
+			self.reindex(raw, options);
+		}
+	}
+
+	/// Replaces the text of the description by `raw` and locates all its
+	/// components.
+	fn reindex(&mut self, raw: String, options: DescriptionOptions) {
+		self.raw = raw;
+		self.options = options;
+		self.index = OnceLock::new();
+	}
+
+	/// Returns the line of the task the description was parsed from.
+	pub(crate) fn source(&self) -> Option<&SourceLine> {
+		self.source.as_deref()
+	}
+
+	/// Sets the line of the task the description was parsed from.
+	pub(crate) fn set_source(&mut self, source: Option<SourceLine>) {
+		self.source = source.map(Box::new);
+	}
+
+	/// Creates a new description from `components`, e.g. a transformed
+	/// stream of [`Self::components`].
+	///
+	/// The components are separated by a single space. Leading and trailing
+	/// whitespace of text components is dropped while whitespace within text
+	/// is kept. Empty and whitespace-only text components are skipped.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::{Component, Description};
+	///
+	/// let description = Description::new("Call Mom @phone +Family @home");
+	///
+	/// let without_phone = Description::from_components(
+	///     description
+	///         .components()
+	///         .filter(|component| *component != Component::Context("@phone")),
+	/// );
+	///
+	/// assert_eq!(without_phone.description(), "Call Mom +Family @home");
+	/// assert_eq!(without_phone.contexts().collect::<Vec<_>>(), ["home"]);
+	/// ```
+	pub fn from_components<'a, I>(components: I) -> Self
+	where
+		I: IntoIterator<Item = Component<'a>>,
+	{
+		let mut raw = String::new();
+
+		for component in components {
+			push_component(&mut raw, component);
+		}
+
+		Self::new(raw)
+	}
+
+	/// Creates a new builder for a description.
+	pub fn builder() -> DescriptionBuilder {
+		DescriptionBuilder::default()
 	}
 
 	/// Returns the text of the whole description.
@@ -135,17 +402,531 @@ impl Description {
 
 	/// Returns an iterator of all projects found within the description.
 	pub fn projects(&self) -> ProjectIter<'_> {
-		ProjectIter::new(self)
+		ProjectIter::new(&self.raw, self.project_ranges())
 	}
 
 	/// Returns an iterator of all contexts found within the description.
 	pub fn contexts(&self) -> ContextIter<'_> {
-		ContextIter::new(self)
+		ContextIter::new(&self.raw, self.context_ranges())
+	}
+
+	/// Returns an iterator of all distinct projects found within the
+	/// description, in the order of their first occurrence.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::Description;
+	///
+	/// let description = Description::new("+b +a @x +b @y @x +a");
+	///
+	/// assert_eq!(description.projects_unique().collect::<Vec<_>>(), ["b", "a"]);
+	/// assert_eq!(description.contexts_unique().collect::<Vec<_>>(), ["x", "y"]);
+	/// ```
+	pub fn projects_unique(&self) -> impl Iterator<Item = &str> + '_ {
+		self.project_ranges().iter().enumerate().filter_map(
+			move |(idx, range)| {
+				let name = range.index(&self.raw);
+
+				self.project_ranges()[..idx]
+					.iter()
+					.all(|other| other.index(&self.raw) != name)
+					.then_some(name)
+			},
+		)
+	}
+
+	/// Returns an iterator of all distinct contexts found within the
+	/// description, in the order of their first occurrence.
+	pub fn contexts_unique(&self) -> impl Iterator<Item = &str> + '_ {
+		self.context_ranges().iter().enumerate().filter_map(
+			move |(idx, range)| {
+				let name = range.index(&self.raw);
+
+				self.context_ranges()[..idx]
+					.iter()
+					.all(|other| other.index(&self.raw) != name)
+					.then_some(name)
+			},
+		)
 	}
 
 	/// Returns an iterator of all custom tags found within the description.
 	pub fn custom(&self) -> CustomIter<'_> {
-		CustomIter::new(self)
+		CustomIter::new(&self.raw, self.custom_ranges())
+	}
+
+	/// Returns `true` if the description contains the project `project`
+	/// (with or without the leading `+`).
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::Description;
+	///
+	/// let description = Description::new("Call Mom +Family @phone due:x");
+	///
+	/// assert!(description.has_project("Family"));
+	/// assert!(description.has_project("+Family"));
+	/// assert!(description.has_context("@phone"));
+	/// assert!(description.has_tag("due"));
+	/// assert!(!description.has_tag("Family"));
+	/// ```
+	pub fn has_project(&self, project: &str) -> bool {
+		let project = project.strip_prefix('+').unwrap_or(project);
+
+		self.project_ranges()
+			.iter()
+			.any(|range| range.index(&self.raw) == project)
+	}
+
+	/// Returns `true` if the description contains the context `context`
+	/// (with or without the leading `@`).
+	pub fn has_context(&self, context: &str) -> bool {
+		let context = context.strip_prefix('@').unwrap_or(context);
+
+		self.context_ranges()
+			.iter()
+			.any(|range| range.index(&self.raw) == context)
+	}
+
+	/// Returns `true` if the description contains the project `project`
+	/// (with or without the leading `+`) or any project nested below it,
+	/// e.g. `+Work/Reports` for `Work` (see [`ProjectPath`]).
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::Description;
+	///
+	/// let description = Description::new("Write summary +Work/Reports/Q3");
+	///
+	/// assert!(description.has_project_under("+Work"));
+	/// assert!(description.has_project_under("Work/Reports"));
+	/// assert!(!description.has_project_under("Work/Meetings"));
+	/// assert!(!description.has_project("Work"));
+	/// ```
+	pub fn has_project_under(&self, project: &str) -> bool {
+		let project = project.strip_prefix('+').unwrap_or(project);
+
+		self.projects().any(|name| is_under(name, project))
+	}
+
+	/// Returns an iterator over the projects of the description as
+	/// hierarchical paths (see [`ProjectPath`]), in the order they appear.
+	pub fn project_paths(&self) -> impl Iterator<Item = ProjectPath> + '_ {
+		self.projects().map(ProjectPath::new)
+	}
+
+	/// Like [`Self::has_project`] but ignores the case of the project name
+	/// (see [`eq_ignore_case`](`crate::eq_ignore_case`)).
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::Description;
+	///
+	/// let description = Description::new("Post signs +garagesale @Phone");
+	///
+	/// assert!(description.has_project_ignore_case("+GarageSale"));
+	/// assert!(description.has_context_ignore_case("phone"));
+	/// ```
+	pub fn has_project_ignore_case(&self, project: &str) -> bool {
+		let project = project.strip_prefix('+').unwrap_or(project);
+
+		self.projects().any(|name| eq_ignore_case(name, project))
+	}
+
+	/// Like [`Self::has_context`] but ignores the case of the context name
+	/// (see [`eq_ignore_case`](`crate::eq_ignore_case`)).
+	pub fn has_context_ignore_case(&self, context: &str) -> bool {
+		let context = context.strip_prefix('@').unwrap_or(context);
+
+		self.contexts().any(|name| eq_ignore_case(name, context))
+	}
+
+	/// Like [`Self::has_project`] but compares the project names after
+	/// normalizing them according to `form` (see
+	/// [`eq_normalized`](`crate::eq_normalized`)).
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::{Description, NormalizationForm};
+	///
+	/// // The `é` is written as `e` followed by a combining accent
+	/// let description = Description::new("Order beans +cafe\u{301} @cafe\u{301}");
+	///
+	/// assert!(!description.has_project("+caf\u{e9}"));
+	/// assert!(description.has_project_normalized("+caf\u{e9}", NormalizationForm::Nfc));
+	/// assert!(description.has_context_normalized("caf\u{e9}", NormalizationForm::Nfc));
+	/// ```
+	#[cfg(feature = "unicode-normalization")]
+	pub fn has_project_normalized(
+		&self,
+		project: &str,
+		form: NormalizationForm,
+	) -> bool {
+		let project = project.strip_prefix('+').unwrap_or(project);
+
+		self.projects().any(|name| eq_normalized(name, project, form))
+	}
+
+	/// Like [`Self::has_context`] but compares the context names after
+	/// normalizing them according to `form` (see
+	/// [`eq_normalized`](`crate::eq_normalized`)).
+	#[cfg(feature = "unicode-normalization")]
+	pub fn has_context_normalized(
+		&self,
+		context: &str,
+		form: NormalizationForm,
+	) -> bool {
+		let context = context.strip_prefix('@').unwrap_or(context);
+
+		self.contexts().any(|name| eq_normalized(name, context, form))
+	}
+
+	/// Like [`Self::tag`] but compares the keys after normalizing them
+	/// according to `form` (see [`eq_normalized`](`crate::eq_normalized`)).
+	#[cfg(feature = "unicode-normalization")]
+	pub fn tag_normalized(
+		&self,
+		key: &str,
+		form: NormalizationForm,
+	) -> Option<&str> {
+		self.custom()
+			.find(|(k, _)| eq_normalized(k, key, form))
+			.map(|(_, value)| value)
+	}
+
+	/// Returns `true` if the description contains a custom tag with the key
+	/// `key`.
+	pub fn has_tag(&self, key: &str) -> bool {
+		self.tag(key).is_some()
+	}
+
+	/// Returns the value of the first custom tag with the key `key`.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::Description;
+	///
+	/// let description = Description::new("Pay rent due:2024-06-01 due:x");
+	///
+	/// assert_eq!(description.tag("due"), Some("2024-06-01"));
+	/// assert_eq!(description.tag("t"), None);
+	/// assert_eq!(description.tags("due").collect::<Vec<_>>(), ["2024-06-01", "x"]);
+	/// ```
+	pub fn tag(&self, key: &str) -> Option<&str> {
+		self.custom().find(|(k, _)| *k == key).map(|(_, value)| value)
+	}
+
+	/// Returns the value of the custom tag with the key `key`, selected
+	/// according to `policy` if the tag appears multiple times.
+	///
+	/// # Errors
+	///
+	/// Returns an error if the tag appears multiple times and `policy` is
+	/// [`DuplicateTagPolicy::Error`].
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::{Description, DuplicateTagPolicy};
+	///
+	/// let description = Description::new("Release dep:1 dep:4");
+	///
+	/// assert_eq!(description.tag_with("dep", DuplicateTagPolicy::Last), Ok(Some("4")));
+	/// assert_eq!(description.tag_with("due", DuplicateTagPolicy::Error), Ok(None));
+	/// ```
+	pub fn tag_with(
+		&self,
+		key: &str,
+		policy: DuplicateTagPolicy,
+	) -> Result<Option<&str>, DuplicateTagError> {
+		let values =
+			self.custom().filter(|(k, _)| *k == key).map(|(_, value)| value);
+
+		policy.resolve(key, values)
+	}
+
+	/// Returns the value of the first custom tag with the key `key`,
+	/// interpreted as the most specific matching type (see [`TagValue`]).
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::time::Duration;
+	///
+	/// use tdtxt::{Date, Description, TagValue};
+	///
+	/// let description = Description::new("Write report due:2021-08-01 estimate:2h");
+	///
+	/// assert_eq!(
+	///     description.typed_tag("due").and_then(|value| value.as_date()),
+	///     Some(Date::from_ymd(2021, 8, 1))
+	/// );
+	/// assert_eq!(
+	///     description.typed_tag("estimate"),
+	///     Some(TagValue::Duration(Duration::from_secs(2 * 60 * 60)))
+	/// );
+	/// assert_eq!(description.typed_tag("t"), None);
+	/// ```
+	pub fn typed_tag(&self, key: &str) -> Option<TagValue<'_>> {
+		self.tag(key).map(TagValue::parse)
+	}
+
+	/// Returns an iterator over all well-known custom tags (see
+	/// [`KnownTag`]) with a valid value, in the order they appear.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::{Date, Description, KnownTag};
+	///
+	/// let description = Description::new("Pay rent due:2021-08-01 due:soon rec:+1m id:7 h:1 x:y");
+	///
+	/// let tags = description.known_tags().collect::<Vec<_>>();
+	///
+	/// assert_eq!(tags.len(), 4);
+	/// assert_eq!(tags[0], KnownTag::Due(Date::from_ymd(2021, 8, 1)));
+	/// assert_eq!(tags[2], KnownTag::Id("7"));
+	/// assert_eq!(tags[3], KnownTag::Hidden(true));
+	/// ```
+	pub fn known_tags(&self) -> impl Iterator<Item = KnownTag<'_>> + '_ {
+		self.custom().filter_map(|(key, value)| KnownTag::parse(key, value))
+	}
+
+	/// Returns an iterator over the values of all custom tags with the key
+	/// `key`, in the order they appear.
+	pub fn tags<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a str> {
+		self.custom().filter(move |(k, _)| *k == key).map(|(_, value)| value)
+	}
+
+	/// Returns an iterator over the spans of all non-overlapping matches of
+	/// `regex` within the text of the description.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use regex::Regex;
+	/// use tdtxt::Description;
+	///
+	/// let description = Description::new("Call Mom and Dad +Family");
+	/// let regex = Regex::new("Mom|Dad").unwrap();
+	///
+	/// let matches = description
+	///     .find_regex(&regex)
+	///     .map(|span| &description[span.to_range_usize()])
+	///     .collect::<Vec<_>>();
+	///
+	/// assert_eq!(matches, ["Mom", "Dad"]);
+	/// ```
+	#[cfg(feature = "regex")]
+	pub fn find_regex<'a>(
+		&'a self,
+		regex: &'a regex::Regex,
+	) -> impl Iterator<Item = ByteSpan> + 'a {
+		regex
+			.find_iter(&self.raw)
+			.map(|m| ByteSpan::from_usize_range(m.start()..m.end()))
+	}
+
+	/// Returns an iterator over all custom tags grouped by their key, e.g.
+	/// for tags like `dep:` which may appear multiple times.
+	///
+	/// The keys are yielded in the order of their first appearance, the
+	/// values of each key in the order they appear.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::Description;
+	///
+	/// let description = Description::new("Release dep:1 due:2021-08-01 dep:4");
+	///
+	/// let grouped = description
+	///     .custom_grouped()
+	///     .map(|(key, values)| (key, values.collect::<Vec<_>>()))
+	///     .collect::<Vec<_>>();
+	///
+	/// assert_eq!(
+	///     grouped,
+	///     vec![("dep", vec!["1", "4"]), ("due", vec!["2021-08-01"])]
+	/// );
+	/// ```
+	pub fn custom_grouped(&self) -> CustomGrouped<'_> {
+		CustomGrouped::new(&self.raw, self.custom_ranges())
+	}
+
+	/// Returns the locations of all projects within the text of the
+	/// description, in the order they appear.
+	pub fn project_ranges(&self) -> &[ProjectRange] {
+		self.component_index().projects()
+	}
+
+	/// Returns the locations of all contexts within the text of the
+	/// description, in the order they appear.
+	pub fn context_ranges(&self) -> &[ContextRange] {
+		self.component_index().contexts()
+	}
+
+	/// Returns the locations of all custom tags within the text of the
+	/// description, in the order they appear.
+	pub fn custom_ranges(&self) -> &[CustomRange] {
+		self.component_index().custom()
+	}
+
+	/// Returns an iterator over the span of every whole project (e.g.
+	/// `+Family`) together with its name (e.g. `Family`).
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::Description;
+	///
+	/// let description = Description::new("Call Mom +Family @phone due:x");
+	/// let text = description.description();
+	///
+	/// let (span, name) = description.project_spans().next().unwrap();
+	/// assert_eq!(name, "Family");
+	/// assert_eq!(&text[span.to_range_usize()], "+Family");
+	///
+	/// let (span, name) = description.context_spans().next().unwrap();
+	/// assert_eq!((span.to_range_usize(), name), (17..23, "phone"));
+	///
+	/// let (span, tag) = description.custom_spans().next().unwrap();
+	/// assert_eq!((span.to_range_usize(), tag), (24..29, ("due", "x")));
+	/// ```
+	pub fn project_spans(
+		&self,
+	) -> impl Iterator<Item = (ByteSpan, &str)> + '_ {
+		self.project_ranges()
+			.iter()
+			.map(move |range| (*range.full(), range.index(&self.raw)))
+	}
+
+	/// Returns an iterator over the span of every whole context (e.g.
+	/// `@phone`) together with its name (e.g. `phone`).
+	pub fn context_spans(
+		&self,
+	) -> impl Iterator<Item = (ByteSpan, &str)> + '_ {
+		self.context_ranges()
+			.iter()
+			.map(move |range| (*range.full(), range.index(&self.raw)))
+	}
+
+	/// Returns an iterator over the span of every whole custom tag (e.g.
+	/// `due:2021-08-01`) together with its key and value.
+	pub fn custom_spans(
+		&self,
+	) -> impl Iterator<Item = (ByteSpan, (&str, &str))> + '_ {
+		self.custom_ranges()
+			.iter()
+			.map(move |range| (*range.full(), range.index(&self.raw)))
+	}
+
+	/// Returns `true` if both descriptions have the same free text (see
+	/// [`Self::plain_words`]) and the same sets of projects, contexts and
+	/// custom tags, regardless of the order of the components and of
+	/// redundant whitespace.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::Description;
+	///
+	/// let a = Description::new("Call Mom +Family @phone due:2021-08-01");
+	/// let b = Description::new("Call  due:2021-08-01 Mom @phone +Family +Family");
+	///
+	/// assert_ne!(a, b);
+	/// assert!(a.eq_semantic(&b));
+	/// assert!(!a.eq_semantic(&Description::new("Mom Call +Family @phone due:2021-08-01")));
+	/// ```
+	pub fn eq_semantic(&self, other: &Self) -> bool {
+		fn set<T: Ord>(iter: impl Iterator<Item = T>) -> Vec<T> {
+			let mut items = iter.collect::<Vec<_>>();
+			items.sort_unstable();
+			items.dedup();
+			items
+		}
+
+		self.plain_words().eq(other.plain_words())
+			&& set(self.projects()) == set(other.projects())
+			&& set(self.contexts()) == set(other.contexts())
+			&& set(self.custom()) == set(other.custom())
+	}
+
+	/// Returns the free text of the description without projects, contexts
+	/// and custom tags. Whitespace is collapsed to single spaces.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::Description;
+	///
+	/// let description =
+	///     Description::new("Call  Mom +Family @phone about   due:2021-08-01 dinner");
+	///
+	/// assert_eq!(description.plain_text(), "Call Mom about dinner");
+	/// ```
+	pub fn plain_text(&self) -> String {
+		self.plain_words().collect::<Vec<_>>().join(" ")
+	}
+
+	/// Returns an iterator over the words of [`Self::plain_text`], e.g. for
+	/// building a full-text search index without allocating.
+	pub fn plain_words(&self) -> impl Iterator<Item = &str> + '_ {
+		self.components()
+			.filter_map(|component| match component {
+				Component::Text(text) => Some(text),
+				_ => None,
+			})
+			.flat_map(str::split_whitespace)
+	}
+
+	/// Returns an iterator over all words of the description together with
+	/// their location within the description.
+	///
+	/// Words are separated by whitespace. Each word is classified as a
+	/// [`Component`], where a [`Component::Text`] is a single word and custom
+	/// tags are never split at their separator. A word which is not (yet) a
+	/// valid component, e.g. a lone `+` while typing a project, is yielded as
+	/// text.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::{Component, Description};
+	///
+	/// let description = Description::new("Call Mom +Fam due:2021-08-01 @");
+	///
+	/// let words = description
+	///     .words()
+	///     .map(|(word, span)| (word, span.to_range_usize()))
+	///     .collect::<Vec<_>>();
+	///
+	/// assert_eq!(
+	///     words,
+	///     vec![
+	///         (Component::Text("Call"), 0..4),
+	///         (Component::Text("Mom"), 5..8),
+	///         (Component::Project("+Fam"), 9..13),
+	///         (
+	///             Component::Custom {
+	///                 key: "due",
+	///                 separator: ":",
+	///                 value: "2021-08-01"
+	///             },
+	///             14..28
+	///         ),
+	///         (Component::Text("@"), 29..30),
+	///     ]
+	/// );
+	/// ```
+	pub fn words(&self) -> Words<'_> {
+		Words::new(self.components_spanned())
 	}
 
 	/// Returns an iterator of all the [`Component`]'s of the description.
@@ -175,7 +956,580 @@ impl Description {
 	/// }));
 	/// ```
 	pub fn components(&self) -> Components<'_> {
-		Components::new(self)
+		Components::new(&self.raw, self.component_index())
+	}
+
+	/// Returns an iterator of all the [`Component`]'s of the description
+	/// together with their location within the description.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::{Component, Description};
+	///
+	/// let description = Description::new("Call Mom +Family");
+	/// let mut components = description.components_spanned();
+	///
+	/// let (component, span) = components.next().unwrap();
+	/// assert_eq!(component, Component::Text("Call Mom "));
+	/// assert_eq!(span.to_range_usize(), 0..9);
+	///
+	/// let (component, span) = components.next().unwrap();
+	/// assert_eq!(component, Component::Project("+Family"));
+	/// assert_eq!(&description[span.to_range_usize()], "+Family");
+	/// ```
+	pub fn components_spanned(&self) -> SpannedComponents<'_> {
+		SpannedComponents::new(self.components())
+	}
+
+	/// Replaces the values of all custom tags with the key `key` by the
+	/// values returned from `f`. Tags for which `f` returns `None` are left
+	/// untouched.
+	///
+	/// Returns `true` if any value was replaced.
+	pub(crate) fn replace_tag_values<F>(&mut self, key: &str, mut f: F) -> bool
+	where
+		F: FnMut(&str) -> Option<String>,
+	{
+		let replacements = self
+			.custom_ranges()
+			.iter()
+			.filter_map(|range| {
+				let (range_key, value) = range.index(&self.raw);
+
+				if range_key == key {
+					f(value).map(|new_value| (*range.value(), new_value))
+				} else {
+					None
+				}
+			})
+			.collect::<Vec<_>>();
+
+		if replacements.is_empty() {
+			return false;
+		}
+
+		let mut raw = std::mem::take(&mut self.raw);
+
+		// Replace from the back, so the remaining spans stay valid
+		for (span, new_value) in replacements.into_iter().rev() {
+			raw.replace_range(span.to_range_usize(), &new_value);
+		}
+
+		self.reindex(raw, self.options);
+
+		true
+	}
+
+	/// Sets the values of all custom tags with the key `key` to `value`.
+	/// Appends the tag `key:value`, separated by a single space, if there is
+	/// no such tag.
+	///
+	/// The tags are rewritten in place, the rest of the description is left
+	/// untouched.
+	///
+	/// # Errors
+	///
+	/// Returns [`BuildDescriptionError::InvalidTag`] and leaves the
+	/// description untouched if `key` or `value` is empty or contains
+	/// whitespace or a `:`, or if `key` starts with `+` or `@`.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::Description;
+	///
+	/// let mut description = Description::new("Pay rent due:2024-05-01 +Home");
+	///
+	/// description.set_tag("due", "2024-06-01")?;
+	/// description.set_tag("t", "2024-05-25")?;
+	/// assert!(description.set_tag("t", "next week").is_err());
+	/// assert_eq!(
+	///     description.description(),
+	///     "Pay rent due:2024-06-01 +Home t:2024-05-25"
+	/// );
+	///
+	/// assert!(description.replace_tag("t", "2024-05-28")?);
+	/// assert!(!description.replace_tag("rec", "1m")?);
+	///
+	/// assert!(description.remove_tag("due"));
+	/// assert_eq!(description.description(), "Pay rent +Home t:2024-05-28");
+	/// # Ok::<(), tdtxt::BuildDescriptionError>(())
+	/// ```
+	pub fn set_tag(
+		&mut self,
+		key: &str,
+		value: &str,
+	) -> Result<(), BuildDescriptionError> {
+		self.validate_tag(key, value)?;
+		self.write_tag(key, value);
+
+		Ok(())
+	}
+
+	/// Sets the values of all custom tags with the key `key` to `value`,
+	/// like [`Self::set_tag`], but does not append a tag if there is none.
+	///
+	/// Returns `Ok(true)` if any tag was changed.
+	///
+	/// # Errors
+	///
+	/// See [`Self::set_tag`].
+	pub fn replace_tag(
+		&mut self,
+		key: &str,
+		value: &str,
+	) -> Result<bool, BuildDescriptionError> {
+		self.validate_tag(key, value)?;
+
+		Ok(self.replace_tag_values(key, |_| Some(value.to_owned())))
+	}
+
+	/// Removes all custom tags with the key `key` together with the
+	/// whitespace separating them from the preceding word. The spacing of the
+	/// rest of the description is kept.
+	///
+	/// Returns `true` if any tag was removed.
+	pub fn remove_tag(&mut self, key: &str) -> bool {
+		self.remove_tags(key, None)
+	}
+
+	/// Appends the tag `key:value` like [`Self::push_word`], even if there
+	/// already is a tag with the key `key`.
+	///
+	/// # Errors
+	///
+	/// See [`Self::set_tag`].
+	pub(crate) fn push_tag(
+		&mut self,
+		key: &str,
+		value: &str,
+	) -> Result<(), BuildDescriptionError> {
+		self.validate_tag(key, value)?;
+		self.push_word(&format!("{}:{}", key, value));
+
+		Ok(())
+	}
+
+	/// Like [`Self::set_tag`] but without validating `key` and `value`.
+	pub(crate) fn write_tag(&mut self, key: &str, value: &str) {
+		if !self.replace_tag_values(key, |_| Some(value.to_owned())) {
+			self.push_word(&format!("{}:{}", key, value));
+		}
+	}
+
+	/// Returns an error if `key:value` would not be located as a custom tag.
+	fn validate_tag(
+		&self,
+		key: &str,
+		value: &str,
+	) -> Result<(), BuildDescriptionError> {
+		if is_valid_tag_key(key) && is_valid_tag_value(value, self.options) {
+			Ok(())
+		} else {
+			Err(BuildDescriptionError::InvalidTag {
+				key: key.to_owned(),
+				value: value.to_owned(),
+			})
+		}
+	}
+
+	/// Replaces the free text of the description with `text` while keeping
+	/// all projects, contexts and custom tags in place.
+	///
+	/// The text is rewritten in place: `text` (without surrounding
+	/// whitespace) takes the place of the first run of words, all other
+	/// words are removed together with the whitespace separating them from
+	/// the preceding component. The components and the whitespace around
+	/// them are left untouched. If the description has no words, `text` is
+	/// put in front of it.
+	///
+	/// Components within `text` are located as usual and components which
+	/// `text` already contains are removed from the rest of the description.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::Description;
+	///
+	/// let mut description =
+	///     Description::new("Call Mom +Family about dinner @phone due:2021-08-01");
+	///
+	/// description.set_text("Call Dad");
+	/// assert_eq!(
+	///     description.description(),
+	///     "Call Dad +Family @phone due:2021-08-01"
+	/// );
+	///
+	/// description.set_text("Book +Family table");
+	/// assert_eq!(
+	///     description.description(),
+	///     "Book +Family table @phone due:2021-08-01"
+	/// );
+	/// ```
+	pub fn set_text(&mut self, text: &str) {
+		let text = text.trim();
+		let new = Self::with_options(text, self.options);
+		let new_metadata = new
+			.components()
+			.filter(|component| !matches!(component, Component::Text(_)))
+			.collect::<Vec<_>>();
+
+		// The spans to remove or (for the first run of words) to replace
+		let mut spans = Vec::new();
+		let mut replaced = None;
+
+		for (component, span) in self.components_spanned() {
+			let span = span.to_range_usize();
+
+			if let Component::Text(words) = component {
+				let trimmed = words.trim_start();
+				let start = span.start + (words.len() - trimmed.len());
+				let end = start + trimmed.trim_end().len();
+
+				if start == end {
+					continue;
+				}
+
+				if replaced.is_none() && !text.is_empty() {
+					replaced = Some(spans.len());
+				}
+
+				spans.push(start..end);
+			} else if new_metadata.contains(&component) {
+				spans.push(span);
+			}
+		}
+
+		if spans.is_empty() && text.is_empty() {
+			return;
+		}
+
+		let mut raw = std::mem::take(&mut self.raw);
+
+		// Edit from the back, so the remaining spans stay valid
+		for (idx, span) in spans.into_iter().enumerate().rev() {
+			if replaced == Some(idx) {
+				raw.replace_range(span, text);
+			} else {
+				remove_span(&mut raw, span);
+			}
+		}
+
+		if replaced.is_none() && !text.is_empty() {
+			if !raw.is_empty() && !raw.starts_with(char::is_whitespace) {
+				raw.insert(0, ' ');
+			}
+
+			raw.insert_str(0, text);
+		}
+
+		self.reindex(raw, self.options);
+	}
+
+	/// Lets `f` edit the text of the description and locates all
+	/// components of the edited text afterwards.
+	pub(crate) fn edit<F, R>(&mut self, f: F) -> R
+	where
+		F: FnOnce(&mut String) -> R,
+	{
+		let mut raw = std::mem::take(&mut self.raw);
+		let result = f(&mut raw);
+		self.reindex(raw, self.options);
+
+		result
+	}
+
+	/// Appends `word` to the description, separated by a space unless the
+	/// description already ends with whitespace.
+	pub(crate) fn push_word(&mut self, word: &str) {
+		self.append_with(|raw| {
+			if !raw.is_empty() && !raw.ends_with(char::is_whitespace) {
+				raw.push(' ');
+			}
+
+			raw.push_str(word);
+		});
+	}
+
+	/// Appends `s` to the text of the description as is.
+	///
+	/// Only the appended text (and the last word before it, which may be
+	/// continued by `s`) is searched for components, so descriptions can be
+	/// built incrementally without scanning the whole text each time.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::Description;
+	///
+	/// let mut description = Description::new("Call Mom +Fam");
+	/// assert_eq!(description.projects().collect::<Vec<_>>(), ["Fam"]);
+	///
+	/// description.push_str("ily @phone");
+	///
+	/// assert_eq!(description.description(), "Call Mom +Family @phone");
+	/// assert_eq!(description.projects().collect::<Vec<_>>(), ["Family"]);
+	/// assert_eq!(description.contexts().collect::<Vec<_>>(), ["phone"]);
+	/// ```
+	pub fn push_str(&mut self, s: &str) {
+		self.append_with(|raw| raw.push_str(s));
+	}
+
+	/// Appends `component` to the description, separated by a single space.
+	///
+	/// Text components are formatted like in [`Self::from_components`], i.e.
+	/// leading and trailing whitespace is dropped and whitespace-only text is
+	/// skipped. Like [`Self::push_str`] only the appended text is searched for
+	/// components.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::{Component, Description};
+	///
+	/// let mut description = Description::new("Call Mom");
+	///
+	/// description.append_component(Component::Project("+Family"));
+	/// description.append_component(Component::Custom {
+	///     key: "due",
+	///     separator: ":",
+	///     value: "2021-08-01",
+	/// });
+	///
+	/// assert_eq!(description.description(), "Call Mom +Family due:2021-08-01");
+	/// assert_eq!(description.tag("due"), Some("2021-08-01"));
+	/// ```
+	pub fn append_component(&mut self, component: Component<'_>) {
+		self.append_with(|raw| push_component(raw, component));
+	}
+
+	/// Lets `f` append to the text of the description and locates the
+	/// components of the appended text, if the components were already
+	/// located.
+	///
+	/// `f` must not modify the existing text.
+	fn append_with<F>(&mut self, f: F)
+	where
+		F: FnOnce(&mut String),
+	{
+		// The last word may be continued by the appended text
+		let start =
+			self.raw.trim_end_matches(|c: char| !c.is_whitespace()).len();
+
+		f(&mut self.raw);
+
+		if let Some(index) = self.index.get_mut() {
+			index.reindex_from(&self.raw, start, self.options);
+		}
+	}
+
+	/// Appends the project `project` (with or without the leading `+`) to
+	/// the description, separated by a single space.
+	///
+	/// Returns `Ok(false)` and leaves the description untouched if it
+	/// already contains the project.
+	///
+	/// # Errors
+	///
+	/// Returns [`BuildDescriptionError::InvalidProject`] if the project name
+	/// is empty or contains whitespace.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::Description;
+	///
+	/// let mut description = Description::new("Outline chapters @desk");
+	///
+	/// assert_eq!(description.add_project("+Novel"), Ok(true));
+	/// assert_eq!(description.add_project("Novel"), Ok(false));
+	/// assert!(description.add_project("Two words").is_err());
+	/// assert_eq!(description.description(), "Outline chapters @desk +Novel");
+	///
+	/// assert!(description.remove_project("Novel"));
+	/// assert_eq!(description.description(), "Outline chapters @desk");
+	/// ```
+	pub fn add_project(
+		&mut self,
+		project: &str,
+	) -> Result<bool, BuildDescriptionError> {
+		let project = project.strip_prefix('+').unwrap_or(project);
+
+		if !is_valid_name(project) {
+			return Err(BuildDescriptionError::InvalidProject(
+				project.to_owned(),
+			));
+		}
+
+		if self.has_project(project) {
+			return Ok(false);
+		}
+
+		self.push_word(&format!("+{}", project));
+
+		Ok(true)
+	}
+
+	/// Removes all occurrences of the project `project` (with or without the
+	/// leading `+`) like [`Self::remove_tag`].
+	///
+	/// Returns `true` if any project was removed.
+	pub fn remove_project(&mut self, project: &str) -> bool {
+		let project = project.strip_prefix('+').unwrap_or(project);
+		let spans = self
+			.project_ranges()
+			.iter()
+			.filter(|range| range.index(&self.raw) == project)
+			.map(|range| *range.full())
+			.collect::<Vec<_>>();
+
+		self.remove_spans(spans)
+	}
+
+	/// Appends the context `context` (with or without the leading `@`) to
+	/// the description, separated by a single space.
+	///
+	/// Returns `Ok(false)` and leaves the description untouched if it
+	/// already contains the context.
+	///
+	/// # Errors
+	///
+	/// Returns [`BuildDescriptionError::InvalidContext`] if the context name
+	/// is empty or contains whitespace.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::Description;
+	///
+	/// let mut description = Description::new("Call Mom +Family");
+	///
+	/// assert_eq!(description.add_context("phone"), Ok(true));
+	/// assert_eq!(description.add_context("@phone"), Ok(false));
+	/// assert!(description.add_context("@").is_err());
+	/// assert_eq!(description.description(), "Call Mom +Family @phone");
+	///
+	/// assert!(description.remove_context("@phone"));
+	/// assert_eq!(description.description(), "Call Mom +Family");
+	/// ```
+	pub fn add_context(
+		&mut self,
+		context: &str,
+	) -> Result<bool, BuildDescriptionError> {
+		let context = context.strip_prefix('@').unwrap_or(context);
+
+		if !is_valid_name(context) {
+			return Err(BuildDescriptionError::InvalidContext(
+				context.to_owned(),
+			));
+		}
+
+		if self.has_context(context) {
+			return Ok(false);
+		}
+
+		self.push_word(&format!("@{}", context));
+
+		Ok(true)
+	}
+
+	/// Removes all occurrences of the context `context` (with or without the
+	/// leading `@`) like [`Self::remove_tag`].
+	///
+	/// Returns `true` if any context was removed.
+	pub fn remove_context(&mut self, context: &str) -> bool {
+		let context = context.strip_prefix('@').unwrap_or(context);
+		let spans = self
+			.context_ranges()
+			.iter()
+			.filter(|range| range.index(&self.raw) == context)
+			.map(|range| *range.full())
+			.collect::<Vec<_>>();
+
+		self.remove_spans(spans)
+	}
+
+	/// Removes all custom tags with the key `key` and, if given, the value
+	/// `value`.
+	///
+	/// Returns `true` if any tag was removed.
+	pub(crate) fn remove_tags(
+		&mut self,
+		key: &str,
+		value: Option<&str>,
+	) -> bool {
+		let spans = self
+			.custom_ranges()
+			.iter()
+			.filter(|range| {
+				let (range_key, range_value) = range.index(&self.raw);
+
+				range_key == key
+					&& value.is_none_or(|value| range_value == value)
+			})
+			.map(|range| *range.full())
+			.collect::<Vec<_>>();
+
+		self.remove_spans(spans)
+	}
+
+	/// Returns the normalized text of the description (see
+	/// [`Task::normalize`](`crate::Task::normalize`)).
+	pub(crate) fn normalized(&self) -> String {
+		// Words paired with their rank (`0` is text)
+		let mut words = Vec::new();
+		let mut custom = self.custom_ranges().iter();
+
+		for component in self.components() {
+			match component {
+				Component::Text(text) => {
+					words
+						.extend(text.split_whitespace().map(|word| (0, word)));
+				}
+				Component::Project(project) => words.push((1, project)),
+				Component::Context(context) => words.push((2, context)),
+				Component::Custom { .. } => {
+					let range = custom.next().expect("one range per tag");
+					words.push((
+						3,
+						Index::index(self.raw.as_str(), range.full),
+					));
+				}
+			}
+		}
+
+		let trailing = words
+			.iter()
+			.rposition(|&(rank, _)| rank == 0)
+			.map_or(0, |idx| idx + 1);
+		words[trailing..].sort_by_key(|&(rank, _)| rank);
+
+		words.iter().map(|&(_, word)| word).collect::<Vec<_>>().join(" ")
+	}
+
+	/// Removes all `spans` (in ascending order) together with the whitespace
+	/// separating them from the preceding word (or the following word, if
+	/// there is no preceding one).
+	///
+	/// The spacing between the remaining words is kept as is.
+	///
+	/// Returns `true` if any span was removed.
+	fn remove_spans(&mut self, spans: Vec<ByteSpan>) -> bool {
+		if spans.is_empty() {
+			return false;
+		}
+
+		let mut raw = std::mem::take(&mut self.raw);
+
+		// Remove from the back, so the remaining spans stay valid
+		for span in spans.into_iter().rev() {
+			remove_span(&mut raw, span.to_range_usize());
+		}
+
+		self.reindex(raw, self.options);
+
+		true
 	}
 
 	// project: \+[^ ]+
@@ -184,35 +1538,36 @@ impl Description {
 	//
 	fn index(
 		s: &str,
-	) -> (Vec<ProjectRange>, Vec<ContextRange>, Vec<CustomRange>) {
-		let mut projects = Vec::new();
-		let mut contexts = Vec::new();
-		let mut custom = Vec::new();
-
-		let mut cursor = Cursor::new(s.as_bytes());
+		start: usize,
+		options: DescriptionOptions,
+		index: &mut ComponentIndex,
+	) {
+		let mut cursor = Cursor::with_index(s.as_bytes(), start);
 
 		while !cursor.is_eof() {
-			// reset word boundry (todo: handle unicode whitespace)
+			// reset word boundry
 			cursor.consume_whitespaces();
 			let word_start = cursor.byte_pos();
 
 			match (cursor.first(), cursor.second()) {
 				// read project
-				(Some(b'+'), Some(b)) if !b.is_ascii_whitespace() => {
-					projects.push(Self::read_project(&mut cursor, word_start));
+				(Some(b'+'), Some(_)) if !cursor.is_whitespace_at(1) => {
+					let range = Self::read_project(&mut cursor, word_start);
+					index.ranges_mut().projects.push(range);
 				}
 
 				// read context
-				(Some(b'@'), Some(b)) if !b.is_ascii_whitespace() => {
-					contexts.push(Self::read_context(&mut cursor, word_start));
+				(Some(b'@'), Some(_)) if !cursor.is_whitespace_at(1) => {
+					let range = Self::read_context(&mut cursor, word_start);
+					index.ranges_mut().contexts.push(range);
 				}
 
 				// try read custom tag
-				(Some(_), Some(b)) if !b.is_ascii_whitespace() => {
+				(Some(_), Some(_)) if !cursor.is_whitespace_at(1) => {
 					if let Some(range) =
-						Self::read_custom(&mut cursor, word_start)
+						Self::read_custom(&mut cursor, word_start, options)
 					{
-						custom.push(range);
+						index.ranges_mut().custom.push(range);
 					} else {
 						// Keep word boundaries intact
 						cursor.consume_non_whitespaces();
@@ -229,12 +1584,8 @@ impl Description {
 			}
 
 			// TODO: check and warn if not at word boundry
-			debug_assert!(cursor
-				.first()
-				.map_or(true, |b| b.is_ascii_whitespace()));
+			debug_assert!(cursor.is_eof() || cursor.is_whitespace_at(0));
 		}
-
-		(projects, contexts, custom)
 	}
 
 	fn read_project(
@@ -258,32 +1609,40 @@ impl Description {
 	fn read_custom(
 		cursor: &mut Cursor<'_>,
 		word_start: BytePos,
+		options: DescriptionOptions,
 	) -> Option<CustomRange> {
 		const fn is_key_value_byte(byte: u8) -> bool {
-			!byte.is_ascii_whitespace() && byte != b':'
+			byte != b':'
 		}
 
-		cursor.consume_while(is_key_value_byte);
+		cursor.consume_word_while(is_key_value_byte);
 
 		if let Some(b':') = cursor.first() {
 			let key_span = ByteSpan::new(word_start, cursor.byte_pos());
 
 			assert_eq!(cursor.consume(), Some(b':'));
 
-			let separator_span =
-				ByteSpan::new(cursor.byte_pos(), cursor.byte_pos())
-					.offset_low(-1);
+			let separator_span = ByteSpan::new(
+				BytePos::from_usize(cursor.index() - 1),
+				cursor.byte_pos(),
+			);
 
-			if key_span.len() > 0
+			if !key_span.is_empty()
 				&& matches!(cursor.first(), Some(b) if is_key_value_byte(b))
+				&& !cursor.is_whitespace_at(0)
 			{
 				let value_start = cursor.byte_pos();
-				cursor.consume_while(is_key_value_byte);
+
+				if options.colons_in_values {
+					cursor.consume_non_whitespaces();
+				} else {
+					cursor.consume_word_while(is_key_value_byte);
+				}
 
 				let value_span = ByteSpan::new(value_start, cursor.byte_pos());
 
-				if !(value_span.len() == 0
-					|| matches!(cursor.first(), Some(b) if !b.is_ascii_whitespace()))
+				if !value_span.is_empty()
+					&& (cursor.is_eof() || cursor.is_whitespace_at(0))
 				{
 					return Some(CustomRange::new(
 						key_span,
@@ -298,6 +1657,255 @@ impl Description {
 	}
 }
 
+/// Removes `span` from `raw` together with the whitespace separating it
+/// from the preceding word (or the following word, if there is no preceding
+/// one).
+fn remove_span(raw: &mut String, span: Range<usize>) {
+	let preceding = raw[..span.start].trim_end().len();
+
+	let range = if preceding > 0 {
+		preceding..span.end
+	} else {
+		let following = raw.len() - raw[span.end..].trim_start().len();
+		span.start..following
+	};
+
+	raw.replace_range(range, "");
+}
+
+/// Appends `component` to `raw`, separated by a single space (see
+/// [`Description::from_components`]).
+fn push_component(raw: &mut String, component: Component<'_>) {
+	if let Component::Text(text) = component {
+		if text.trim().is_empty() {
+			return;
+		}
+	}
+
+	if !raw.is_empty() {
+		raw.push(' ');
+	}
+
+	match component {
+		Component::Text(text) => raw.push_str(text.trim()),
+		Component::Project(full) | Component::Context(full) => {
+			raw.push_str(full)
+		}
+		Component::Custom { key, separator, value } => {
+			raw.push_str(key);
+			raw.push_str(separator);
+			raw.push_str(value);
+		}
+	}
+}
+
+/// Returns `true` if `key` can be the key of a custom tag.
+fn is_valid_tag_key(key: &str) -> bool {
+	is_valid_tag_value(key, DescriptionOptions::default())
+		&& !key.starts_with(['+', '@'])
+}
+
+/// Returns `true` if `value` can be the value of a custom tag.
+pub fn is_valid_tag_value(value: &str, options: DescriptionOptions) -> bool {
+	if options.colons_in_values {
+		return !value.is_empty()
+			&& !value.starts_with(':')
+			&& !value.contains(char::is_whitespace);
+	}
+
+	!value.is_empty()
+		&& !value.contains(|c: char| c.is_whitespace() || c == ':')
+}
+
+/// Returns `true` if `name` can be the name of a project or context.
+fn is_valid_name(name: &str) -> bool {
+	!name.is_empty() && !name.contains(char::is_whitespace)
+}
+
+/// A builder which assembles a [`Description`] piece by piece.
+///
+/// Pieces are separated by a single space. Every piece is validated when it
+/// is pushed, the first invalid piece is reported by
+/// [`DescriptionBuilder::build`].
+///
+/// # Examples
+///
+/// ```rust
+/// use tdtxt::{Description, Task};
+///
+/// let description = Description::builder()
+///     .push_text("Call Mom")
+///     .push_project("Family")
+///     .push_context("@phone")
+///     .push_tag("due", "2021-08-01")
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(
+///     description.description(),
+///     "Call Mom +Family @phone due:2021-08-01"
+/// );
+///
+/// let task = Task::build().build(description);
+/// assert_eq!(task.to_string(), "Call Mom +Family @phone due:2021-08-01");
+///
+/// assert!(Description::builder().push_text("Call\nMom").build().is_err());
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DescriptionBuilder {
+	raw: String,
+	error: Option<BuildDescriptionError>,
+}
+
+impl DescriptionBuilder {
+	/// Creates a new instance of the builder.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Appends the piece if `valid` or records `error` otherwise.
+	fn push(
+		&mut self,
+		valid: bool,
+		piece: &[&str],
+		error: impl FnOnce() -> BuildDescriptionError,
+	) -> &mut Self {
+		if self.error.is_some() {
+			return self;
+		}
+
+		if !valid {
+			self.error = Some(error());
+			return self;
+		}
+
+		if !self.raw.is_empty() {
+			self.raw.push(' ');
+		}
+
+		piece.iter().for_each(|part| self.raw.push_str(part));
+
+		self
+	}
+
+	/// Appends plain text.
+	///
+	/// The text must not be empty, contain line breaks or start or end with
+	/// whitespace.
+	pub fn push_text(&mut self, text: &str) -> &mut Self {
+		let valid = !text.is_empty()
+			&& !text.contains(['\n', '\r'])
+			&& text.trim() == text;
+
+		self.push(valid, &[text], || {
+			BuildDescriptionError::InvalidText(text.to_owned())
+		})
+	}
+
+	/// Appends the project `project` (with or without the leading `+`).
+	///
+	/// The name must not be empty or contain whitespace.
+	pub fn push_project(&mut self, project: &str) -> &mut Self {
+		let project = project.strip_prefix('+').unwrap_or(project);
+
+		self.push(is_valid_name(project), &["+", project], || {
+			BuildDescriptionError::InvalidProject(project.to_owned())
+		})
+	}
+
+	/// Appends the context `context` (with or without the leading `@`).
+	///
+	/// The name must not be empty or contain whitespace.
+	pub fn push_context(&mut self, context: &str) -> &mut Self {
+		let context = context.strip_prefix('@').unwrap_or(context);
+
+		self.push(is_valid_name(context), &["@", context], || {
+			BuildDescriptionError::InvalidContext(context.to_owned())
+		})
+	}
+
+	/// Appends the custom tag `key:value`.
+	///
+	/// Neither the key nor the value must be empty or contain whitespace or
+	/// a `:`. The key must not start with `+` or `@`.
+	pub fn push_tag(&mut self, key: &str, value: &str) -> &mut Self {
+		let valid = is_valid_tag_key(key)
+			&& is_valid_tag_value(value, DescriptionOptions::default());
+
+		self.push(valid, &[key, ":", value], || {
+			BuildDescriptionError::InvalidTag {
+				key: key.to_owned(),
+				value: value.to_owned(),
+			}
+		})
+	}
+
+	/// Creates the description from the builder.
+	///
+	/// # Errors
+	///
+	/// Returns the error of the first invalid piece.
+	pub fn build(&self) -> Result<Description, BuildDescriptionError> {
+		match &self.error {
+			Some(err) => Err(err.clone()),
+			None => Ok(Description::new(self.raw.as_str())),
+		}
+	}
+}
+
+/// This enum represents errors which may occur when building a
+/// [`Description`] with a [`DescriptionBuilder`] or when adding components to
+/// an existing description.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildDescriptionError {
+	/// The text is empty, contains a line break or starts or ends with
+	/// whitespace.
+	InvalidText(String),
+
+	/// The name of the project is empty or contains whitespace.
+	InvalidProject(String),
+
+	/// The name of the context is empty or contains whitespace.
+	InvalidContext(String),
+
+	/// The key or value of the custom tag is invalid.
+	InvalidTag {
+		/// The key of the tag.
+		key: String,
+
+		/// The value of the tag.
+		value: String,
+	},
+}
+
+impl fmt::Display for BuildDescriptionError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::InvalidText(text) => write!(f, "invalid text `{}`", text),
+			Self::InvalidProject(project) => {
+				write!(f, "invalid project `{}`", project)
+			}
+			Self::InvalidContext(context) => {
+				write!(f, "invalid context `{}`", context)
+			}
+			Self::InvalidTag { key, value } => {
+				write!(f, "invalid tag `{}:{}`", key, value)
+			}
+		}
+	}
+}
+
+impl std::error::Error for BuildDescriptionError {}
+
+impl PartialEq for Description {
+	fn eq(&self, other: &Self) -> bool {
+		// The components are derived from the text and the options
+		self.raw == other.raw && self.options == other.options
+	}
+}
+
+impl Eq for Description {}
+
 impl fmt::Display for Description {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 		f.write_str(&self.raw)
@@ -312,6 +1920,12 @@ impl Deref for Description {
 	}
 }
 
+impl<'a> FromIterator<Component<'a>> for Description {
+	fn from_iter<I: IntoIterator<Item = Component<'a>>>(iter: I) -> Self {
+		Self::from_components(iter)
+	}
+}
+
 impl<S> From<S> for Description
 where
 	S: Into<String>,
@@ -321,30 +1935,48 @@ where
 	}
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Description {
+	fn arbitrary(
+		u: &mut arbitrary::Unstructured<'a>,
+	) -> arbitrary::Result<Self> {
+		Ok(Self::new(String::arbitrary(u)?))
+	}
+}
+
 crate::parse_error!(ParseDescriptionError: "description");
 
 impl Parse for Description {
 	type Error = ParseDescriptionError;
 
 	fn parse(parser: &mut Parser<'_>) -> Result<Self, Self::Error> {
-		let description = parser
-			.parse_until(b'\n')
-			.ok_or_else(ParseDescriptionError::default)?;
-		let description = std::str::from_utf8(description)
-			.map_err(|_| ParseDescriptionError::default())?;
-		let description = Self::new(description);
-
-		// consume possible new line
-		let _ = parser.parse_u8();
-
-		Ok(description)
+		parse_text(parser).map(Self::new)
 	}
 }
 
 crate::impl_fromstr!(Description);
 
+/// Reads the text of a description up to the end of the line and consumes
+/// the line ending.
+pub fn parse_text<'a>(
+	parser: &mut Parser<'a>,
+) -> Result<&'a str, ParseDescriptionError> {
+	let description = parser
+		.parse_until(b'\n')
+		.ok_or_else(ParseDescriptionError::default)?;
+	// strip carriage return of a possible `\r\n` line ending
+	let description = description.strip_suffix(b"\r").unwrap_or(description);
+	let description = std::str::from_utf8(description)
+		.map_err(|_| ParseDescriptionError::default())?;
+
+	// consume possible new line
+	let _ = parser.parse_u8();
+
+	Ok(description)
+}
+
 macro_rules! simple_iter {
-	( $name:ident => $range:ty, $rangevar:ident, $item:ty) => {
+	( $name:ident => $range:ty, $item:ty) => {
 		#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 		pub struct $name<'a> {
 			description: &'a str,
@@ -353,12 +1985,11 @@ macro_rules! simple_iter {
 		}
 
 		impl<'a> $name<'a> {
-			fn new(description: &'a Description) -> Self {
-				Self {
-					description: &description.raw,
-					ranges: &description.$rangevar,
-					ranges_idx: 0,
-				}
+			pub(crate) const fn new(
+				description: &'a str,
+				ranges: &'a [$range],
+			) -> Self {
+				Self { description, ranges, ranges_idx: 0 }
 			}
 		}
 
@@ -375,16 +2006,108 @@ macro_rules! simple_iter {
 	};
 }
 
-simple_iter!(ProjectIter => ProjectRange, projects, &'a str);
-simple_iter!(ContextIter => ContextRange, contexts, &'a str);
-simple_iter!(CustomIter => CustomRange, custom, (&'a str, &'a str));
+simple_iter!(ProjectIter => ProjectRange, &'a str);
+simple_iter!(ContextIter => ContextRange, &'a str);
+simple_iter!(CustomIter => CustomRange, (&'a str, &'a str));
+
+/// An iterator over the custom tags of a [`Description`] grouped by their
+/// key.
+///
+/// The keys are yielded in the order of their first appearance.
+///
+/// This iterator is returned by calling [`Description::custom_grouped`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CustomGrouped<'a> {
+	description: &'a str,
+	ranges: &'a [CustomRange],
+	ranges_idx: usize,
+}
+
+impl<'a> CustomGrouped<'a> {
+	pub(crate) const fn new(
+		description: &'a str,
+		ranges: &'a [CustomRange],
+	) -> Self {
+		Self { description, ranges, ranges_idx: 0 }
+	}
+}
+
+impl<'a> Iterator for CustomGrouped<'a> {
+	type Item = (&'a str, TagValues<'a>);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		while let Some(range) = self.ranges.get(self.ranges_idx) {
+			let (seen, rest) = self.ranges.split_at(self.ranges_idx);
+			self.ranges_idx += 1;
+
+			let key = &self.description[range.key().to_range_usize()];
+			let is_new = !seen
+				.iter()
+				.any(|r| &self.description[r.key().to_range_usize()] == key);
+
+			if is_new {
+				return Some((
+					key,
+					TagValues::new(self.description, rest, key),
+				));
+			}
+		}
+
+		None
+	}
+}
+
+impl std::iter::FusedIterator for CustomGrouped<'_> {}
+
+/// An iterator over the values of all custom tags with the same key.
+///
+/// This iterator is yielded by [`CustomGrouped`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TagValues<'a> {
+	description: &'a str,
+	ranges: &'a [CustomRange],
+	key: &'a str,
+}
+
+impl<'a> TagValues<'a> {
+	const fn new(
+		description: &'a str,
+		ranges: &'a [CustomRange],
+		key: &'a str,
+	) -> Self {
+		Self { description, ranges, key }
+	}
+}
+
+impl<'a> Iterator for TagValues<'a> {
+	type Item = &'a str;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		while let Some((range, rest)) = self.ranges.split_first() {
+			self.ranges = rest;
+
+			let (key, value) = range.index(self.description);
+			if key == self.key {
+				return Some(value);
+			}
+		}
+
+		None
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(0, Some(self.ranges.len()))
+	}
+}
+
+impl std::iter::FusedIterator for TagValues<'_> {}
 
 /// A single component of a [`Description`].
 ///
 /// Variants of this enum are created by [`Components`], which is an iterator
 /// over all components of a description. This iterator is returned by calling
 /// [`Description::components`].
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Component<'a> {
 	/// A text component, e.g. `Hello World`.
 	Text(&'a str),
@@ -408,6 +2131,46 @@ pub enum Component<'a> {
 	},
 }
 
+impl<'a> Component<'a> {
+	/// Returns the text of the component if it is a single slice of the
+	/// description, i.e. for every variant except [`Component::Custom`].
+	pub const fn as_str(&self) -> Option<&'a str> {
+		match self {
+			Self::Text(s) | Self::Project(s) | Self::Context(s) => Some(s),
+			Self::Custom { .. } => None,
+		}
+	}
+}
+
+/// Formats the component as it appears in the description.
+///
+/// # Examples
+///
+/// ```rust
+/// use tdtxt::Description;
+///
+/// let description = Description::new("Call Mom +Family @phone due:x");
+///
+/// let text = description
+///     .components()
+///     .map(|component| component.to_string())
+///     .collect::<String>();
+///
+/// assert_eq!(text, description.description());
+/// ```
+impl fmt::Display for Component<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Text(s) | Self::Project(s) | Self::Context(s) => {
+				f.write_str(s)
+			}
+			Self::Custom { key, separator, value } => {
+				write!(f, "{}{}{}", key, separator, value)
+			}
+		}
+	}
+}
+
 /// An iterator of all the [`Component`]'s of a [`Description`].
 ///
 /// This iterator is returned by calling [`Description::components`].
@@ -443,17 +2206,176 @@ pub struct Components<'a> {
 	project_ranges: &'a [ProjectRange],
 	context_ranges: &'a [ContextRange],
 	custom_ranges: &'a [CustomRange],
-	byte_idx: usize,
+
+	/// The start of the not yet yielded part of [`Self::raw`].
+	front: usize,
+
+	/// The end of the not yet yielded part of [`Self::raw`].
+	back: usize,
+
+	/// The number of not yet yielded components.
+	len: usize,
 }
 
 impl<'a> Components<'a> {
-	fn new(description: &'a Description) -> Self {
-		Self {
-			raw: &description.raw,
-			project_ranges: &description.projects,
-			context_ranges: &description.contexts,
-			custom_ranges: &description.custom,
-			byte_idx: 0,
+	pub(crate) fn new(raw: &'a str, index: &'a ComponentIndex) -> Self {
+		let mut components = Self {
+			raw,
+			project_ranges: index.projects(),
+			context_ranges: index.contexts(),
+			custom_ranges: index.custom(),
+			front: 0,
+			back: raw.len(),
+			len: 0,
+		};
+		components.len = components.count_all();
+
+		components
+	}
+
+	/// Counts all components.
+	///
+	/// Consecutive projects, contexts and custom tags are always separated
+	/// by whitespace, so there is exactly one text component between each
+	/// of them.
+	fn count_all(&self) -> usize {
+		let ranges = self.project_ranges.len()
+			+ self.context_ranges.len()
+			+ self.custom_ranges.len();
+
+		match (self.first_low(), self.last_high()) {
+			(Some(low), Some(high)) => {
+				2 * ranges - 1
+					+ usize::from(low > self.front)
+					+ usize::from(high < self.back)
+			}
+			_ => usize::from(self.front < self.back),
+		}
+	}
+
+	/// Returns the start of the first remaining range.
+	fn first_low(&self) -> Option<usize> {
+		let project = self.project_ranges.first().map(|range| range.full);
+		let context = self.context_ranges.first().map(|range| range.full);
+		let custom = self.custom_ranges.first().map(|range| range.full);
+
+		[project, context, custom]
+			.iter()
+			.flatten()
+			.map(|span| span.low().as_usize())
+			.min()
+	}
+
+	/// Returns the end of the last remaining range.
+	fn last_high(&self) -> Option<usize> {
+		let project = self.project_ranges.last().map(|range| range.full);
+		let context = self.context_ranges.last().map(|range| range.full);
+		let custom = self.custom_ranges.last().map(|range| range.full);
+
+		[project, context, custom]
+			.iter()
+			.flatten()
+			.map(|span| span.high().as_usize())
+			.max()
+	}
+
+	/// Removes the first remaining component and returns it together with
+	/// its location.
+	fn pop_front(&mut self) -> Option<(Component<'a>, ByteSpan)> {
+		if self.front >= self.back {
+			return None;
+		}
+
+		let (component, span) = if let Some((range, rest)) = self
+			.project_ranges
+			.split_first()
+			.filter(|(range, _)| range.full.low().as_usize() == self.front)
+		{
+			self.project_ranges = rest;
+			(
+				Component::Project(Index::index(self.raw, range.full)),
+				range.full,
+			)
+		} else if let Some((range, rest)) = self
+			.context_ranges
+			.split_first()
+			.filter(|(range, _)| range.full.low().as_usize() == self.front)
+		{
+			self.context_ranges = rest;
+			(
+				Component::Context(Index::index(self.raw, range.full)),
+				range.full,
+			)
+		} else if let Some((range, rest)) = self
+			.custom_ranges
+			.split_first()
+			.filter(|(range, _)| range.full.low().as_usize() == self.front)
+		{
+			self.custom_ranges = rest;
+			(self.custom_component(range), range.full)
+		} else {
+			let end = self.first_low().unwrap_or(self.back);
+			let span = ByteSpan::from_usize_range(self.front..end);
+			(Component::Text(Index::index(self.raw, span)), span)
+		};
+
+		self.front = span.high().as_usize();
+		self.len -= 1;
+
+		Some((component, span))
+	}
+
+	/// Removes the last remaining component and returns it together with
+	/// its location.
+	fn pop_back(&mut self) -> Option<(Component<'a>, ByteSpan)> {
+		if self.front >= self.back {
+			return None;
+		}
+
+		let (component, span) = if let Some((range, rest)) = self
+			.project_ranges
+			.split_last()
+			.filter(|(range, _)| range.full.high().as_usize() == self.back)
+		{
+			self.project_ranges = rest;
+			(
+				Component::Project(Index::index(self.raw, range.full)),
+				range.full,
+			)
+		} else if let Some((range, rest)) = self
+			.context_ranges
+			.split_last()
+			.filter(|(range, _)| range.full.high().as_usize() == self.back)
+		{
+			self.context_ranges = rest;
+			(
+				Component::Context(Index::index(self.raw, range.full)),
+				range.full,
+			)
+		} else if let Some((range, rest)) = self
+			.custom_ranges
+			.split_last()
+			.filter(|(range, _)| range.full.high().as_usize() == self.back)
+		{
+			self.custom_ranges = rest;
+			(self.custom_component(range), range.full)
+		} else {
+			let start = self.last_high().unwrap_or(self.front);
+			let span = ByteSpan::from_usize_range(start..self.back);
+			(Component::Text(Index::index(self.raw, span)), span)
+		};
+
+		self.back = span.low().as_usize();
+		self.len -= 1;
+
+		Some((component, span))
+	}
+
+	fn custom_component(&self, range: &CustomRange) -> Component<'a> {
+		Component::Custom {
+			key: Index::index(self.raw, range.key),
+			separator: Index::index(self.raw, range.separator),
+			value: Index::index(self.raw, range.value),
 		}
 	}
 }
@@ -462,78 +2384,119 @@ impl<'a> Iterator for Components<'a> {
 	type Item = Component<'a>;
 
 	fn next(&mut self) -> Option<Self::Item> {
-		if self.byte_idx >= self.raw.len() {
-			None
-		} else {
-			let mut range_end = self.raw.len();
+		self.pop_front().map(|(component, _)| component)
+	}
 
-			if let Some(project_range) = self.project_ranges.first() {
-				let low_idx = project_range.full().low().as_usize();
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(self.len, Some(self.len))
+	}
+}
 
-				if low_idx == self.byte_idx {
-					// Update internal state
-					// "Pop" range
-					self.project_ranges = &self.project_ranges[1..];
-					self.byte_idx = project_range.full().high().as_usize();
+impl DoubleEndedIterator for Components<'_> {
+	fn next_back(&mut self) -> Option<Self::Item> {
+		self.pop_back().map(|(component, _)| component)
+	}
+}
 
-					return Some(Component::Project(Index::index(
-						self.raw,
-						project_range.full,
-					)));
-				} else {
-					range_end = range_end.min(low_idx);
-				}
-			}
+impl ExactSizeIterator for Components<'_> {}
 
-			if let Some(context_range) = self.context_ranges.first() {
-				let low_idx = context_range.full().low().as_usize();
+impl std::iter::FusedIterator for Components<'_> {}
 
-				if low_idx == self.byte_idx {
-					// Update internal state
-					// "Pop" range
-					self.context_ranges = &self.context_ranges[1..];
-					self.byte_idx = context_range.full().high().as_usize();
+/// An iterator of all the [`Component`]'s of a [`Description`] together with
+/// their location within the description.
+///
+/// This iterator is returned by calling [`Description::components_spanned`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpannedComponents<'a> {
+	components: Components<'a>,
+}
 
-					return Some(Component::Context(Index::index(
-						self.raw,
-						context_range.full,
-					)));
-				} else {
-					range_end = range_end.min(low_idx);
-				}
+impl<'a> SpannedComponents<'a> {
+	pub(crate) const fn new(components: Components<'a>) -> Self {
+		Self { components }
+	}
+}
+
+impl<'a> Iterator for SpannedComponents<'a> {
+	type Item = (Component<'a>, ByteSpan);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.components.pop_front()
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		self.components.size_hint()
+	}
+}
+
+impl DoubleEndedIterator for SpannedComponents<'_> {
+	fn next_back(&mut self) -> Option<Self::Item> {
+		self.components.pop_back()
+	}
+}
+
+impl ExactSizeIterator for SpannedComponents<'_> {}
+
+impl std::iter::FusedIterator for SpannedComponents<'_> {}
+
+/// An iterator over all words of a [`Description`] together with their
+/// location within the description.
+///
+/// This iterator is returned by calling [`Description::words`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Words<'a> {
+	components: SpannedComponents<'a>,
+
+	/// The not yet yielded part of the current text component.
+	text: &'a str,
+
+	/// The offset of [`Self::text`] within the description.
+	offset: usize,
+}
+
+impl<'a> Words<'a> {
+	pub(crate) const fn new(components: SpannedComponents<'a>) -> Self {
+		Self { components, text: "", offset: 0 }
+	}
+}
+
+impl<'a> Iterator for Words<'a> {
+	type Item = (Component<'a>, ByteSpan);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			let trimmed = self.text.trim_start();
+			self.offset += self.text.len() - trimmed.len();
+			self.text = trimmed;
+
+			if !self.text.is_empty() {
+				let len = self
+					.text
+					.find(char::is_whitespace)
+					.unwrap_or(self.text.len());
+				let (word, rest) = self.text.split_at(len);
+				let span =
+					ByteSpan::from_usize_range(self.offset..self.offset + len);
+
+				self.text = rest;
+				self.offset += len;
+
+				return Some((Component::Text(word), span));
 			}
 
-			if let Some(custom_range) = self.custom_ranges.first() {
-				let low_idx = custom_range.full().low().as_usize();
-
-				if low_idx == self.byte_idx {
-					// Update internal state
-					// "Pop" range
-					self.custom_ranges = &self.custom_ranges[1..];
-					self.byte_idx = custom_range.full().high().as_usize();
-
-					return Some(Component::Custom {
-						key: Index::index(self.raw, custom_range.key),
-						separator: Index::index(
-							self.raw,
-							custom_range.separator,
-						),
-						value: Index::index(self.raw, custom_range.value),
-					});
-				} else {
-					range_end = range_end.min(low_idx);
+			match self.components.next()? {
+				(Component::Text(text), span) => {
+					self.text = text;
+					self.offset = span.low().as_usize();
 				}
+				component => return Some(component),
 			}
-
-			std::mem::swap(&mut self.byte_idx, &mut range_end);
-
-			return Some(Component::Text(
-				self.raw.index(range_end..self.byte_idx),
-			));
 		}
 	}
 }
 
+impl std::iter::FusedIterator for Words<'_> {}
+
 #[cfg(feature = "serde")]
 impl serde::Serialize for Description {
 	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -0,0 +1,217 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::ops::Deref;
+use std::sync::OnceLock;
+
+use crate::description::{
+	ComponentIndex, Components, ContextIter, ContextRange, CustomGrouped,
+	CustomIter, CustomRange, Description, DescriptionOptions, ProjectIter,
+	ProjectRange, SpannedComponents, Words,
+};
+use crate::tag::TagValue;
+
+/// A [`Description`] which borrows its text where possible.
+///
+/// It offers the read-only api of [`Description`] without copying the text,
+/// e.g. when scanning lines of an existing buffer. Use
+/// [`Self::into_owned`] to get an editable [`Description`].
+///
+/// # Examples
+///
+/// ```rust
+/// use tdtxt::DescriptionRef;
+///
+/// let buffer = String::from("Call Mom +Family @phone\nPay rent due:2021-08-01");
+///
+/// let descriptions =
+///     buffer.lines().map(DescriptionRef::new).collect::<Vec<_>>();
+///
+/// assert!(descriptions[0].is_borrowed());
+/// assert!(descriptions[0].has_project("Family"));
+/// assert_eq!(descriptions[1].tag("due"), Some("2021-08-01"));
+///
+/// let mut owned = descriptions[1].clone().into_owned();
+/// owned.set_tag("due", "2021-09-01")?;
+/// assert_eq!(owned.description(), "Pay rent due:2021-09-01");
+/// # Ok::<(), tdtxt::BuildDescriptionError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct DescriptionRef<'a> {
+	/// The whole text of the description.
+	raw: Cow<'a, str>,
+
+	/// The options used to locate the components.
+	options: DescriptionOptions,
+
+	/// The located components of [`Self::raw`].
+	index: OnceLock<ComponentIndex>,
+}
+
+impl<'a> DescriptionRef<'a> {
+	/// Creates a new description from `s`.
+	///
+	/// All projects, contexts and custom tags are located on first access.
+	pub fn new<S>(s: S) -> Self
+	where
+		S: Into<Cow<'a, str>>,
+	{
+		Self::with_options(s, DescriptionOptions::default())
+	}
+
+	/// Creates a new description from `s` and locates its components
+	/// according to `options`.
+	pub fn with_options<S>(s: S, options: DescriptionOptions) -> Self
+	where
+		S: Into<Cow<'a, str>>,
+	{
+		Self { raw: s.into(), options, index: OnceLock::new() }
+	}
+
+	/// Returns the located components, locating them if necessary.
+	fn component_index(&self) -> &ComponentIndex {
+		self.index.get_or_init(|| ComponentIndex::new(&self.raw, self.options))
+	}
+
+	/// Returns `true` if the text is borrowed.
+	pub const fn is_borrowed(&self) -> bool {
+		matches!(self.raw, Cow::Borrowed(_))
+	}
+
+	/// Returns the options used to locate the components.
+	pub const fn options(&self) -> DescriptionOptions {
+		self.options
+	}
+
+	/// Returns the text of the whole description.
+	pub fn description(&self) -> &str {
+		&self.raw
+	}
+
+	/// See [`Description::projects`].
+	pub fn projects(&self) -> ProjectIter<'_> {
+		ProjectIter::new(&self.raw, self.project_ranges())
+	}
+
+	/// See [`Description::contexts`].
+	pub fn contexts(&self) -> ContextIter<'_> {
+		ContextIter::new(&self.raw, self.context_ranges())
+	}
+
+	/// See [`Description::custom`].
+	pub fn custom(&self) -> CustomIter<'_> {
+		CustomIter::new(&self.raw, self.custom_ranges())
+	}
+
+	/// See [`Description::custom_grouped`].
+	pub fn custom_grouped(&self) -> CustomGrouped<'_> {
+		CustomGrouped::new(&self.raw, self.custom_ranges())
+	}
+
+	/// See [`Description::components`].
+	pub fn components(&self) -> Components<'_> {
+		Components::new(&self.raw, self.component_index())
+	}
+
+	/// See [`Description::components_spanned`].
+	pub fn components_spanned(&self) -> SpannedComponents<'_> {
+		SpannedComponents::new(self.components())
+	}
+
+	/// See [`Description::words`].
+	pub fn words(&self) -> Words<'_> {
+		Words::new(self.components_spanned())
+	}
+
+	/// See [`Description::has_project`].
+	pub fn has_project(&self, project: &str) -> bool {
+		let project = project.strip_prefix('+').unwrap_or(project);
+
+		self.projects().any(|name| name == project)
+	}
+
+	/// See [`Description::has_context`].
+	pub fn has_context(&self, context: &str) -> bool {
+		let context = context.strip_prefix('@').unwrap_or(context);
+
+		self.contexts().any(|name| name == context)
+	}
+
+	/// See [`Description::has_tag`].
+	pub fn has_tag(&self, key: &str) -> bool {
+		self.tag(key).is_some()
+	}
+
+	/// See [`Description::tag`].
+	pub fn tag(&self, key: &str) -> Option<&str> {
+		self.custom().find(|(k, _)| *k == key).map(|(_, value)| value)
+	}
+
+	/// See [`Description::typed_tag`].
+	pub fn typed_tag(&self, key: &str) -> Option<TagValue<'_>> {
+		self.tag(key).map(TagValue::parse)
+	}
+
+	/// See [`Description::project_ranges`].
+	pub fn project_ranges(&self) -> &[ProjectRange] {
+		self.component_index().projects()
+	}
+
+	/// See [`Description::context_ranges`].
+	pub fn context_ranges(&self) -> &[ContextRange] {
+		self.component_index().contexts()
+	}
+
+	/// See [`Description::custom_ranges`].
+	pub fn custom_ranges(&self) -> &[CustomRange] {
+		self.component_index().custom()
+	}
+
+	/// Converts the description into an owned [`Description`], copying the
+	/// text if it is borrowed.
+	pub fn into_owned(self) -> Description {
+		Description::from_parts(
+			self.raw.into_owned(),
+			self.options,
+			self.index,
+		)
+	}
+}
+
+impl<'a> From<&'a Description> for DescriptionRef<'a> {
+	fn from(description: &'a Description) -> Self {
+		Self {
+			raw: Cow::Borrowed(description.description()),
+			options: description.options(),
+			index: description.index_cell().clone(),
+		}
+	}
+}
+
+impl From<DescriptionRef<'_>> for Description {
+	fn from(description: DescriptionRef<'_>) -> Self {
+		description.into_owned()
+	}
+}
+
+impl PartialEq for DescriptionRef<'_> {
+	fn eq(&self, other: &Self) -> bool {
+		// The components are derived from the text and the options
+		self.raw == other.raw && self.options == other.options
+	}
+}
+
+impl Eq for DescriptionRef<'_> {}
+
+impl fmt::Display for DescriptionRef<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(&self.raw)
+	}
+}
+
+impl Deref for DescriptionRef<'_> {
+	type Target = str;
+
+	fn deref(&self) -> &Self::Target {
+		&self.raw
+	}
+}
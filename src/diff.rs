@@ -0,0 +1,227 @@
+use crate::date::DateCompound;
+use crate::priority::Priority;
+use crate::state::State;
+use crate::task::Task;
+
+/// A single difference between two [`Task`]s, returned by [`Task::diff`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TaskChange {
+	/// The state changed.
+	State {
+		/// The old state.
+		from: State,
+
+		/// The new state.
+		to: State,
+	},
+
+	/// The priority changed.
+	Priority {
+		/// The old priority.
+		from: Option<Priority>,
+
+		/// The new priority.
+		to: Option<Priority>,
+	},
+
+	/// The creation or completion date changed.
+	DateCompound {
+		/// The old dates.
+		from: Option<DateCompound>,
+
+		/// The new dates.
+		to: Option<DateCompound>,
+	},
+
+	/// The text of the description changed.
+	///
+	/// This is accompanied by changes of the contained projects, contexts
+	/// and custom tags.
+	Description {
+		/// The old description.
+		from: String,
+
+		/// The new description.
+		to: String,
+	},
+
+	/// A project (without `+`) was added.
+	ProjectAdded(String),
+
+	/// A project (without `+`) was removed.
+	ProjectRemoved(String),
+
+	/// A context (without `@`) was added.
+	ContextAdded(String),
+
+	/// A context (without `@`) was removed.
+	ContextRemoved(String),
+
+	/// A custom tag was added.
+	TagAdded {
+		/// The key of the tag.
+		key: String,
+
+		/// The value of the tag.
+		value: String,
+	},
+
+	/// A custom tag was removed.
+	TagRemoved {
+		/// The key of the tag.
+		key: String,
+
+		/// The value of the tag.
+		value: String,
+	},
+}
+
+impl Task {
+	/// Returns all differences from `self` to `other`.
+	///
+	/// Projects, contexts and custom tags are compared as multisets, meaning
+	/// their order within the description is ignored. A changed tag value is
+	/// reported as a removed and an added tag.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::str::FromStr;
+	///
+	/// use tdtxt::{Priority, Task, TaskChange};
+	///
+	/// let old = Task::from_str("(A) Call Mom +Family due:2021-08-01").unwrap();
+	/// let new = Task::from_str("(B) Call Mom +Family due:2021-08-03").unwrap();
+	///
+	/// assert_eq!(
+	///     old.diff(&new),
+	///     [
+	///         TaskChange::Priority {
+	///             from: Some(Priority::A),
+	///             to: Some(Priority::B)
+	///         },
+	///         TaskChange::Description {
+	///             from: "Call Mom +Family due:2021-08-01".into(),
+	///             to: "Call Mom +Family due:2021-08-03".into(),
+	///         },
+	///         TaskChange::TagAdded {
+	///             key: "due".into(),
+	///             value: "2021-08-03".into()
+	///         },
+	///         TaskChange::TagRemoved {
+	///             key: "due".into(),
+	///             value: "2021-08-01".into()
+	///         },
+	///     ]
+	/// );
+	///
+	/// assert!(old.diff(&old).is_empty());
+	/// ```
+	pub fn diff(&self, other: &Self) -> Vec<TaskChange> {
+		let mut changes = Vec::new();
+
+		if self.state != other.state {
+			changes
+				.push(TaskChange::State { from: self.state, to: other.state });
+		}
+
+		if self.priority != other.priority {
+			changes.push(TaskChange::Priority {
+				from: self.priority,
+				to: other.priority,
+			});
+		}
+
+		if self.date_compound != other.date_compound {
+			changes.push(TaskChange::DateCompound {
+				from: self.date_compound,
+				to: other.date_compound,
+			});
+		}
+
+		let (old, new) = (&self.description, &other.description);
+
+		if old.description() == new.description() {
+			return changes;
+		}
+
+		changes.push(TaskChange::Description {
+			from: old.description().to_owned(),
+			to: new.description().to_owned(),
+		});
+
+		diff_components(
+			&mut changes,
+			old.projects().collect(),
+			new.projects().collect(),
+			|project, added| {
+				let project = project.to_owned();
+
+				if added {
+					TaskChange::ProjectAdded(project)
+				} else {
+					TaskChange::ProjectRemoved(project)
+				}
+			},
+		);
+
+		diff_components(
+			&mut changes,
+			old.contexts().collect(),
+			new.contexts().collect(),
+			|context, added| {
+				let context = context.to_owned();
+
+				if added {
+					TaskChange::ContextAdded(context)
+				} else {
+					TaskChange::ContextRemoved(context)
+				}
+			},
+		);
+
+		diff_components(
+			&mut changes,
+			old.custom().collect(),
+			new.custom().collect(),
+			|(key, value), added| {
+				let (key, value) = (key.to_owned(), value.to_owned());
+
+				if added {
+					TaskChange::TagAdded { key, value }
+				} else {
+					TaskChange::TagRemoved { key, value }
+				}
+			},
+		);
+
+		changes
+	}
+}
+
+/// Pushes a change for each component only in `new` (added) followed by
+/// each component only in `old` (removed).
+fn diff_components<T, F>(
+	changes: &mut Vec<TaskChange>,
+	mut old: Vec<T>,
+	new: Vec<T>,
+	mut change: F,
+) where
+	T: PartialEq,
+	F: FnMut(T, bool) -> TaskChange,
+{
+	let mut added = Vec::new();
+
+	for component in new {
+		match old.iter().position(|c| *c == component) {
+			Some(idx) => {
+				let _ = old.remove(idx);
+			}
+			None => added.push(component),
+		}
+	}
+
+	changes.extend(added.into_iter().map(|c| change(c, true)));
+	changes.extend(old.into_iter().map(|c| change(c, false)));
+}
@@ -0,0 +1,2138 @@
+use std::collections::{btree_set, BTreeSet, HashMap};
+use std::ffi::OsString;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::date::{
+	Date, DateCompound, DateOrderError, Days, DUE_TAG, THRESHOLD_TAG,
+};
+use crate::decode::DecodeOptions;
+use crate::dependency::DependencyGraph;
+use crate::description::{Component, Description};
+use crate::filter::Filter;
+use crate::id::{IdGenerator, ID_TAG};
+use crate::priority::Priority;
+use crate::project::is_under;
+use crate::report::ParseReport;
+use crate::sort::SortKey;
+#[cfg(feature = "regex")]
+use crate::span::ByteSpan;
+use crate::state::State;
+use crate::tag::{TagRegistry, TagWarning};
+use crate::task::Task;
+
+/// Returns `true` if `new` differs from `old` or is formatted differently.
+fn is_changed(old: &Task, new: &Task) -> bool {
+	// Normalizing a task drops the original line without changing it
+	old != new
+		|| old.description.source().is_some()
+			!= new.description.source().is_some()
+}
+
+/// Maps each project and context to the indices of the tasks containing it.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct TaskIndex {
+	/// Project name (without the leading `+`) to task indices.
+	projects: HashMap<String, BTreeSet<usize>>,
+
+	/// Context name (without the leading `@`) to task indices.
+	contexts: HashMap<String, BTreeSet<usize>>,
+}
+
+impl TaskIndex {
+	fn build(tasks: &[Task]) -> Self {
+		let mut index = Self::default();
+
+		for (idx, task) in tasks.iter().enumerate() {
+			index.insert(idx, task);
+		}
+
+		index
+	}
+
+	fn insert(&mut self, idx: usize, task: &Task) {
+		for project in task.description.projects() {
+			self.projects.entry(project.to_owned()).or_default().insert(idx);
+		}
+
+		for context in task.description.contexts() {
+			self.contexts.entry(context.to_owned()).or_default().insert(idx);
+		}
+	}
+
+	fn remove(&mut self, idx: usize, task: &Task) {
+		fn remove_from(
+			map: &mut HashMap<String, BTreeSet<usize>>,
+			key: &str,
+			idx: usize,
+		) {
+			if let Some(set) = map.get_mut(key) {
+				set.remove(&idx);
+
+				if set.is_empty() {
+					map.remove(key);
+				}
+			}
+		}
+
+		for project in task.description.projects() {
+			remove_from(&mut self.projects, project, idx);
+		}
+
+		for context in task.description.contexts() {
+			remove_from(&mut self.contexts, context, idx);
+		}
+	}
+}
+
+/// A change to a [`TodoFile`] which is reported to all registered listeners.
+///
+/// See [`TodoFile::subscribe`] for more information.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskEvent<'a> {
+	/// A task was added to the list.
+	Added {
+		/// Index of the new task.
+		index: usize,
+
+		/// The new task.
+		task: &'a Task,
+	},
+
+	/// A task was removed from the list.
+	Removed {
+		/// Index the task had before it was removed.
+		index: usize,
+
+		/// The removed task.
+		task: &'a Task,
+	},
+
+	/// A task of the list was modified or replaced.
+	Modified {
+		/// Index of the modified task.
+		index: usize,
+
+		/// The task before the modification.
+		old: &'a Task,
+
+		/// The task after the modification.
+		new: &'a Task,
+	},
+}
+
+/// Identifies a listener registered with [`TodoFile::subscribe`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ListenerId(u64);
+
+/// A callback which gets notified about changes to a [`TodoFile`].
+type Listener = Box<dyn FnMut(&TaskEvent<'_>)>;
+
+/// All listeners registered on a [`TodoFile`].
+///
+/// Listeners are not considered part of the value of the list, e.g. they are
+/// neither cloned nor compared.
+#[derive(Default)]
+struct Listeners {
+	next_id: u64,
+	listeners: Vec<(ListenerId, Listener)>,
+}
+
+impl Listeners {
+	fn is_empty(&self) -> bool {
+		self.listeners.is_empty()
+	}
+
+	fn emit(&mut self, event: &TaskEvent<'_>) {
+		for (_, listener) in &mut self.listeners {
+			listener(event);
+		}
+	}
+}
+
+impl fmt::Debug for Listeners {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_list()
+			.entries(self.listeners.iter().map(|(id, _)| id))
+			.finish()
+	}
+}
+
+impl Clone for Listeners {
+	fn clone(&self) -> Self {
+		Self::default()
+	}
+}
+
+impl PartialEq for Listeners {
+	fn eq(&self, _: &Self) -> bool {
+		true
+	}
+}
+
+impl Eq for Listeners {}
+
+/// A comparison key for a task which ignores insignificant whitespace and the
+/// order of projects, contexts and custom tags within the description.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct DedupeKey<'a> {
+	state: State,
+	priority: Option<Priority>,
+	date_compound: Option<DateCompound>,
+	words: Vec<&'a str>,
+	tags: Vec<Component<'a>>,
+}
+
+impl<'a> DedupeKey<'a> {
+	fn new(task: &'a Task) -> Self {
+		let mut words = Vec::new();
+		let mut tags = Vec::new();
+
+		for component in task.description.components() {
+			match component {
+				Component::Text(text) => words.extend(text.split_whitespace()),
+				component => tags.push(component),
+			}
+		}
+
+		tags.sort_unstable();
+
+		Self {
+			state: task.state,
+			priority: task.priority,
+			date_compound: task.date_compound,
+			words,
+			tags,
+		}
+	}
+}
+
+/// A callback resolving a conflict between a local and a remote task.
+pub type ResolveFn<'a> = Box<dyn FnMut(&Task, &Task) -> Task + 'a>;
+
+/// Decides how conflicting tasks are resolved by [`TodoFile::merge`].
+///
+/// Two tasks conflict if they have the same description text but differ in
+/// any other component (e.g. one was completed on another device).
+pub enum MergePolicy<'a> {
+	/// Keeps both tasks.
+	Union,
+
+	/// Keeps the task with the newer completion date.
+	///
+	/// A done task is considered newer than an open one and a done task
+	/// without a completion date is considered older than one with it. If both
+	/// are equal in this regard the local task is kept.
+	PreferNewerCompletion,
+
+	/// Replaces the local task with the result of the callback, which is
+	/// called with the local and the remote task (in that order).
+	Resolve(ResolveFn<'a>),
+}
+
+impl MergePolicy<'_> {
+	fn resolve(&mut self, local: &Task, remote: &Task) -> Option<Task> {
+		match self {
+			Self::Union => None,
+			Self::PreferNewerCompletion => {
+				fn completion(task: &Task) -> (bool, Option<&Date>) {
+					(
+						task.state.is_done(),
+						task.date_compound
+							.as_ref()
+							.and_then(|dc| dc.date_completed()),
+					)
+				}
+
+				if completion(remote) > completion(local) {
+					Some(remote.clone())
+				} else {
+					Some(local.clone())
+				}
+			}
+			Self::Resolve(f) => Some(f(local, remote)),
+		}
+	}
+}
+
+impl fmt::Debug for MergePolicy<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Union => f.write_str("Union"),
+			Self::PreferNewerCompletion => {
+				f.write_str("PreferNewerCompletion")
+			}
+			Self::Resolve(_) => f.write_str("Resolve(..)"),
+		}
+	}
+}
+
+/// The line ending used when formatting a [`TodoFile`].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LineEnding {
+	/// Unix style line ending (`\n`).
+	#[default]
+	Lf,
+
+	/// Windows style line ending (`\r\n`).
+	CrLf,
+}
+
+impl LineEnding {
+	/// Detects the dominant line ending of `s`.
+	///
+	/// Returns [`LineEnding::Lf`] if `s` contains no line endings or both
+	/// occur equally often.
+	pub fn detect(s: &str) -> Self {
+		let lf = s.matches('\n').count();
+		let crlf = s.matches("\r\n").count();
+
+		if crlf > lf - crlf {
+			Self::CrLf
+		} else {
+			Self::Lf
+		}
+	}
+
+	/// Returns the string representation of the line ending.
+	pub const fn as_str(&self) -> &'static str {
+		match self {
+			Self::Lf => "\n",
+			Self::CrLf => "\r\n",
+		}
+	}
+}
+
+impl fmt::Display for LineEnding {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+/// A line of a [`TodoFile`] which is not a task.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct OpaqueLine {
+	/// The number of tasks preceding the line.
+	position: usize,
+
+	/// The raw content of the line (without the line ending).
+	line: String,
+}
+
+/// The id generator of a [`TodoFile`].
+///
+/// Like [`Listeners`] the generator is not considered part of the value of the
+/// list.
+#[derive(Default)]
+struct IdGen(Option<Box<dyn IdGenerator>>);
+
+impl fmt::Debug for IdGen {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self.0 {
+			Some(_) => f.write_str("Some(..)"),
+			None => f.write_str("None"),
+		}
+	}
+}
+
+impl Clone for IdGen {
+	fn clone(&self) -> Self {
+		Self::default()
+	}
+}
+
+impl PartialEq for IdGen {
+	fn eq(&self, _: &Self) -> bool {
+		true
+	}
+}
+
+impl Eq for IdGen {}
+
+/// The modification state of a [`TodoFile`].
+///
+/// Like [`Listeners`] the state is not considered part of the value of the
+/// list, e.g. it is ignored when comparing lists.
+#[derive(Debug, Default, Clone, Copy)]
+struct Revision {
+	/// Incremented on every modification.
+	current: u64,
+
+	/// The value of [`Self::current`] when the list was last marked as clean.
+	clean: u64,
+}
+
+impl PartialEq for Revision {
+	fn eq(&self, _: &Self) -> bool {
+		true
+	}
+}
+
+impl Eq for Revision {}
+
+/// A list of [`Task`]s, e.g. the content of a whole `todo.txt` file.
+///
+/// Every task occupies exactly one line.
+///
+/// # Opaque lines
+///
+/// Blank lines and comments (lines starting with `#`) are not parsed as
+/// tasks. They are kept as opaque entries at their position relative to the
+/// surrounding tasks (see [`TodoFile::entries`]), so that formatting a parsed
+/// file reproduces the original input except for edited tasks. Opaque lines
+/// are not counted by [`TodoFile::len`] and do not affect task indices.
+///
+/// # Line endings
+///
+/// Both `\n` and `\r\n` line endings are accepted when parsing. The dominant
+/// one is remembered and used when formatting the file. It can be overridden
+/// with [`TodoFile::set_line_ending`].
+///
+/// # Indexing
+///
+/// An optional index which maps each project and context to the tasks
+/// containing it can be enabled with [`TodoFile::build_index`]. While enabled
+/// it is kept up to date by all mutating functions of the list and is used by
+/// [`TodoFile::tasks_with_project`] and [`TodoFile::tasks_with_context`] to
+/// avoid a full scan per lookup.
+///
+/// # Listeners
+///
+/// Callbacks can be registered with [`TodoFile::subscribe`] to get notified
+/// about every change made through the list api.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::str::FromStr as _;
+///
+/// use tdtxt::TodoFile;
+///
+/// let input = "(A) Thank Mom for the meatballs @phone
+/// (B) Schedule Goodwill pickup +GarageSale @phone
+/// Post signs around the neighborhood +GarageSale";
+///
+/// let mut todo = TodoFile::from_str(input).unwrap();
+/// todo.build_index();
+///
+/// let garage_sale = todo
+///     .tasks_with_project("GarageSale")
+///     .map(|(_, task)| task.description().description())
+///     .collect::<Vec<_>>();
+///
+/// assert_eq!(
+///     garage_sale,
+///     vec![
+///         "Schedule Goodwill pickup +GarageSale @phone",
+///         "Post signs around the neighborhood +GarageSale"
+///     ]
+/// );
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TodoFile {
+	/// All tasks in the order they appear in the file.
+	tasks: Vec<Task>,
+
+	/// All lines which are not tasks, ordered by their position.
+	opaque: Vec<OpaqueLine>,
+
+	/// Set if the parsed input did not end with a line ending.
+	no_trailing_newline: bool,
+
+	/// The line ending used when formatting.
+	line_ending: LineEnding,
+
+	/// Optional project/context index over [`Self::tasks`].
+	index: Option<TaskIndex>,
+
+	/// Callbacks notified about changes to the list.
+	listeners: Listeners,
+
+	/// Generator for the ids of tasks added with [`TodoFile::add`].
+	id_generator: IdGen,
+
+	/// Modification state used for dirty tracking.
+	revision: Revision,
+}
+
+impl TodoFile {
+	/// Creates a new empty list.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Returns all tasks of the list.
+	pub fn tasks(&self) -> &[Task] {
+		&self.tasks
+	}
+
+	/// Returns an iterator over all tasks of the list.
+	pub fn iter(&self) -> std::slice::Iter<'_, Task> {
+		self.tasks.iter()
+	}
+
+	/// Returns the number of tasks in the list.
+	pub const fn len(&self) -> usize {
+		self.tasks.len()
+	}
+
+	/// Returns `true` if the list contains no tasks.
+	pub const fn is_empty(&self) -> bool {
+		self.tasks.is_empty()
+	}
+
+	/// Returns an iterator over all entries (tasks and opaque lines) in the
+	/// order they appear in the file.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::str::FromStr as _;
+	///
+	/// use tdtxt::{Entry, TodoFile};
+	///
+	/// let input = "# Home\nWater plants @home\n\n# Work\nCall boss @phone\n";
+	/// let todo = TodoFile::from_str(input).unwrap();
+	///
+	/// assert_eq!(todo.len(), 2);
+	/// assert_eq!(todo.entries().count(), 5);
+	/// assert!(matches!(todo.entries().next(), Some(Entry::Opaque("# Home"))));
+	/// assert_eq!(todo.to_string(), input);
+	/// ```
+	pub fn entries(&self) -> Entries<'_> {
+		Entries { tasks: &self.tasks, opaque: &self.opaque, task_idx: 0 }
+	}
+
+	/// Returns the line ending used when formatting the list.
+	pub const fn line_ending(&self) -> LineEnding {
+		self.line_ending
+	}
+
+	/// Sets the line ending used when formatting the list.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::str::FromStr as _;
+	///
+	/// use tdtxt::{LineEnding, TodoFile};
+	///
+	/// let mut todo = TodoFile::from_str("Call Mom\r\nBuy milk\r\n").unwrap();
+	/// assert_eq!(todo.line_ending(), LineEnding::CrLf);
+	/// assert_eq!(todo.to_string(), "Call Mom\r\nBuy milk\r\n");
+	///
+	/// todo.set_line_ending(LineEnding::Lf);
+	/// assert_eq!(todo.to_string(), "Call Mom\nBuy milk\n");
+	/// ```
+	pub fn set_line_ending(&mut self, line_ending: LineEnding) {
+		if self.line_ending != line_ending {
+			self.line_ending = line_ending;
+			self.touch();
+		}
+	}
+
+	/// Returns the revision of the list.
+	///
+	/// The revision starts at `0` and is incremented by every modification
+	/// made through the list api.
+	pub const fn revision(&self) -> u64 {
+		self.revision.current
+	}
+
+	/// Returns `true` if the list was modified since it was created or last
+	/// marked as clean with [`TodoFile::mark_clean`].
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::str::FromStr;
+	///
+	/// use tdtxt::{Task, TodoFile};
+	///
+	/// let mut todo = TodoFile::from_str("Call Mom\n").unwrap();
+	/// assert!(!todo.is_dirty());
+	///
+	/// todo.push(Task::from_str("Buy milk").unwrap());
+	/// assert!(todo.is_dirty());
+	/// assert_eq!(todo.revision(), 1);
+	///
+	/// // e.g. after saving
+	/// todo.mark_clean();
+	/// assert!(!todo.is_dirty());
+	/// ```
+	pub const fn is_dirty(&self) -> bool {
+		self.revision.current != self.revision.clean
+	}
+
+	/// Marks the current revision as clean, e.g. after the list was saved.
+	pub const fn mark_clean(&mut self) {
+		self.revision.clean = self.revision.current;
+	}
+
+	/// Returns a checksum of the formatted list.
+	///
+	/// The checksum is equal to [`TodoFile::checksum_of`] the bytes written
+	/// by e.g. [`TodoFile::save_atomic`]. This can be used to cheaply detect
+	/// if a file was changed externally.
+	///
+	/// # Notes
+	///
+	/// The checksum is a non-cryptographic 64-bit FNV-1a hash and is stable
+	/// across platforms and versions of this crate.
+	pub fn checksum(&self) -> u64 {
+		Self::checksum_of(self.to_string().as_bytes())
+	}
+
+	/// Returns the checksum of `bytes` (see [`TodoFile::checksum`]).
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::str::FromStr;
+	///
+	/// use tdtxt::TodoFile;
+	///
+	/// let content = "(A) Call Mom\n# notes\nBuy milk\n";
+	/// let todo = TodoFile::from_str(content).unwrap();
+	///
+	/// assert_eq!(todo.checksum(), TodoFile::checksum_of(content.as_bytes()));
+	/// ```
+	pub fn checksum_of(bytes: &[u8]) -> u64 {
+		const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+		const PRIME: u64 = 0x0100_0000_01b3;
+
+		bytes.iter().fold(OFFSET_BASIS, |hash, &byte| {
+			(hash ^ u64::from(byte)).wrapping_mul(PRIME)
+		})
+	}
+
+	/// Records a modification of the list.
+	const fn touch(&mut self) {
+		self.revision.current = self.revision.current.wrapping_add(1);
+	}
+
+	/// Returns the task at `idx`.
+	pub fn get(&self, idx: usize) -> Option<&Task> {
+		self.tasks.get(idx)
+	}
+
+	/// Appends a task to the end of the list and returns its index.
+	pub fn push(&mut self, task: Task) -> usize {
+		let idx = self.tasks.len();
+
+		if let Some(index) = &mut self.index {
+			index.insert(idx, &task);
+		}
+
+		self.tasks.push(task);
+		self.touch();
+
+		if !self.listeners.is_empty() {
+			self.listeners.emit(&TaskEvent::Added {
+				index: idx,
+				task: &self.tasks[idx],
+			});
+		}
+
+		idx
+	}
+
+	/// Appends a task to the end of the list and returns its index.
+	///
+	/// If an id generator is set (see [`TodoFile::set_id_generator`]) and the
+	/// task has no `id:` tag, a newly generated one is appended to its
+	/// description.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::{SequenceIdGenerator, Task, TodoFile};
+	///
+	/// let mut todo = TodoFile::new();
+	/// todo.set_id_generator(SequenceIdGenerator::new());
+	///
+	/// todo.add(Task::build().build("Call Mom"));
+	/// todo.add(Task::build().build("Buy milk id:7"));
+	/// todo.add(Task::build().build("Water plants"));
+	///
+	/// assert_eq!(todo.to_string(), "Call Mom id:1\nBuy milk id:7\nWater plants id:8\n");
+	/// ```
+	pub fn add(&mut self, mut task: Task) -> usize {
+		if let Some(generator) = &mut self.id_generator.0 {
+			if !task.description.custom().any(|(key, _)| key == ID_TAG) {
+				let id = generator.generate(&self.tasks);
+				task.description.push_word(&format!("{}:{}", ID_TAG, id));
+			}
+		}
+
+		self.push(task)
+	}
+
+	/// Sets the generator used by [`TodoFile::add`] to create ids for new
+	/// tasks.
+	///
+	/// # Notes
+	///
+	/// Like listeners the generator is not cloned together with the list.
+	pub fn set_id_generator<G>(&mut self, generator: G)
+	where
+		G: IdGenerator + 'static,
+	{
+		self.id_generator = IdGen(Some(Box::new(generator)));
+	}
+
+	/// Removes the id generator.
+	pub fn clear_id_generator(&mut self) {
+		self.id_generator = IdGen(None);
+	}
+
+	/// Removes and returns the task at `idx`, shifting all tasks after it.
+	///
+	/// # Notes
+	///
+	/// If the index is enabled it will be rebuilt, as the indices of all
+	/// following tasks change.
+	///
+	/// # Panics
+	///
+	/// Panics if `idx` is out of bounds.
+	pub fn remove(&mut self, idx: usize) -> Task {
+		let task = self.tasks.remove(idx);
+
+		for line in &mut self.opaque {
+			if line.position > idx {
+				line.position -= 1;
+			}
+		}
+
+		if self.index.is_some() {
+			self.index = Some(TaskIndex::build(&self.tasks));
+		}
+
+		self.touch();
+		self.listeners.emit(&TaskEvent::Removed { index: idx, task: &task });
+
+		task
+	}
+
+	/// Sorts the tasks by `keys` (see [`SortKey`]).
+	///
+	/// The sort is stable, e.g. tasks which are equal for all keys keep
+	/// their relative order. Opaque lines keep their position. Listeners are
+	/// notified with a [`TaskEvent::Modified`] for each index whose task
+	/// changed.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::str::FromStr;
+	///
+	/// use tdtxt::{SortKey, TodoFile};
+	///
+	/// let mut todo = TodoFile::from_str(
+	///     "x (A) Call Mom\nBuy milk due:2021-08-02\n(B) Pay rent\n\
+	///      Water plants due:2021-08-01\n",
+	/// )
+	/// .unwrap();
+	///
+	/// todo.sort_by_keys(&[SortKey::State, SortKey::Priority, SortKey::DueDate]);
+	///
+	/// assert_eq!(
+	///     todo.to_string(),
+	///     "(B) Pay rent\nWater plants due:2021-08-01\nBuy milk \
+	///      due:2021-08-02\nx (A) Call Mom\n"
+	/// );
+	/// ```
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(level = "debug", skip_all, fields(keys = ?keys))
+	)]
+	pub fn sort_by_keys(&mut self, keys: &[SortKey]) {
+		let mut order = (0..self.tasks.len()).collect::<Vec<_>>();
+		order.sort_by(|&a, &b| {
+			SortKey::compare_all(keys, &self.tasks[a], &self.tasks[b])
+		});
+
+		if order.iter().enumerate().all(|(idx, &from)| idx == from) {
+			crate::trace_event!(debug, "list already sorted");
+			return;
+		}
+
+		let old = if self.listeners.is_empty() {
+			None
+		} else {
+			Some(self.tasks.clone())
+		};
+
+		let mut tasks = std::mem::take(&mut self.tasks)
+			.into_iter()
+			.map(Some)
+			.collect::<Vec<_>>();
+		self.tasks = order
+			.iter()
+			.map(|&from| tasks[from].take().expect("each index is unique"))
+			.collect();
+
+		if self.index.is_some() {
+			self.index = Some(TaskIndex::build(&self.tasks));
+		}
+
+		self.touch();
+
+		if let Some(old) = old {
+			for (idx, &from) in order.iter().enumerate() {
+				if idx != from {
+					self.listeners.emit(&TaskEvent::Modified {
+						index: idx,
+						old: &old[idx],
+						new: &self.tasks[idx],
+					});
+				}
+			}
+		}
+	}
+
+	/// Retains only the tasks for which `f` returns `true`.
+	///
+	/// Opaque lines keep their position relative to the retained tasks.
+	/// Listeners are notified for each removed task, starting with the last
+	/// one.
+	///
+	/// Returns the number of removed tasks.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::str::FromStr;
+	///
+	/// use tdtxt::TodoFile;
+	///
+	/// let mut todo =
+	///     TodoFile::from_str("Call Mom +Family\n# work\nWrite report +Work\n")
+	///         .unwrap();
+	///
+	/// assert_eq!(todo.retain(|task| task.description.projects().all(|p| p != "Family")), 1);
+	/// assert_eq!(todo.to_string(), "# work\nWrite report +Work\n");
+	/// ```
+	pub fn retain<F>(&mut self, mut f: F) -> usize
+	where
+		F: FnMut(&Task) -> bool,
+	{
+		self.extract(|task| !f(task)).len()
+	}
+
+	/// Removes all done tasks from the list and returns them in order.
+	///
+	/// Opaque lines keep their position relative to the remaining tasks.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::str::FromStr;
+	///
+	/// use tdtxt::TodoFile;
+	///
+	/// let mut todo =
+	///     TodoFile::from_str("x Call Mom\nBuy milk\nx Water plants\n").unwrap();
+	///
+	/// let done = todo.drain_done();
+	///
+	/// assert_eq!(done.len(), 2);
+	/// assert_eq!(done[1].to_string(), "x Water plants");
+	/// assert_eq!(todo.to_string(), "Buy milk\n");
+	/// ```
+	pub fn drain_done(&mut self) -> Vec<Task> {
+		self.extract(|task| task.state == State::Done)
+	}
+
+	/// Removes all tasks for which `f` returns `true` and returns them in
+	/// order.
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(level = "debug", skip_all)
+	)]
+	fn extract<F>(&mut self, mut f: F) -> Vec<Task>
+	where
+		F: FnMut(&Task) -> bool,
+	{
+		let mut kept = Vec::with_capacity(self.tasks.len());
+		// Original index and task
+		let mut extracted = Vec::new();
+		// Number of kept tasks preceding each original position
+		let mut kept_before = Vec::with_capacity(self.tasks.len() + 1);
+
+		for (idx, task) in
+			std::mem::take(&mut self.tasks).into_iter().enumerate()
+		{
+			kept_before.push(kept.len());
+
+			if f(&task) {
+				extracted.push((idx, task));
+			} else {
+				kept.push(task);
+			}
+		}
+
+		kept_before.push(kept.len());
+		self.tasks = kept;
+
+		crate::trace_event!(
+			debug,
+			extracted = extracted.len(),
+			kept = self.tasks.len(),
+			"extracted tasks"
+		);
+
+		if extracted.is_empty() {
+			return Vec::new();
+		}
+
+		for line in &mut self.opaque {
+			line.position = kept_before[line.position];
+		}
+
+		if self.index.is_some() {
+			self.index = Some(TaskIndex::build(&self.tasks));
+		}
+
+		self.touch();
+
+		// Removing from the last to the first keeps the reported indices
+		// valid at the time of each removal.
+		for (idx, task) in extracted.iter().rev() {
+			self.listeners.emit(&TaskEvent::Removed { index: *idx, task });
+		}
+
+		extracted.into_iter().map(|(_, task)| task).collect()
+	}
+
+	/// Replaces the task at `idx` with `task` and returns the old one.
+	///
+	/// Like [`Self::modify`] the list is only marked as modified if `task`
+	/// differs from the old task.
+	///
+	/// # Panics
+	///
+	/// Panics if `idx` is out of bounds.
+	pub fn replace(&mut self, idx: usize, task: Task) -> Task {
+		let old = std::mem::replace(&mut self.tasks[idx], task);
+
+		if let Some(index) = &mut self.index {
+			index.remove(idx, &old);
+			index.insert(idx, &self.tasks[idx]);
+		}
+
+		if is_changed(&old, &self.tasks[idx]) {
+			self.touch();
+			self.listeners.emit(&TaskEvent::Modified {
+				index: idx,
+				old: &old,
+				new: &self.tasks[idx],
+			});
+		}
+
+		old
+	}
+
+	/// Modifies the task at `idx` in place with `f`.
+	///
+	/// The list is only marked as modified (see [`Self::revision`]) and
+	/// listeners are only notified if `f` changed the task.
+	///
+	/// Returns `None` if there is no task at `idx`.
+	pub fn modify<F, R>(&mut self, idx: usize, f: F) -> Option<R>
+	where
+		F: FnOnce(&mut Task) -> R,
+	{
+		let task = self.tasks.get_mut(idx)?;
+		let old = task.clone();
+
+		if let Some(index) = &mut self.index {
+			index.remove(idx, task);
+		}
+
+		let ret = f(task);
+
+		if let Some(index) = &mut self.index {
+			index.insert(idx, task);
+		}
+
+		if is_changed(&old, task) {
+			self.touch();
+			self.listeners.emit(&TaskEvent::Modified {
+				index: idx,
+				old: &old,
+				new: &self.tasks[idx],
+			});
+		}
+
+		Some(ret)
+	}
+
+	/// Shifts the due date (`due:` tag) of all tasks matching `predicate`
+	/// by `days`.
+	///
+	/// This is a shorthand for [`Self::shift_dates`] with the tag
+	/// [`DUE_TAG`](`crate::DUE_TAG`). Returns the number of changed tasks.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::str::FromStr;
+	///
+	/// use tdtxt::{Date, Days, Filter, TodoFile};
+	///
+	/// let mut todo = TodoFile::from_str(
+	///     "Pay rent due:2021-07-30\nCall Mom due:2021-08-15\n",
+	/// )
+	/// .unwrap();
+	///
+	/// let overdue = Filter::overdue(Date::from_ymd(2021, 8, 1));
+	///
+	/// assert_eq!(todo.postpone(|task| overdue.matches(task), Days(7)), 1);
+	/// assert_eq!(
+	///     todo.to_string(),
+	///     "Pay rent due:2021-08-06\nCall Mom due:2021-08-15\n"
+	/// );
+	/// ```
+	pub fn postpone<P>(&mut self, predicate: P, days: Days) -> usize
+	where
+		P: FnMut(&Task) -> bool,
+	{
+		self.shift_dates(predicate, days, &[DUE_TAG])
+	}
+
+	/// Shifts all date-valued custom tags with a key in `tags` (e.g.
+	/// [`DUE_TAG`](`crate::DUE_TAG`) and
+	/// [`THRESHOLD_TAG`](`crate::THRESHOLD_TAG`)) of all tasks matching
+	/// `predicate` by `days`.
+	///
+	/// Tag values which are not valid dates or which would be shifted out of
+	/// range are left untouched. Returns the number of changed tasks.
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(level = "debug", skip_all, fields(days = days.0, tags = ?tags))
+	)]
+	pub fn shift_dates<P>(
+		&mut self,
+		mut predicate: P,
+		days: Days,
+		tags: &[&str],
+	) -> usize
+	where
+		P: FnMut(&Task) -> bool,
+	{
+		let shift = |value: &str| {
+			Date::from_str(value)
+				.ok()?
+				.checked_add_days(days)
+				.map(|date| date.to_string())
+		};
+
+		let candidates = self
+			.tasks
+			.iter()
+			.enumerate()
+			.filter(|(_, task)| predicate(task))
+			.filter(|(_, task)| {
+				task.description.custom().any(|(key, value)| {
+					tags.contains(&key) && shift(value).is_some()
+				})
+			})
+			.map(|(idx, _)| idx)
+			.collect::<Vec<_>>();
+
+		for &idx in &candidates {
+			let _ = self.modify(idx, |task| {
+				for tag in tags {
+					task.description.replace_tag_values(tag, shift);
+				}
+			});
+		}
+
+		crate::trace_event!(
+			debug,
+			shifted = candidates.len(),
+			"shifted dates"
+		);
+
+		candidates.len()
+	}
+
+	/// Rewrites dates in alternative formats (see [`Date::parse_lenient`])
+	/// into the canonical `yyyy-mm-dd` format.
+	///
+	/// This includes:
+	///
+	/// - Creation/completion dates at the start of the description of tasks
+	///   without a (canonical) date compound. These are moved into the date
+	///   compound of the task.
+	/// - The values of the date-valued tags
+	///   [`DUE_TAG`](`crate::DUE_TAG`) and
+	///   [`THRESHOLD_TAG`](`crate::THRESHOLD_TAG`).
+	///
+	/// Returns a report of all rewritten dates.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::str::FromStr;
+	///
+	/// use tdtxt::{Date, DateField, TodoFile};
+	///
+	/// let mut todo = TodoFile::from_str(
+	///     "x 2021/8/1 2021/7/1 Call Mom\nBuy milk due:20210805\n",
+	/// )
+	/// .unwrap();
+	///
+	/// let report = todo.normalize_dates();
+	///
+	/// assert_eq!(report.len(), 3);
+	/// assert_eq!(report[0].field, DateField::Completed);
+	/// assert_eq!(report[0].original, "2021/8/1");
+	/// assert_eq!(report[2].field, DateField::Tag("due".into()));
+	/// assert_eq!(report[2].normalized, Date::from_ymd(2021, 8, 5));
+	///
+	/// assert_eq!(
+	///     todo.to_string(),
+	///     "x 2021-08-01 2021-07-01 Call Mom\nBuy milk due:2021-08-05\n"
+	/// );
+	/// ```
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(level = "debug", skip_all)
+	)]
+	pub fn normalize_dates(&mut self) -> Vec<DateNormalization> {
+		let mut report = Vec::new();
+
+		for idx in 0..self.tasks.len() {
+			if let Some(task) =
+				normalize_task_dates(&self.tasks[idx], idx, &mut report)
+			{
+				let _ = self.replace(idx, task);
+			}
+		}
+
+		crate::trace_event!(
+			debug,
+			normalized = report.len(),
+			"normalized dates"
+		);
+
+		report
+	}
+
+	/// Removes all tasks and opaque lines from the list.
+	///
+	/// Listeners are notified for each task, starting with the last one.
+	pub fn clear(&mut self) {
+		if self.tasks.is_empty() && self.opaque.is_empty() {
+			return;
+		}
+
+		self.touch();
+		self.opaque.clear();
+
+		while let Some(task) = self.tasks.pop() {
+			let idx = self.tasks.len();
+			self.listeners
+				.emit(&TaskEvent::Removed { index: idx, task: &task });
+		}
+
+		if let Some(index) = &mut self.index {
+			*index = TaskIndex::default();
+		}
+	}
+
+	/// Merges the tasks of `other` into this list.
+	///
+	/// Tasks of `other` which are already contained in this list are skipped
+	/// and tasks with a new description text are appended. All other tasks
+	/// conflict with a local task of the same description text and are
+	/// resolved with `policy`.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::str::FromStr as _;
+	///
+	/// use tdtxt::{MergePolicy, TodoFile};
+	///
+	/// let mut local = TodoFile::from_str("(A) Call Mom\nBuy milk").unwrap();
+	/// let remote =
+	///     TodoFile::from_str("x 2021-09-01 2021-08-30 Call Mom\nBuy eggs").unwrap();
+	///
+	/// local.merge(&remote, MergePolicy::PreferNewerCompletion);
+	///
+	/// assert_eq!(
+	///     local.to_string(),
+	///     "x 2021-09-01 2021-08-30 Call Mom\nBuy milk\nBuy eggs"
+	/// );
+	/// ```
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(level = "debug", skip_all, fields(local = self.len(), remote = other.len()))
+	)]
+	pub fn merge(&mut self, other: &Self, mut policy: MergePolicy<'_>) {
+		for remote in other {
+			if self.tasks.contains(remote) {
+				continue;
+			}
+
+			let conflict = self.tasks.iter().position(|local| {
+				local.description.description()
+					== remote.description.description()
+			});
+
+			match conflict {
+				Some(idx) => match policy.resolve(&self.tasks[idx], remote) {
+					Some(task) if task != self.tasks[idx] => {
+						let _ = self.replace(idx, task);
+					}
+					Some(_) => {}
+					None => {
+						let _ = self.push(remote.clone());
+					}
+				},
+				None => {
+					let _ = self.push(remote.clone());
+				}
+			}
+		}
+	}
+
+	/// Returns an iterator over all tasks which are duplicates of an earlier
+	/// task in the list.
+	///
+	/// Two tasks are considered duplicates if they are equal after ignoring
+	/// insignificant whitespace in the description and the order of its
+	/// projects, contexts and custom tags.
+	///
+	/// The iterator yields the index of the duplicate and the index of the
+	/// first task it duplicates (in that order).
+	pub fn duplicates(&self) -> Duplicates {
+		let mut first = HashMap::with_capacity(self.tasks.len());
+		let mut duplicates = Vec::new();
+
+		for (idx, task) in self.tasks.iter().enumerate() {
+			let original = *first.entry(DedupeKey::new(task)).or_insert(idx);
+
+			if original != idx {
+				duplicates.push((idx, original));
+			}
+		}
+
+		Duplicates { inner: duplicates.into_iter() }
+	}
+
+	/// Removes all duplicates (see [`TodoFile::duplicates`]) from the list,
+	/// keeping the first occurrence of each task.
+	///
+	/// Returns the number of removed tasks.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::str::FromStr as _;
+	///
+	/// use tdtxt::TodoFile;
+	///
+	/// let mut todo = TodoFile::from_str(
+	///     "Call Mom @phone +Family\nBuy milk\nCall  Mom +Family @phone",
+	/// )
+	/// .unwrap();
+	///
+	/// assert_eq!(todo.dedupe(), 1);
+	/// assert_eq!(todo.to_string(), "Call Mom @phone +Family\nBuy milk");
+	/// ```
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(level = "debug", skip_all)
+	)]
+	pub fn dedupe(&mut self) -> usize {
+		let mut duplicates =
+			self.duplicates().map(|(idx, _)| idx).collect::<Vec<_>>();
+		duplicates.sort_unstable();
+
+		for &idx in duplicates.iter().rev() {
+			let _ = self.remove(idx);
+		}
+
+		crate::trace_event!(
+			debug,
+			removed = duplicates.len(),
+			"removed duplicates"
+		);
+
+		duplicates.len()
+	}
+
+	/// Registers a listener which gets called for every change made through
+	/// the list api.
+	///
+	/// The returned id can be used to remove the listener again with
+	/// [`TodoFile::unsubscribe`].
+	///
+	/// # Notes
+	///
+	/// Listeners are not cloned together with the list and are ignored when
+	/// comparing lists.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::cell::RefCell;
+	/// use std::rc::Rc;
+	///
+	/// use tdtxt::{Task, TaskEvent, TodoFile};
+	///
+	/// let added = Rc::new(RefCell::new(Vec::new()));
+	/// let added_clone = Rc::clone(&added);
+	///
+	/// let mut todo = TodoFile::new();
+	/// let id = todo.subscribe(move |event| {
+	///     if let TaskEvent::Added { task, .. } = event {
+	///         added_clone.borrow_mut().push(task.to_string());
+	///     }
+	/// });
+	///
+	/// todo.push(Task::build().build("Call Mom"));
+	/// assert!(todo.unsubscribe(id));
+	/// todo.push(Task::build().build("Call Dad"));
+	///
+	/// assert_eq!(*added.borrow(), vec!["Call Mom"]);
+	/// ```
+	pub fn subscribe<F>(&mut self, listener: F) -> ListenerId
+	where
+		F: FnMut(&TaskEvent<'_>) + 'static,
+	{
+		let id = ListenerId(self.listeners.next_id);
+		self.listeners.next_id += 1;
+		self.listeners.listeners.push((id, Box::new(listener)));
+		id
+	}
+
+	/// Removes the listener with the id `id`.
+	///
+	/// Returns `false` if no listener with that id was registered.
+	pub fn unsubscribe(&mut self, id: ListenerId) -> bool {
+		let len = self.listeners.listeners.len();
+		self.listeners.listeners.retain(|(listener_id, _)| *listener_id != id);
+		len != self.listeners.listeners.len()
+	}
+
+	/// Builds the project/context index and keeps it up to date on all
+	/// following mutations.
+	pub fn build_index(&mut self) {
+		self.index = Some(TaskIndex::build(&self.tasks));
+	}
+
+	/// Drops the project/context index.
+	pub fn drop_index(&mut self) {
+		self.index = None;
+	}
+
+	/// Returns `true` if the project/context index is enabled.
+	pub const fn is_indexed(&self) -> bool {
+		self.index.is_some()
+	}
+
+	/// Returns the names of all distinct projects of all tasks, sorted
+	/// alphabetically.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::str::FromStr;
+	///
+	/// use tdtxt::TodoFile;
+	///
+	/// let todo =
+	///     TodoFile::from_str("Call Mom +Family @phone\nPaint +House +Family\n")
+	///         .unwrap();
+	///
+	/// assert!(todo.projects().into_iter().eq(["Family", "House"]));
+	/// assert!(todo.contexts().into_iter().eq(["phone"]));
+	/// ```
+	pub fn projects(&self) -> BTreeSet<&str> {
+		self.tasks
+			.iter()
+			.flat_map(|task| task.description.projects())
+			.collect()
+	}
+
+	/// Returns the names of all distinct contexts of all tasks, sorted
+	/// alphabetically.
+	pub fn contexts(&self) -> BTreeSet<&str> {
+		self.tasks
+			.iter()
+			.flat_map(|task| task.description.contexts())
+			.collect()
+	}
+
+	/// Builds the dependency graph of the tasks (see [`DependencyGraph`]).
+	pub fn dependency_graph(&self) -> DependencyGraph {
+		DependencyGraph::new(&self.tasks)
+	}
+
+	/// Returns an iterator over all tasks (and their indices) which are
+	/// matched by `filter` (see [`Filter`]).
+	pub fn filter(&self, filter: &Filter) -> TaskMatches<'_> {
+		TaskMatches::scanned(self, |task| filter.matches(task))
+	}
+
+	/// Validates the custom tags of all tasks with `registry` and returns
+	/// the index of the task together with each warning.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::str::FromStr;
+	///
+	/// use tdtxt::{TagRegistry, TodoFile};
+	///
+	/// let file = TodoFile::from_str("Call Mom due:2021-08-01\nPay rent due:soon\n")
+	///     .unwrap();
+	///
+	/// let warnings = file.validate_tags(&TagRegistry::with_known_tags());
+	///
+	/// assert_eq!(warnings.len(), 1);
+	/// assert_eq!(warnings[0].0, 1);
+	/// assert_eq!(warnings[0].1.value, "soon");
+	/// ```
+	pub fn validate_tags(
+		&self,
+		registry: &TagRegistry,
+	) -> Vec<(usize, TagWarning)> {
+		self.tasks
+			.iter()
+			.enumerate()
+			.flat_map(|(idx, task)| {
+				registry
+					.validate(&task.description)
+					.into_iter()
+					.map(move |warning| (idx, warning))
+			})
+			.collect()
+	}
+
+	/// Returns an iterator over all tasks whose description is matched by
+	/// `regex`, together with the spans of the matches (see
+	/// [`Description::find_regex`]).
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::str::FromStr;
+	///
+	/// use regex::Regex;
+	/// use tdtxt::TodoFile;
+	///
+	/// let file =
+	///     TodoFile::from_str("Call Mom\n(A) Pay rent\nCall Dad\n").unwrap();
+	/// let regex = Regex::new("(Mom|Dad)$").unwrap();
+	///
+	/// let matches = file.grep(&regex).collect::<Vec<_>>();
+	///
+	/// assert_eq!(matches.len(), 2);
+	/// assert_eq!(matches[1].index, 2);
+	/// assert_eq!(matches[1].spans[0].to_range_usize(), 5..8);
+	/// ```
+	#[cfg(feature = "regex")]
+	pub fn grep<'a>(
+		&'a self,
+		regex: &'a regex::Regex,
+	) -> impl Iterator<Item = GrepMatch<'a>> + 'a {
+		self.tasks.iter().enumerate().filter_map(move |(index, task)| {
+			let spans = task.description.find_regex(regex).collect::<Vec<_>>();
+
+			if spans.is_empty() {
+				None
+			} else {
+				Some(GrepMatch { index, task, spans })
+			}
+		})
+	}
+
+	/// Returns an iterator over all tasks (and their indices) which contain
+	/// the project `project` (with or without the leading `+`).
+	///
+	/// # Notes
+	///
+	/// If the index is not enabled this will scan the whole list.
+	pub fn tasks_with_project(&self, project: &str) -> TaskMatches<'_> {
+		let project = project.strip_prefix('+').unwrap_or(project);
+
+		match &self.index {
+			Some(index) => {
+				TaskMatches::indexed(self, index.projects.get(project))
+			}
+			None => TaskMatches::scanned(self, |task| {
+				task.description.has_project(project)
+			}),
+		}
+	}
+
+	/// Returns an iterator over all tasks (and their indices) which contain
+	/// the project `project` (with or without the leading `+`) or any project
+	/// nested below it (see [`ProjectPath`](`crate::ProjectPath`)).
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::str::FromStr;
+	///
+	/// use tdtxt::TodoFile;
+	///
+	/// let file = TodoFile::from_str(
+	///     "Plan +Work\nWrite +Work/Reports/Q3\nBuy milk\nFix bike +Workshop\n",
+	/// )
+	/// .unwrap();
+	///
+	/// let indices = file
+	///     .tasks_under_project("+Work")
+	///     .map(|(idx, _)| idx)
+	///     .collect::<Vec<_>>();
+	///
+	/// assert_eq!(indices, [0, 1]);
+	/// ```
+	///
+	/// # Notes
+	///
+	/// If the index is enabled only the indexed project names are scanned.
+	pub fn tasks_under_project(&self, project: &str) -> TaskMatches<'_> {
+		let project = project.strip_prefix('+').unwrap_or(project);
+
+		match &self.index {
+			Some(index) => {
+				let indices = index
+					.projects
+					.iter()
+					.filter(|(name, _)| is_under(name, project))
+					.flat_map(|(_, indices)| indices.iter().copied())
+					.collect::<BTreeSet<_>>();
+
+				TaskMatches::collected(self, indices.into_iter().collect())
+			}
+			None => TaskMatches::scanned(self, |task| {
+				task.description.has_project_under(project)
+			}),
+		}
+	}
+
+	/// Returns an iterator over all tasks (and their indices) which contain
+	/// the context `context` (with or without the leading `@`).
+	///
+	/// # Notes
+	///
+	/// If the index is not enabled this will scan the whole list.
+	pub fn tasks_with_context(&self, context: &str) -> TaskMatches<'_> {
+		let context = context.strip_prefix('@').unwrap_or(context);
+
+		match &self.index {
+			Some(index) => {
+				TaskMatches::indexed(self, index.contexts.get(context))
+			}
+			None => TaskMatches::scanned(self, |task| {
+				task.description.has_context(context)
+			}),
+		}
+	}
+}
+
+impl fmt::Display for TodoFile {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let mut entries = self.entries().peekable();
+
+		while let Some(entry) = entries.next() {
+			fmt::Display::fmt(&entry, f)?;
+
+			if entries.peek().is_some() || !self.no_trailing_newline {
+				f.write_str(self.line_ending.as_str())?;
+			}
+		}
+
+		Ok(())
+	}
+}
+
+impl From<Vec<Task>> for TodoFile {
+	fn from(value: Vec<Task>) -> Self {
+		Self { tasks: value, ..Self::default() }
+	}
+}
+
+impl From<TodoFile> for Vec<Task> {
+	fn from(value: TodoFile) -> Self {
+		value.tasks
+	}
+}
+
+impl std::iter::FromIterator<Task> for TodoFile {
+	fn from_iter<T: IntoIterator<Item = Task>>(iter: T) -> Self {
+		Self::from(iter.into_iter().collect::<Vec<_>>())
+	}
+}
+
+impl Extend<Task> for TodoFile {
+	fn extend<T: IntoIterator<Item = Task>>(&mut self, iter: T) {
+		for task in iter {
+			let _ = self.push(task);
+		}
+	}
+}
+
+impl IntoIterator for TodoFile {
+	type IntoIter = std::vec::IntoIter<Task>;
+	type Item = Task;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.tasks.into_iter()
+	}
+}
+
+impl<'a> IntoIterator for &'a TodoFile {
+	type IntoIter = std::slice::Iter<'a, Task>;
+	type Item = &'a Task;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.tasks.iter()
+	}
+}
+
+crate::parse_error!(ParseTodoFileError: "todo file");
+
+impl TodoFile {
+	/// Parses a todo file from raw bytes.
+	///
+	/// The bytes are decoded according to `options` first. Lines dropped by
+	/// [`InvalidUtf8Policy::SkipLine`](`crate::InvalidUtf8Policy::SkipLine`)
+	/// are not preserved.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::{DecodeOptions, InvalidUtf8Policy, TodoFile};
+	///
+	/// let bytes = b"\xEF\xBB\xBFBuy milk\nCall \xFFmom\nClean up\n";
+	///
+	/// assert!(TodoFile::from_bytes(bytes, DecodeOptions::new()).is_err());
+	///
+	/// let todo = TodoFile::from_bytes(
+	///     bytes,
+	///     DecodeOptions::new().invalid_utf8(InvalidUtf8Policy::SkipLine),
+	/// )
+	/// .unwrap();
+	///
+	/// assert_eq!(todo.to_string(), "Buy milk\nClean up\n");
+	/// ```
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(level = "debug", skip_all, fields(bytes = bytes.len()))
+	)]
+	pub fn from_bytes(
+		bytes: &[u8],
+		options: DecodeOptions,
+	) -> Result<Self, ParseTodoFileError> {
+		let (s, skipped) = options.decode_lines(bytes).map_err(|line| {
+			ParseTodoFileError::with_msg(format!(
+				"invalid utf-8 on line {}",
+				line
+			))
+		})?;
+		let mut file = Self::from_str(&s)?;
+
+		for task in &mut file.tasks {
+			task.description.set_options(options.description);
+		}
+
+		if options.validate_dates {
+			file.check_date_order().map_err(|(line, err)| {
+				ParseTodoFileError::with_msg(format!(
+					"{} on line {}",
+					err,
+					crate::decode::original_line(line, &skipped)
+				))
+			})?;
+		}
+
+		Ok(file)
+	}
+
+	/// Checks that no task has a completion date before its creation date
+	/// (see [`Task::check_date_order`]).
+	///
+	/// # Errors
+	///
+	/// Returns the one-indexed line number and the error of the first such
+	/// task.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::str::FromStr;
+	///
+	/// use tdtxt::TodoFile;
+	///
+	/// let todo = TodoFile::from_str(concat!(
+	///     "# notes\n",
+	///     "x 2021-08-02 2021-08-01 Call Mom\n",
+	///     "x 2021-07-01 2021-08-01 Pay rent\n",
+	/// ))
+	/// .unwrap();
+	///
+	/// let (line, err) = todo.check_date_order().unwrap_err();
+	///
+	/// assert_eq!(line, 3);
+	/// assert_eq!(
+	///     err.to_string(),
+	///     "completion date 2021-07-01 is before creation date 2021-08-01"
+	/// );
+	/// ```
+	pub fn check_date_order(&self) -> Result<(), (usize, DateOrderError)> {
+		self.entries().enumerate().try_for_each(|(idx, entry)| match entry {
+			Entry::Task(task) => {
+				task.check_date_order().map_err(|err| (idx + 1, err))
+			}
+			Entry::Opaque(_) => Ok(()),
+		})
+	}
+
+	/// Parses a todo file from `s` and collects statistics about its content
+	/// (see [`ParseReport`]).
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::TodoFile;
+	///
+	/// let (todo, report) = TodoFile::parse_with_report(
+	///     "(a) Call Mom +Family\n# notes\n(B) Buy milk @store\n",
+	/// )
+	/// .unwrap();
+	///
+	/// assert_eq!(todo.len(), 2);
+	/// assert_eq!(report.lines, 3);
+	/// assert_eq!(report.comments, 1);
+	/// assert_eq!(report.with_priority, 1);
+	/// assert_eq!(report.lowercase_priority_hints, 1);
+	/// ```
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(level = "debug", skip_all, fields(bytes = s.len()))
+	)]
+	pub fn parse_with_report(
+		s: &str,
+	) -> Result<(Self, ParseReport), ParseTodoFileError> {
+		let todo = Self::from_str(s)?;
+		let report = ParseReport::collect(&todo);
+
+		Ok((todo, report))
+	}
+
+	/// Atomically writes the file to `path`.
+	///
+	/// The content is first written to a temporary file next to `path`,
+	/// which is then renamed to `path`. This way `path` either contains the
+	/// old or the new content, even if the process crashes mid-write. The
+	/// permissions of an existing file at `path` are kept and on unix the
+	/// directory is synced after the rename.
+	///
+	/// # Errors
+	///
+	/// Returns an error if the temporary file can not be written or renamed.
+	/// In which case the temporary file is removed (on a best-effort basis).
+	///
+	/// # Notes
+	///
+	/// The list is not marked as clean, see [`TodoFile::mark_clean`].
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(level = "debug", skip_all, fields(path = ?path.as_ref()))
+	)]
+	pub fn save_atomic<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+		/// Distinguishes the temporary files of concurrent saves within the
+		/// same process.
+		static TMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+		let path = path.as_ref();
+
+		let file_name = path.file_name().ok_or_else(|| {
+			io::Error::new(
+				io::ErrorKind::InvalidInput,
+				"path does not point to a file",
+			)
+		})?;
+
+		let mut tmp_name = OsString::from(".");
+		tmp_name.push(file_name);
+		tmp_name.push(format!(
+			".{}.{}.tmp",
+			std::process::id(),
+			TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+		));
+		let tmp_path = path.with_file_name(tmp_name);
+
+		let write = || -> io::Result<()> {
+			let content = self.to_string();
+
+			let mut file = fs::OpenOptions::new()
+				.write(true)
+				.create_new(true)
+				.open(&tmp_path)?;
+
+			if let Ok(metadata) = fs::metadata(path) {
+				file.set_permissions(metadata.permissions())?;
+			}
+
+			file.write_all(content.as_bytes())?;
+			file.sync_all()?;
+			fs::rename(&tmp_path, path)?;
+
+			// Persist the rename itself
+			#[cfg(unix)]
+			{
+				let dir = path
+					.parent()
+					.filter(|dir| !dir.as_os_str().is_empty())
+					.unwrap_or_else(|| Path::new("."));
+
+				fs::File::open(dir)?.sync_all()?;
+			}
+
+			crate::trace_event!(debug, bytes = content.len(), "saved file");
+
+			Ok(())
+		};
+
+		write().inspect_err(|_err| {
+			crate::trace_event!(
+				warn,
+				error = %_err,
+				tmp_path = ?tmp_path,
+				"failed to save file, removing temporary file"
+			);
+
+			let _ = fs::remove_file(&tmp_path);
+		})
+	}
+}
+
+impl FromStr for TodoFile {
+	type Err = ParseTodoFileError;
+
+	#[cfg_attr(
+		feature = "tracing",
+		tracing::instrument(level = "debug", skip_all, fields(bytes = s.len()))
+	)]
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let mut file = Self::new();
+		file.line_ending = LineEnding::detect(s);
+
+		for (idx, line) in s.lines().enumerate() {
+			let trimmed = line.trim_start();
+
+			if trimmed.is_empty() || trimmed.starts_with('#') {
+				file.opaque.push(OpaqueLine {
+					position: file.tasks.len(),
+					line: line.to_owned(),
+				});
+			} else {
+				let task = Task::from_str(line).map_err(|_| {
+					crate::trace_event!(
+						debug,
+						line = idx + 1,
+						"failed to parse task"
+					);
+
+					ParseTodoFileError::with_msg(format!(
+						"invalid task on line {}",
+						idx + 1
+					))
+				})?;
+
+				file.tasks.push(task);
+			}
+		}
+
+		file.no_trailing_newline = !s.is_empty() && !s.ends_with('\n');
+
+		crate::trace_event!(
+			debug,
+			tasks = file.tasks.len(),
+			opaque = file.opaque.len(),
+			line_ending = ?file.line_ending,
+			"parsed file"
+		);
+
+		Ok(file)
+	}
+}
+
+/// The date-valued tags rewritten by [`TodoFile::normalize_dates`].
+const DATE_TAGS: &[&str] = &[DUE_TAG, THRESHOLD_TAG];
+
+/// The location of a date rewritten by [`TodoFile::normalize_dates`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DateField {
+	/// The creation date of the task.
+	Created,
+
+	/// The completion date of the task.
+	Completed,
+
+	/// The value of the custom tag with the contained key.
+	Tag(String),
+}
+
+/// A single date rewritten by [`TodoFile::normalize_dates`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DateNormalization {
+	/// The index of the task containing the date.
+	pub index: usize,
+
+	/// The location of the date within the task.
+	pub field: DateField,
+
+	/// The date as it was written before.
+	pub original: String,
+
+	/// The parsed date, which is now written in the canonical format.
+	pub normalized: Date,
+}
+
+/// Returns the normalized task if any of its dates were rewritten.
+///
+/// All rewritten dates are appended to `report`.
+fn normalize_task_dates(
+	task: &Task,
+	index: usize,
+	report: &mut Vec<DateNormalization>,
+) -> Option<Task> {
+	/// Parses a date which is not in the canonical format.
+	fn parse_alternative(s: &str) -> Option<Date> {
+		if Date::from_str(s).is_ok() {
+			None
+		} else {
+			Date::parse_lenient(s)
+		}
+	}
+
+	/// Splits `s` into the first word and the remaining text.
+	fn split_word(s: &str) -> (&str, &str) {
+		match s.find(char::is_whitespace) {
+			Some(idx) => {
+				let (word, rest) = s.split_at(idx);
+				(word, rest.trim_start())
+			}
+			None => (s, ""),
+		}
+	}
+
+	let mut normalized = None;
+	let mut push = |field, original: &str, date| {
+		report.push(DateNormalization {
+			index,
+			field,
+			original: original.to_owned(),
+			normalized: date,
+		});
+	};
+
+	if task.date_compound.is_none() {
+		let (first, rest) = split_word(task.description.description());
+
+		if let Some(date1) = Date::parse_lenient(first) {
+			let (second, remainder) = split_word(rest);
+
+			// Same as the parser: a second date makes the first one the
+			// completion date. A task needs a non empty description.
+			let converted = match Date::parse_lenient(second) {
+				Some(date2) if !remainder.is_empty() => {
+					push(DateField::Completed, first, date1);
+					push(DateField::Created, second, date2);
+					Some((DateCompound::completed(date2, date1), remainder))
+				}
+				None if !rest.is_empty() => {
+					push(DateField::Created, first, date1);
+					Some((DateCompound::created(date1), rest))
+				}
+				_ => None,
+			};
+
+			if let Some((date_compound, description)) = converted {
+				let mut task = task.clone();
+				task.date_compound = Some(date_compound);
+				task.description = Description::new(description);
+				normalized = Some(task);
+			}
+		}
+	}
+
+	let needs_rewrite = task.description.custom().any(|(key, value)| {
+		DATE_TAGS.contains(&key) && parse_alternative(value).is_some()
+	});
+
+	if needs_rewrite {
+		let task = normalized.get_or_insert_with(|| task.clone());
+
+		for &tag in DATE_TAGS {
+			let _ = task.description.replace_tag_values(tag, |value| {
+				let date = parse_alternative(value)?;
+				push(DateField::Tag(tag.to_owned()), value, date);
+				Some(date.to_string())
+			});
+		}
+	}
+
+	normalized
+}
+
+/// A single entry of a [`TodoFile`].
+///
+/// This is returned by the iterator [`Entries`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Entry<'a> {
+	/// A task.
+	Task(&'a Task),
+
+	/// A line which is not a task, e.g. a blank line or a comment.
+	Opaque(&'a str),
+}
+
+impl fmt::Display for Entry<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Task(task) => fmt::Display::fmt(task, f),
+			Self::Opaque(line) => f.write_str(line),
+		}
+	}
+}
+
+/// An iterator over all entries of a [`TodoFile`].
+///
+/// This iterator is returned by calling [`TodoFile::entries`].
+#[derive(Debug, Clone)]
+pub struct Entries<'a> {
+	tasks: &'a [Task],
+	opaque: &'a [OpaqueLine],
+	task_idx: usize,
+}
+
+impl<'a> Iterator for Entries<'a> {
+	type Item = Entry<'a>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		match self.opaque.first() {
+			Some(line) if line.position <= self.task_idx => {
+				self.opaque = &self.opaque[1..];
+				Some(Entry::Opaque(&line.line))
+			}
+			_ => {
+				let task = self.tasks.get(self.task_idx)?;
+				self.task_idx += 1;
+				Some(Entry::Task(task))
+			}
+		}
+	}
+}
+
+/// A task of a [`TodoFile`] matched by [`TodoFile::grep`].
+#[cfg(feature = "regex")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrepMatch<'a> {
+	/// The index of the task within the file.
+	pub index: usize,
+
+	/// The matched task.
+	pub task: &'a Task,
+
+	/// The spans of all matches within the text of the description.
+	pub spans: Vec<ByteSpan>,
+}
+
+/// The storage for the matching indices of [`TaskMatches`].
+#[derive(Debug, Clone)]
+enum MatchIndices<'a> {
+	/// Indices taken from the project/context index.
+	Indexed(Option<btree_set::Iter<'a, usize>>),
+
+	/// Indices collected by scanning the whole list.
+	Scanned(std::vec::IntoIter<usize>),
+}
+
+/// An iterator over all tasks (and their indices) of a [`TodoFile`] which
+/// contain a specific project or context.
+///
+/// This iterator is returned by calling [`TodoFile::tasks_with_project`],
+/// [`TodoFile::tasks_under_project`],
+/// [`TodoFile::tasks_with_context`] or [`TodoFile::filter`].
+#[derive(Debug, Clone)]
+pub struct TaskMatches<'a> {
+	tasks: &'a [Task],
+	indices: MatchIndices<'a>,
+}
+
+impl<'a> TaskMatches<'a> {
+	fn indexed(file: &'a TodoFile, set: Option<&'a BTreeSet<usize>>) -> Self {
+		Self {
+			tasks: &file.tasks,
+			indices: MatchIndices::Indexed(set.map(BTreeSet::iter)),
+		}
+	}
+
+	fn scanned<P>(file: &'a TodoFile, predicate: P) -> Self
+	where
+		P: Fn(&Task) -> bool,
+	{
+		let indices = file
+			.tasks
+			.iter()
+			.enumerate()
+			.filter(|(_, task)| predicate(task))
+			.map(|(idx, _)| idx)
+			.collect::<Vec<_>>();
+
+		Self::collected(file, indices)
+	}
+
+	fn collected(file: &'a TodoFile, indices: Vec<usize>) -> Self {
+		Self {
+			tasks: &file.tasks,
+			indices: MatchIndices::Scanned(indices.into_iter()),
+		}
+	}
+}
+
+impl<'a> Iterator for TaskMatches<'a> {
+	type Item = (usize, &'a Task);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let idx = match &mut self.indices {
+			MatchIndices::Indexed(iter) => *iter.as_mut()?.next()?,
+			MatchIndices::Scanned(iter) => iter.next()?,
+		};
+
+		Some((idx, &self.tasks[idx]))
+	}
+}
+
+/// An iterator over all duplicate tasks of a [`TodoFile`].
+///
+/// This iterator is returned by calling [`TodoFile::duplicates`].
+#[derive(Debug, Clone)]
+pub struct Duplicates {
+	inner: std::vec::IntoIter<(usize, usize)>,
+}
+
+impl Iterator for Duplicates {
+	type Item = (usize, usize);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.inner.next()
+	}
+}
@@ -0,0 +1,477 @@
+use std::convert::TryFrom;
+use std::ops::{Not, RangeBounds};
+use std::str::FromStr;
+
+use crate::date::{Date, DateRange};
+use crate::priority::Priority;
+use crate::state::State;
+use crate::task::Task;
+
+/// A composable filter over [`Task`]s.
+///
+/// Filters are created with the associated constructors (e.g.
+/// [`Filter::project`]) and combined with [`Filter::and`], [`Filter::or`] and
+/// `!` (negation). They can be applied to a single task with
+/// [`Filter::matches`] or to a whole list with
+/// [`TodoFile::filter`](`crate::TodoFile::filter`).
+///
+/// # Examples
+///
+/// ```rust
+/// use std::str::FromStr;
+///
+/// use tdtxt::{Filter, Priority, TodoFile};
+///
+/// let todo = TodoFile::from_str(
+///     "(A) Schedule Goodwill pickup +GarageSale @phone\n\
+///      (C) Call neighbors +GarageSale @phone\n\
+///      x (A) Call Mom +GarageSale @phone\n\
+///      (B) Post signs around the neighborhood +GarageSale\n",
+/// )
+/// .unwrap();
+///
+/// let filter = Filter::project("GarageSale")
+///     .and(Filter::context("phone"))
+///     .and(Filter::priority_at_least(Priority::B))
+///     .and(Filter::not_done());
+///
+/// let matches = todo.filter(&filter).map(|(idx, _)| idx).collect::<Vec<_>>();
+/// assert_eq!(matches, [0]);
+///
+/// let others = todo.filter(&!filter).map(|(idx, _)| idx).collect::<Vec<_>>();
+/// assert_eq!(others, [1, 2, 3]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Filter {
+	node: Node,
+}
+
+/// A single node of the expression tree of a [`Filter`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Node {
+	Any,
+	Project { name: String, ignore_case: bool },
+	Context { name: String, ignore_case: bool },
+	Tag { key: String, value: Option<String> },
+	Text(String),
+	Priority(Option<Priority>),
+	PriorityAtLeast(Priority),
+	State(State),
+	DueBefore(Date),
+	DueWithin(DateRange),
+	CreatedIn(DateRange),
+	CompletedIn(DateRange),
+	Hidden,
+	And(Box<Self>, Box<Self>),
+	Or(Box<Self>, Box<Self>),
+	Not(Box<Self>),
+}
+
+impl Filter {
+	const fn new(node: Node) -> Self {
+		Self { node }
+	}
+
+	/// Matches every task.
+	pub const fn any() -> Self {
+		Self::new(Node::Any)
+	}
+
+	/// Matches tasks containing the project `project` (with or without the
+	/// leading `+`).
+	pub fn project<S: AsRef<str>>(project: S) -> Self {
+		let project = project.as_ref();
+		Self::new(Node::Project {
+			name: project.strip_prefix('+').unwrap_or(project).to_owned(),
+			ignore_case: false,
+		})
+	}
+
+	/// Matches tasks containing the context `context` (with or without the
+	/// leading `@`).
+	pub fn context<S: AsRef<str>>(context: S) -> Self {
+		let context = context.as_ref();
+		Self::new(Node::Context {
+			name: context.strip_prefix('@').unwrap_or(context).to_owned(),
+			ignore_case: false,
+		})
+	}
+
+	/// Matches tasks containing a custom tag with the key `key`.
+	pub fn tag<S: Into<String>>(key: S) -> Self {
+		Self::new(Node::Tag { key: key.into(), value: None })
+	}
+
+	/// Matches tasks containing the custom tag `key:value`.
+	pub fn tag_value<K, V>(key: K, value: V) -> Self
+	where
+		K: Into<String>,
+		V: Into<String>,
+	{
+		Self::new(Node::Tag { key: key.into(), value: Some(value.into()) })
+	}
+
+	/// Matches tasks whose description contains `text`.
+	pub fn text<S: Into<String>>(text: S) -> Self {
+		Self::new(Node::Text(text.into()))
+	}
+
+	/// Matches tasks with exactly the priority `priority`.
+	pub const fn priority(priority: Priority) -> Self {
+		Self::new(Node::Priority(Some(priority)))
+	}
+
+	/// Matches tasks without a priority.
+	pub const fn no_priority() -> Self {
+		Self::new(Node::Priority(None))
+	}
+
+	/// Matches tasks with a priority of at least `priority` (e.g. `A` and `B`
+	/// for `B`).
+	pub const fn priority_at_least(priority: Priority) -> Self {
+		Self::new(Node::PriorityAtLeast(priority))
+	}
+
+	/// Matches tasks with the state `state`.
+	pub const fn state(state: State) -> Self {
+		Self::new(Node::State(state))
+	}
+
+	/// Matches done tasks.
+	pub const fn done() -> Self {
+		Self::state(State::Done)
+	}
+
+	/// Matches open tasks.
+	pub const fn not_done() -> Self {
+		Self::state(State::Open)
+	}
+
+	/// Matches tasks with a due date (`due:` tag) before `date`.
+	pub const fn due_before(date: Date) -> Self {
+		Self::new(Node::DueBefore(date))
+	}
+
+	/// Matches open tasks with a due date (`due:` tag) before `today`.
+	pub fn overdue(today: Date) -> Self {
+		Self::not_done().and(Self::due_before(today))
+	}
+
+	/// Matches tasks with a due date (`due:` tag) within `range` (see
+	/// [`DateRange::new`]).
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::str::FromStr;
+	///
+	/// use tdtxt::{Date, Filter, TodoFile};
+	///
+	/// let todo = TodoFile::from_str(
+	///     "2021-07-01 Pay rent due:2021-08-01\n\
+	///      x 2021-08-03 2021-08-02 Call Mom due:2021-08-07\n\
+	///      2021-08-05 Buy milk\n",
+	/// )
+	/// .unwrap();
+	///
+	/// let first_week = Date::from_ymd(2021, 8, 1)..Date::from_ymd(2021, 8, 8);
+	///
+	/// let due = todo.filter(&Filter::due_within(first_week.clone()));
+	/// assert_eq!(due.map(|(idx, _)| idx).collect::<Vec<_>>(), [0, 1]);
+	///
+	/// let created = todo.filter(&Filter::created_in(first_week.clone()));
+	/// assert_eq!(created.map(|(idx, _)| idx).collect::<Vec<_>>(), [1, 2]);
+	///
+	/// let completed = todo.filter(&Filter::completed_in(first_week));
+	/// assert_eq!(completed.map(|(idx, _)| idx).collect::<Vec<_>>(), [1]);
+	/// ```
+	pub fn due_within<R>(range: R) -> Self
+	where
+		R: RangeBounds<Date>,
+	{
+		Self::new(Node::DueWithin(DateRange::new(range)))
+	}
+
+	/// Matches tasks with a creation date within `range` (see
+	/// [`DateRange::new`]).
+	pub fn created_in<R>(range: R) -> Self
+	where
+		R: RangeBounds<Date>,
+	{
+		Self::new(Node::CreatedIn(DateRange::new(range)))
+	}
+
+	/// Matches tasks with a completion date within `range` (see
+	/// [`DateRange::new`]).
+	pub fn completed_in<R>(range: R) -> Self
+	where
+		R: RangeBounds<Date>,
+	{
+		Self::new(Node::CompletedIn(DateRange::new(range)))
+	}
+
+	/// Matches hidden tasks (see [`Task::is_hidden`]).
+	pub const fn hidden() -> Self {
+		Self::new(Node::Hidden)
+	}
+
+	/// Matches tasks which are not hidden (see [`Task::is_hidden`]).
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::str::FromStr;
+	///
+	/// use tdtxt::{Filter, TodoFile};
+	///
+	/// let todo = TodoFile::from_str("+GarageSale h:1\nCall Mom\n").unwrap();
+	///
+	/// let visible = todo.filter(&Filter::not_hidden()).map(|(idx, _)| idx);
+	/// assert_eq!(visible.collect::<Vec<_>>(), [1]);
+	/// ```
+	pub fn not_hidden() -> Self {
+		!Self::hidden()
+	}
+
+	/// Matches tasks matched by both `self` and `other`.
+	pub fn and(self, other: Self) -> Self {
+		Self::new(Node::And(Box::new(self.node), Box::new(other.node)))
+	}
+
+	/// Matches tasks matched by `self` or `other`.
+	pub fn or(self, other: Self) -> Self {
+		Self::new(Node::Or(Box::new(self.node), Box::new(other.node)))
+	}
+
+	/// Makes all project and context terms of the filter ignore the case of
+	/// the names (see [`eq_ignore_case`](`crate::eq_ignore_case`)).
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::str::FromStr;
+	///
+	/// use tdtxt::{Filter, TodoFile};
+	///
+	/// let todo = TodoFile::from_str(
+	///     "Call neighbors +GarageSale @phone\n\
+	///      Post signs +garagesale @PHONE\n",
+	/// )
+	/// .unwrap();
+	///
+	/// let filter = Filter::parse("+GarageSale @phone").unwrap();
+	///
+	/// let matches = todo.filter(&filter).map(|(idx, _)| idx);
+	/// assert_eq!(matches.collect::<Vec<_>>(), [0]);
+	///
+	/// let filter = filter.ignore_case();
+	///
+	/// let matches = todo.filter(&filter).map(|(idx, _)| idx);
+	/// assert_eq!(matches.collect::<Vec<_>>(), [0, 1]);
+	/// ```
+	pub fn ignore_case(mut self) -> Self {
+		self.node.set_ignore_case();
+		self
+	}
+
+	/// Returns `true` if `task` is matched by the filter.
+	pub fn matches(&self, task: &Task) -> bool {
+		self.node.matches(task)
+	}
+
+	/// Parses a filter from a todo.sh-style query string.
+	///
+	/// The query consists of whitespace separated terms, which all must
+	/// match:
+	///
+	/// | Term        | Matches tasks ...                          |
+	/// |-------------|--------------------------------------------|
+	/// | `+project`  | containing the project                     |
+	/// | `@context`  | containing the context                     |
+	/// | `pri:A`     | with the priority                          |
+	/// | `key:value` | containing the custom tag                  |
+	/// | `text`      | whose description contains the text        |
+	/// | `-term`     | not matched by the term                    |
+	/// | `a\|b`      | matched by any of the alternatives         |
+	///
+	/// An empty query matches every task.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::str::FromStr;
+	///
+	/// use tdtxt::{Filter, Priority};
+	///
+	/// let filter = Filter::parse("+GarageSale @phone pri:A -@home").unwrap();
+	///
+	/// assert_eq!(
+	///     filter,
+	///     Filter::project("GarageSale")
+	///         .and(Filter::context("phone"))
+	///         .and(Filter::priority(Priority::A))
+	///         .and(!Filter::context("home"))
+	/// );
+	///
+	/// assert!(Filter::parse("pri:a").is_err());
+	/// ```
+	pub fn parse(query: &str) -> Result<Self, ParseFilterError> {
+		query
+			.split_whitespace()
+			.map(Self::parse_term)
+			.try_fold(None, |filter: Option<Self>, term| {
+				let term = term?;
+				Ok(Some(match filter {
+					Some(filter) => filter.and(term),
+					None => term,
+				}))
+			})
+			.map(Option::unwrap_or_default)
+	}
+
+	/// Parses a single term of a query (see [`Filter::parse`]).
+	fn parse_term(term: &str) -> Result<Self, ParseFilterError> {
+		if let Some(negated) = term.strip_prefix('-') {
+			if negated.is_empty() {
+				return Err(ParseFilterError::with_msg("empty negation"));
+			}
+
+			return Self::parse_term(negated).map(Not::not);
+		}
+
+		let mut alternatives = term.split('|').map(|alternative| {
+			if alternative.is_empty() {
+				Err(ParseFilterError::with_msg("empty alternative"))
+			} else {
+				Self::parse_atom(alternative)
+			}
+		});
+
+		let first =
+			alternatives.next().expect("split yields at least once")?;
+		alternatives
+			.try_fold(first, |filter, alternative| Ok(filter.or(alternative?)))
+	}
+
+	/// Parses a single term without negation and alternatives.
+	fn parse_atom(atom: &str) -> Result<Self, ParseFilterError> {
+		match atom.as_bytes() {
+			[b'+', _, ..] => Ok(Self::project(atom)),
+			[b'@', _, ..] => Ok(Self::context(atom)),
+			_ => match atom.split_once(':') {
+				Some((PRIORITY_KEY, value)) => {
+					let priority = match value.as_bytes() {
+						[b] => Priority::try_from(char::from(*b)).ok(),
+						_ => None,
+					}
+					.ok_or_else(|| {
+						ParseFilterError::with_msg(format!(
+							"invalid priority `{}`",
+							value
+						))
+					})?;
+
+					Ok(Self::priority(priority))
+				}
+				Some((key, value)) if !key.is_empty() && !value.is_empty() => {
+					Ok(Self::tag_value(key, value))
+				}
+				_ => Ok(Self::text(atom)),
+			},
+		}
+	}
+}
+
+/// The key used in queries to match priorities (e.g. `pri:A`).
+const PRIORITY_KEY: &str = "pri";
+
+crate::parse_error!(ParseFilterError: "filter");
+
+impl FromStr for Filter {
+	type Err = ParseFilterError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Self::parse(s)
+	}
+}
+
+impl Default for Filter {
+	fn default() -> Self {
+		Self::any()
+	}
+}
+
+impl Not for Filter {
+	type Output = Self;
+
+	fn not(self) -> Self::Output {
+		match self.node {
+			Node::Not(node) => Self::new(*node),
+			node => Self::new(Node::Not(Box::new(node))),
+		}
+	}
+}
+
+impl Node {
+	fn set_ignore_case(&mut self) {
+		match self {
+			Self::Project { ignore_case, .. }
+			| Self::Context { ignore_case, .. } => *ignore_case = true,
+			Self::And(a, b) | Self::Or(a, b) => {
+				a.set_ignore_case();
+				b.set_ignore_case();
+			}
+			Self::Not(node) => node.set_ignore_case(),
+			_ => {}
+		}
+	}
+
+	fn matches(&self, task: &Task) -> bool {
+		let description = &task.description;
+
+		match self {
+			Self::Any => true,
+			Self::Project { name, ignore_case: false } => {
+				description.has_project(name)
+			}
+			Self::Project { name, ignore_case: true } => {
+				description.has_project_ignore_case(name)
+			}
+			Self::Context { name, ignore_case: false } => {
+				description.has_context(name)
+			}
+			Self::Context { name, ignore_case: true } => {
+				description.has_context_ignore_case(name)
+			}
+			Self::Tag { key, value } => description.custom().any(|(k, v)| {
+				k == key && value.as_ref().is_none_or(|value| v == value)
+			}),
+			Self::Text(text) => {
+				description.description().contains(text.as_str())
+			}
+			Self::Priority(priority) => task.priority == *priority,
+			Self::PriorityAtLeast(priority) => {
+				task.priority.is_some_and(|p| p >= *priority)
+			}
+			Self::State(state) => task.state == *state,
+			Self::DueBefore(date) => {
+				task.due_date().is_some_and(|due| due < *date)
+			}
+			Self::DueWithin(range) => {
+				task.due_date().is_some_and(|due| range.contains(&due))
+			}
+			Self::CreatedIn(range) => task
+				.date_compound
+				.is_some_and(|dates| range.contains(dates.date_created())),
+			Self::CompletedIn(range) => {
+				task.date_compound.is_some_and(|dates| {
+					dates.date_completed().is_some_and(|d| range.contains(d))
+				})
+			}
+			Self::Hidden => task.is_hidden(),
+			Self::And(a, b) => a.matches(task) && b.matches(task),
+			Self::Or(a, b) => a.matches(task) || b.matches(task),
+			Self::Not(node) => !node.matches(task),
+		}
+	}
+}
@@ -0,0 +1,164 @@
+use crate::date::DateCompound;
+use crate::description::Component;
+use crate::description_ref::DescriptionRef;
+use crate::parse::{Parse, Parser};
+use crate::priority::Priority;
+use crate::span::{BytePos, ByteSpan};
+use crate::state::State;
+
+/// The kind of a token returned by [`highlight`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TokenKind {
+	/// The done marker (`x`).
+	State,
+
+	/// The priority (e.g. `(A)`).
+	Priority,
+
+	/// The completion date of a done task.
+	CompletionDate,
+
+	/// The creation date.
+	CreationDate,
+
+	/// A project (e.g. `+Family`).
+	Project,
+
+	/// A context (e.g. `@phone`).
+	Context,
+
+	/// The key of a custom tag (e.g. `due`).
+	TagKey,
+
+	/// The separator of a custom tag (`:`).
+	TagSeparator,
+
+	/// The value of a custom tag (e.g. `2021-08-01`).
+	TagValue,
+
+	/// A word of the free text.
+	Text,
+}
+
+/// Splits `line` into tokens for syntax highlighting, e.g. in editor
+/// plugins.
+///
+/// The tokens are returned in the order they appear, whitespace between them
+/// is not part of any token. Tokenizing never fails: parts of the line
+/// which are not valid components (e.g. the lowercase priority `(a)`) are
+/// returned as [`TokenKind::Text`], like they are treated when parsing the
+/// line as a [`Task`](`crate::Task`).
+///
+/// # Examples
+///
+/// ```rust
+/// use tdtxt::{highlight, TokenKind};
+///
+/// let line = "x (A) 2021-08-01 2021-07-30 Call Mom +Family due:2021-08-05";
+///
+/// let tokens = highlight(line)
+///     .map(|(kind, span)| (kind, &line[span.to_range_usize()]))
+///     .collect::<Vec<_>>();
+///
+/// assert_eq!(
+///     tokens,
+///     vec![
+///         (TokenKind::State, "x"),
+///         (TokenKind::Priority, "(A)"),
+///         (TokenKind::CompletionDate, "2021-08-01"),
+///         (TokenKind::CreationDate, "2021-07-30"),
+///         (TokenKind::Text, "Call"),
+///         (TokenKind::Text, "Mom"),
+///         (TokenKind::Project, "+Family"),
+///         (TokenKind::TagKey, "due"),
+///         (TokenKind::TagSeparator, ":"),
+///         (TokenKind::TagValue, "2021-08-05"),
+///     ]
+/// );
+/// ```
+pub fn highlight(line: &str) -> impl Iterator<Item = (TokenKind, ByteSpan)> {
+	let mut tokens = Vec::new();
+	let mut parser = Parser::new(line.as_bytes());
+
+	if let Some(span) = prefix_token::<State>(&mut parser) {
+		tokens.push((TokenKind::State, span));
+	}
+
+	if let Some(span) = prefix_token::<Priority>(&mut parser) {
+		tokens.push((TokenKind::Priority, span));
+	}
+
+	if let Some(span) = prefix_token::<DateCompound>(&mut parser) {
+		let text = &line[span.to_range_usize()];
+
+		match text.find(|c: char| c.is_ascii_whitespace()) {
+			Some(idx) => {
+				let start = span.low().as_usize();
+				let created = start + text.trim_end().len()
+					- text[idx..].trim_start().len();
+
+				tokens.push((
+					TokenKind::CompletionDate,
+					ByteSpan::from_usize_range(start..start + idx),
+				));
+				tokens.push((
+					TokenKind::CreationDate,
+					span.with_low(BytePos::from_usize(created)),
+				));
+			}
+			None => tokens.push((TokenKind::CreationDate, span)),
+		}
+	}
+
+	let offset = parser.index();
+	let description = DescriptionRef::new(&line[offset..]);
+
+	for (component, span) in description.words() {
+		let span = ByteSpan::from_usize_range(
+			offset + span.low().as_usize()..offset + span.high().as_usize(),
+		);
+
+		match component {
+			Component::Text(_) => tokens.push((TokenKind::Text, span)),
+			Component::Project(_) => tokens.push((TokenKind::Project, span)),
+			Component::Context(_) => tokens.push((TokenKind::Context, span)),
+			Component::Custom { key, separator, .. } => {
+				let start = span.low().as_usize();
+				let value = start + key.len() + separator.len();
+
+				tokens.push((
+					TokenKind::TagKey,
+					ByteSpan::from_usize_range(start..start + key.len()),
+				));
+				tokens.push((
+					TokenKind::TagSeparator,
+					ByteSpan::from_usize_range(start + key.len()..value),
+				));
+				tokens.push((
+					TokenKind::TagValue,
+					span.with_low(BytePos::from_usize(value)),
+				));
+			}
+		}
+	}
+
+	tokens.into_iter()
+}
+
+/// Parses a leading component of a task followed by whitespace (or the end
+/// of the line) and returns its span.
+fn prefix_token<T: Parse>(parser: &mut Parser<'_>) -> Option<ByteSpan> {
+	let mut p_copy = *parser;
+	let start = p_copy.index();
+
+	T::parse_opt(&mut p_copy)?;
+	let end = p_copy.index();
+
+	if p_copy.is_eof() || p_copy.expect_whitespace().is_some() {
+		*parser = p_copy;
+		Some(ByteSpan::from_usize_range(start..end))
+	} else {
+		None
+	}
+}
@@ -0,0 +1,99 @@
+use std::collections::HashSet;
+
+use crate::task::Task;
+
+/// The key of the custom tag holding the id of a task (e.g. `id:42`).
+pub const ID_TAG: &str = "id";
+
+/// Generates unique ids for new tasks.
+///
+/// A generator can be registered with
+/// [`TodoFile::set_id_generator`](`crate::TodoFile::set_id_generator`), after
+/// which every task added with
+/// [`TodoFile::add`](`crate::TodoFile::add`) without an `id:` tag gets one.
+///
+/// The trait is implemented for all closures with the signature
+/// `FnMut(&[Task]) -> String`.
+pub trait IdGenerator {
+	/// Generates a new id which is not used by any task in `tasks`.
+	///
+	/// The id must not contain any whitespace.
+	fn generate(&mut self, tasks: &[Task]) -> String;
+}
+
+impl<F> IdGenerator for F
+where
+	F: FnMut(&[Task]) -> String,
+{
+	fn generate(&mut self, tasks: &[Task]) -> String {
+		self(tasks)
+	}
+}
+
+/// Generates ascending numeric ids (`1`, `2`, ...).
+///
+/// Each generated id is greater than all numeric ids already used by the
+/// tasks of the list and all ids previously generated. Once the greatest id
+/// is `u64::MAX`, the smallest id not used by any task is generated instead.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SequenceIdGenerator {
+	last: u64,
+}
+
+impl SequenceIdGenerator {
+	/// Creates a new generator starting at `1`.
+	pub const fn new() -> Self {
+		Self { last: 0 }
+	}
+}
+
+impl IdGenerator for SequenceIdGenerator {
+	fn generate(&mut self, tasks: &[Task]) -> String {
+		let used_ids = || {
+			tasks
+				.iter()
+				.flat_map(|task| task.description.custom())
+				.filter(|(key, _)| *key == ID_TAG)
+				.filter_map(|(_, value)| value.parse::<u64>().ok())
+		};
+
+		let max_used = used_ids().max().unwrap_or(0);
+
+		if let Some(next) = self.last.max(max_used).checked_add(1) {
+			self.last = next;
+			return next.to_string();
+		}
+
+		self.last = u64::MAX;
+
+		let used = used_ids().collect::<HashSet<_>>();
+		(1..)
+			.find(|id| !used.contains(id))
+			.expect("less tasks than ids")
+			.to_string()
+	}
+}
+
+/// Generates random [ULID](https://github.com/ulid/spec)s.
+#[cfg(feature = "ulid")]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UlidGenerator;
+
+#[cfg(feature = "ulid")]
+impl IdGenerator for UlidGenerator {
+	fn generate(&mut self, _: &[Task]) -> String {
+		ulid::Ulid::new().to_string()
+	}
+}
+
+/// Generates random (version 4) UUIDs.
+#[cfg(feature = "uuid")]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UuidGenerator;
+
+#[cfg(feature = "uuid")]
+impl IdGenerator for UuidGenerator {
+	fn generate(&mut self, _: &[Task]) -> String {
+		uuid::Uuid::new_v4().to_string()
+	}
+}
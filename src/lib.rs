@@ -82,7 +82,6 @@
 	future_incompatible,
 	missing_copy_implementations,
 	missing_debug_implementations,
-	private_in_public,
 	rust_2018_compatibility,
 	rust_2018_idioms,
 	trivial_casts,
@@ -99,26 +98,115 @@
 )]
 #![cfg_attr(docsrs, feature(doc_cfg), feature(doc_alias))]
 
+mod case;
+mod clock;
 mod date;
+#[cfg(feature = "datetime")]
+mod datetime;
+mod decode;
+mod dependency;
 mod description;
+mod description_ref;
+mod diff;
+mod file;
+mod filter;
+mod highlight;
+mod id;
+#[cfg(feature = "unicode-normalization")]
+mod normalization;
+mod patch;
 mod priority;
+mod project;
+mod recurrence;
+mod report;
+mod sort;
 mod state;
+mod summary;
+mod tag;
 mod task;
+mod task_ref;
+mod template;
+mod urgency;
 
 mod parse;
 mod span;
+mod trace;
 
-#[cfg(not(feature = "chrono"))]
-pub use crate::date::SimpleDate;
+#[cfg(feature = "cli")]
+pub mod cli;
+#[cfg(feature = "todo_txt-compat")]
+mod compat;
+#[cfg(all(feature = "serde", feature = "chrono"))]
+pub mod serde_chrono;
+
+pub use crate::case::eq_ignore_case;
+pub use crate::clock::{Clock, FixedClock, SystemClock};
 pub use crate::date::{
-	Date, DateCompound, ParseDateCompoundError, ParseDateError,
+	Date, DateCompound, DateOrderError, DateRange, Days, EnglishPhrasing,
+	FormattedDate, IsoWeek, ParseDateCompoundError, ParseDateError,
+	RelativeDate, RelativePhrasing, SimpleDate, Weekday, DUE_TAG,
+	THRESHOLD_TAG,
+};
+#[cfg(feature = "datetime")]
+pub use crate::datetime::{DateTimeTag, ParseDateTimeTagError};
+pub use crate::decode::{DecodeOptions, InvalidUtf8Policy};
+pub use crate::dependency::{
+	DependencyCycle, DependencyGraph, TopologicalOrder, DEPENDENCY_TAG,
+	PARENT_TAG,
 };
 pub use crate::description::{
-	Component, Components, Description, ParseDescriptionError,
+	BuildDescriptionError, Component, Components, ContextRange, CustomGrouped,
+	CustomRange, Description, DescriptionBuilder, DescriptionOptions,
+	ParseDescriptionError, ProjectRange, SpannedComponents, TagValues, Words,
+};
+pub use crate::description_ref::DescriptionRef;
+pub use crate::diff::TaskChange;
+#[cfg(feature = "regex")]
+pub use crate::file::GrepMatch;
+pub use crate::file::{
+	DateField, DateNormalization, Duplicates, Entries, Entry, LineEnding,
+	ListenerId, MergePolicy, ParseTodoFileError, ResolveFn, TaskEvent,
+	TaskMatches, TodoFile,
+};
+pub use crate::filter::{Filter, ParseFilterError};
+pub use crate::highlight::{highlight, TokenKind};
+#[cfg(feature = "ulid")]
+pub use crate::id::UlidGenerator;
+#[cfg(feature = "uuid")]
+pub use crate::id::UuidGenerator;
+pub use crate::id::{IdGenerator, SequenceIdGenerator, ID_TAG};
+#[cfg(feature = "unicode-normalization")]
+pub use crate::normalization::{eq_normalized, NormalizationForm};
+pub use crate::patch::{PatchOp, TaskPatch};
+pub use crate::priority::{
+	ParsePriorityError, Priority, PriorityAppearance, PriorityStep,
+	PriorityStyle, PRIORITY_TAG,
 };
-pub use crate::priority::{ParsePriorityError, Priority};
+pub use crate::project::{ProjectPath, PROJECT_SEPARATOR};
+pub use crate::recurrence::{
+	ParseRecurrenceError, Recurrence, RecurrenceUnit,
+};
+pub use crate::report::ParseReport;
+pub use crate::sort::{SortKey, TaskOrder};
+pub use crate::span::{BytePos, ByteSpan, LineCol};
 pub use crate::state::{ParseStateError, State};
-pub use crate::task::{ParseTaskError, Task, TaskBuilder};
+pub use crate::summary::{summarize_project, ESTIMATE_TAG};
+pub use crate::tag::{
+	DuplicateTagError, DuplicateTagPolicy, KnownTag, TagRegistry,
+	TagValidator, TagValue, TagWarning,
+};
+#[doc(hidden)]
+pub use crate::task::__is_task_literal;
+pub use crate::task::{
+	check_format_parse_identity, BuildError, CompletionPolicy,
+	CompletionPriority, ParseTaskError, RoundTripError, Task, TaskBuilder,
+	HIDDEN_TAG, RECURRENCE_TAG,
+};
+pub use crate::task_ref::TaskRef;
+pub use crate::template::{
+	InstantiateError, ParseTemplateError, TaskTemplate,
+};
+pub use crate::urgency::Urgency;
 
 pub mod prelude {
 	//! The prelude exports all components needed for regular use.
@@ -129,17 +217,73 @@ pub mod prelude {
 	//! use tdtxt::prelude::*;
 	//! ```
 
-	#[cfg(not(feature = "chrono"))]
-	pub use crate::date::SimpleDate;
+	pub use crate::case::eq_ignore_case;
+	pub use crate::clock::{Clock, FixedClock, SystemClock};
 	pub use crate::date::{
-		Date, DateCompound, ParseDateCompoundError, ParseDateError,
+		Date, DateCompound, DateOrderError, DateRange, Days, EnglishPhrasing,
+		FormattedDate, IsoWeek, ParseDateCompoundError, ParseDateError,
+		RelativeDate, RelativePhrasing, SimpleDate, Weekday, DUE_TAG,
+		THRESHOLD_TAG,
+	};
+	#[cfg(feature = "datetime")]
+	pub use crate::datetime::{DateTimeTag, ParseDateTimeTagError};
+	pub use crate::decode::{DecodeOptions, InvalidUtf8Policy};
+	pub use crate::dependency::{
+		DependencyCycle, DependencyGraph, TopologicalOrder, DEPENDENCY_TAG,
+		PARENT_TAG,
 	};
 	pub use crate::description::{
-		Component, Components, Description, ParseDescriptionError,
+		BuildDescriptionError, Component, Components, ContextRange,
+		CustomGrouped, CustomRange, Description, DescriptionBuilder,
+		DescriptionOptions, ParseDescriptionError, ProjectRange,
+		SpannedComponents, TagValues, Words,
+	};
+	pub use crate::description_ref::DescriptionRef;
+	pub use crate::diff::TaskChange;
+	#[cfg(feature = "regex")]
+	pub use crate::file::GrepMatch;
+	pub use crate::file::{
+		DateField, DateNormalization, Duplicates, Entries, Entry, LineEnding,
+		ListenerId, MergePolicy, ParseTodoFileError, ResolveFn, TaskEvent,
+		TaskMatches, TodoFile,
+	};
+	pub use crate::filter::{Filter, ParseFilterError};
+	pub use crate::highlight::{highlight, TokenKind};
+	#[cfg(feature = "ulid")]
+	pub use crate::id::UlidGenerator;
+	#[cfg(feature = "uuid")]
+	pub use crate::id::UuidGenerator;
+	pub use crate::id::{IdGenerator, SequenceIdGenerator, ID_TAG};
+	#[cfg(feature = "unicode-normalization")]
+	pub use crate::normalization::{eq_normalized, NormalizationForm};
+	pub use crate::patch::{PatchOp, TaskPatch};
+	pub use crate::priority::{
+		ParsePriorityError, Priority, PriorityAppearance, PriorityStep,
+		PriorityStyle, PRIORITY_TAG,
+	};
+	pub use crate::project::{ProjectPath, PROJECT_SEPARATOR};
+	pub use crate::recurrence::{
+		ParseRecurrenceError, Recurrence, RecurrenceUnit,
 	};
-	pub use crate::priority::{ParsePriorityError, Priority};
+	pub use crate::report::ParseReport;
+	pub use crate::sort::{SortKey, TaskOrder};
+	pub use crate::span::{BytePos, ByteSpan, LineCol};
 	pub use crate::state::{ParseStateError, State};
-	pub use crate::task::{ParseTaskError, Task, TaskBuilder};
+	pub use crate::summary::{summarize_project, ESTIMATE_TAG};
+	pub use crate::tag::{
+		DuplicateTagError, DuplicateTagPolicy, KnownTag, TagRegistry,
+		TagValidator, TagValue, TagWarning,
+	};
+	pub use crate::task::{
+		check_format_parse_identity, BuildError, CompletionPolicy,
+		CompletionPriority, ParseTaskError, RoundTripError, Task, TaskBuilder,
+		HIDDEN_TAG, RECURRENCE_TAG,
+	};
+	pub use crate::task_ref::TaskRef;
+	pub use crate::template::{
+		InstantiateError, ParseTemplateError, TaskTemplate,
+	};
+	pub use crate::urgency::Urgency;
 }
 
 #[cfg(test)]
@@ -150,10 +294,14 @@ mod tests {
 
 	use crate::date::{Date, DateCompound};
 	use crate::description::Description;
+	use crate::file::TodoFile;
 	use crate::parse::*;
-	use crate::priority::Priority;
+	use crate::priority::{Priority, PriorityStep};
 	use crate::state::State;
-	use crate::task::{ParseTaskError, Task};
+	use crate::task::{
+		BuildError, CompletionPolicy, CompletionPriority, ParseTaskError,
+		Task, TaskBuilder,
+	};
 	use crate::Component;
 
 	#[cfg(feature = "serde")]
@@ -201,21 +349,20 @@ mod tests {
 		let input = b"2020-01-01";
 		let mut parser = Parser::new(input);
 
-		assert_eq!(Date::parse(&mut parser), Ok(Date::from_ymd(2020, 01, 01)));
+		assert_eq!(Date::parse(&mut parser), Ok(Date::from_ymd(2020, 1, 1)));
 
 		let input = b"1234-07-16";
 		let mut parser = Parser::new(input);
 
-		let d =
-			DateCompound::Created { created: Date::from_ymd(1234, 07, 16) };
+		let d = DateCompound::Created { created: Date::from_ymd(1234, 7, 16) };
 		assert_eq!(DateCompound::parse(&mut parser), Ok(d));
 
 		let input = b"2000-01-01 1970-01-01";
 		let mut parser = Parser::new(input);
 
 		let d = DateCompound::Completed {
-			created: Date::from_ymd(1970, 01, 01),
-			completed: Date::from_ymd(2000, 01, 01),
+			created: Date::from_ymd(1970, 1, 1),
+			completed: Date::from_ymd(2000, 1, 1),
 		};
 		assert_eq!(DateCompound::parse(&mut parser), Ok(d));
 
@@ -246,7 +393,7 @@ mod tests {
 			state: State::Done,
 			priority: Some(Priority::Z),
 			date_compound: Some(DateCompound::Created {
-				created: Date::from_ymd(2020, 01, 01),
+				created: Date::from_ymd(2020, 1, 1),
 			}),
 			description: Description::new("Hello World"),
 		};
@@ -318,7 +465,7 @@ Post signs around the neighborhood +GarageSale
 
 		let task = Task::build()
 			.date_compound(DateCompound::Created {
-				created: Date::from_ymd(2011, 03, 02),
+				created: Date::from_ymd(2011, 3, 2),
 			})
 			.build("Document +TodoTxt task format");
 		assert_eq!(Task::parse(&mut parser), Ok(task));
@@ -326,7 +473,7 @@ Post signs around the neighborhood +GarageSale
 		let task = Task::build()
 			.priority(Priority::A)
 			.date_compound(DateCompound::Created {
-				created: Date::from_ymd(2011, 03, 02),
+				created: Date::from_ymd(2011, 3, 2),
 			})
 			.build("Call Mom");
 		assert_eq!(Task::parse(&mut parser), Ok(task));
@@ -435,8 +582,8 @@ Post signs around the neighborhood +GarageSale
 			.state(State::Done)
 			.priority(Priority::J)
 			.date_compound(DateCompound::Completed {
-				created: Date::from_ymd(1980, 01, 01),
-				completed: Date::from_ymd(1990, 01, 01),
+				created: Date::from_ymd(1980, 1, 1),
+				completed: Date::from_ymd(1990, 1, 1),
 			})
 			.build("Wait ten year @home for +century_waiting author:me");
 		let task_is = Task::parse(&mut parser);
@@ -879,4 +1026,3518 @@ x Download Todo.txt mobile app @Phone";
 		assert!(Priority::A == Priority::A);
 		assert!(Priority::Z < Priority::A);
 	}
+
+	#[test]
+	fn todo_file_index() {
+		let input = "(A) Thank Mom for the meatballs @phone
+(B) Schedule Goodwill pickup +GarageSale @phone
+
+Post signs around the neighborhood +GarageSale
+@GroceryStore Eskimo pies";
+
+		let mut todo = TodoFile::from_str(input).unwrap();
+		assert_eq!(todo.len(), 4);
+		assert_eq!(todo.to_string(), input);
+
+		let scanned = todo
+			.tasks_with_project("GarageSale")
+			.map(|(idx, _)| idx)
+			.collect::<Vec<_>>();
+		todo.build_index();
+		let indexed = todo
+			.tasks_with_project("GarageSale")
+			.map(|(idx, _)| idx)
+			.collect::<Vec<_>>();
+		assert_eq!(scanned, vec![1, 2]);
+		assert_eq!(indexed, scanned);
+
+		let _ =
+			todo.push(Task::build().build("Buy cake @GroceryStore +Party"));
+		assert_eq!(
+			todo.tasks_with_context("GroceryStore")
+				.map(|(idx, _)| idx)
+				.collect::<Vec<_>>(),
+			vec![3, 4]
+		);
+
+		let _ = todo.remove(0);
+		assert_eq!(
+			todo.tasks_with_context("phone")
+				.map(|(idx, _)| idx)
+				.collect::<Vec<_>>(),
+			vec![0]
+		);
+
+		let _ = todo.modify(0, |task| {
+			task.description = Description::new("Schedule pickup @home");
+		});
+		assert_eq!(todo.tasks_with_context("phone").count(), 0);
+		assert_eq!(
+			todo.tasks_with_project("GarageSale")
+				.map(|(idx, _)| idx)
+				.collect::<Vec<_>>(),
+			vec![1]
+		);
+		assert_eq!(todo.tasks_with_context("home").count(), 1);
+	}
+
+	#[test]
+	fn todo_file_listeners() {
+		use std::cell::RefCell;
+		use std::rc::Rc;
+
+		use crate::file::TaskEvent;
+
+		let events = Rc::new(RefCell::new(Vec::new()));
+		let events_clone = Rc::clone(&events);
+
+		let mut todo = TodoFile::new();
+		let _ = todo.subscribe(move |event| {
+			let event = match event {
+				TaskEvent::Added { index, task } => {
+					format!("+{} {}", index, task)
+				}
+				TaskEvent::Removed { index, task } => {
+					format!("-{} {}", index, task)
+				}
+				TaskEvent::Modified { index, old, new } => {
+					format!("~{} {} => {}", index, old, new)
+				}
+			};
+			events_clone.borrow_mut().push(event);
+		});
+
+		let _ = todo.push(Task::build().build("Call Mom"));
+		let _ = todo.push(Task::build().build("Call Dad"));
+		let _ = todo.modify(0, |task| task.priority = Some(Priority::A));
+		let _ = todo.replace(1, Task::build().build("Call Grandma"));
+		let _ = todo.remove(0);
+		todo.clear();
+
+		assert_eq!(
+			*events.borrow(),
+			vec![
+				"+0 Call Mom",
+				"+1 Call Dad",
+				"~0 Call Mom => (A) Call Mom",
+				"~1 Call Dad => Call Grandma",
+				"-0 (A) Call Mom",
+				"-0 Call Grandma",
+			]
+		);
+	}
+
+	#[test]
+	fn task_macro() {
+		let task = crate::task!(
+			"x (A) 2016-05-20 2016-04-30 measure space for +chapelShelving"
+		);
+
+		let task_should = Task::build()
+			.state(State::Done)
+			.priority(Priority::A)
+			.date_compound(DateCompound::completed(
+				Date::from_ymd(2016, 4, 30),
+				Date::from_ymd(2016, 5, 20),
+			))
+			.build("measure space for +chapelShelving");
+
+		assert_eq!(task, task_should);
+		assert!(!crate::__is_task_literal(""));
+		assert!(!crate::__is_task_literal("a\nb"));
+		assert!(crate::__is_task_literal("a b"));
+	}
+
+	#[test]
+	fn todo_file_merge() {
+		use crate::file::MergePolicy;
+
+		let local = TodoFile::from_str(
+			"(A) Call Mom\nx 2021-09-02 Buy milk\nWater plants",
+		)
+		.unwrap();
+		let remote = TodoFile::from_str(
+			"x 2021-09-01 Call Mom\nx 2021-09-01 Buy milk\nWater plants\nBuy eggs",
+		)
+		.unwrap();
+
+		let mut merged = local.clone();
+		merged.merge(&remote, MergePolicy::Union);
+		assert_eq!(
+			merged.to_string(),
+			"(A) Call Mom\nx 2021-09-02 Buy milk\nWater plants\nx 2021-09-01 Call \
+			 Mom\nx 2021-09-01 Buy milk\nBuy eggs"
+		);
+
+		let mut merged = local.clone();
+		merged.merge(&remote, MergePolicy::PreferNewerCompletion);
+		assert_eq!(
+			merged.to_string(),
+			"x 2021-09-01 Call Mom\nx 2021-09-02 Buy milk\nWater plants\nBuy eggs"
+		);
+
+		let mut merged = local;
+		merged.merge(
+			&remote,
+			MergePolicy::Resolve(Box::new(|local, _| {
+				let mut task = local.clone();
+				task.priority = Some(Priority::Z);
+				task
+			})),
+		);
+		assert_eq!(
+			merged.to_string(),
+			"(Z) Call Mom\nx (Z) 2021-09-02 Buy milk\nWater plants\nBuy eggs"
+		);
+	}
+
+	#[test]
+	fn todo_file_dedupe() {
+		let mut todo = TodoFile::from_str(
+			"(A) Call Mom @phone due:2021-09-01\nBuy milk\n(A) Call   Mom \
+			 due:2021-09-01 @phone\nx (A) Call Mom @phone due:2021-09-01\nBuy \
+			 milk\nCall Mom (A) @phone due:2021-09-01",
+		)
+		.unwrap();
+
+		assert_eq!(
+			todo.duplicates().collect::<Vec<_>>(),
+			vec![(2, 0), (4, 1)]
+		);
+		assert_eq!(todo.dedupe(), 2);
+		assert_eq!(todo.duplicates().count(), 0);
+		assert_eq!(
+			todo.to_string(),
+			"(A) Call Mom @phone due:2021-09-01\nBuy milk\nx (A) Call Mom @phone \
+			 due:2021-09-01\nCall Mom (A) @phone due:2021-09-01"
+		);
+	}
+
+	#[test]
+	fn task_format_parse_identity() {
+		use crate::task::check_format_parse_identity;
+
+		let task = Task::build()
+			.state(State::Done)
+			.priority(Priority::A)
+			.date_compound(DateCompound::completed(
+				Date::from_ymd(2016, 4, 30),
+				Date::from_ymd(2016, 5, 20),
+			))
+			.build("measure space for +chapelShelving @chapel due:2016-05-30");
+		assert_eq!(check_format_parse_identity(&task), Ok(()));
+
+		for description in [
+			"",
+			"(A) Call Mom",
+			"2016-04-30 Call Mom",
+			"Call\nMom",
+			"x Call Mom",
+		] {
+			let task = Task::build().build(description);
+			let err = check_format_parse_identity(&task).unwrap_err();
+			assert_eq!(err.formatted(), description);
+		}
+	}
+
+	#[test]
+	fn priority_style() {
+		use crate::priority::{PriorityAppearance, PriorityStyle};
+
+		let mut style = PriorityStyle::default();
+		assert_eq!(style.label(Priority::Z), "Z");
+		assert_eq!(style.color(Priority::Z), None);
+		assert_eq!(style.symbol(Priority::B), Some("\u{1F7E1}"));
+
+		let _ = style.set(
+			Priority::D,
+			PriorityAppearance::new("someday")
+				.with_symbol("\u{1F4A4}")
+				.with_color(8),
+		);
+		assert_eq!(style.label(Priority::D), "someday");
+		assert_eq!(style.symbol(Priority::D), Some("\u{1F4A4}"));
+		assert_eq!(style.color(Priority::D), Some(8));
+
+		let _ = style.remove(Priority::D);
+		assert_eq!(style.label(Priority::D), "D");
+		assert_eq!(style.get(Priority::D), None);
+	}
+
+	#[cfg(feature = "serde")]
+	#[test]
+	fn priority_style_serde() {
+		use crate::priority::PriorityStyle;
+
+		let style = PriorityStyle::default();
+		let json = serde_json::to_string(&style).unwrap();
+		let style_in: PriorityStyle = serde_json::from_str(&json).unwrap();
+
+		assert_eq!(style_in, style);
+	}
+
+	#[test]
+	fn todo_file_opaque_lines() {
+		use crate::file::Entry;
+
+		let input = "# todo.txt\n\n(A) Call Mom @phone\n   \n# errands\nBuy \
+		             milk\nWater plants\n\n";
+		let mut todo = TodoFile::from_str(input).unwrap();
+
+		assert_eq!(todo.len(), 3);
+		assert_eq!(todo.entries().count(), 8);
+		assert_eq!(todo.to_string(), input);
+
+		let _ = todo.remove(1);
+		assert_eq!(
+			todo.to_string(),
+			"# todo.txt\n\n(A) Call Mom @phone\n   \n# errands\nWater plants\n\n"
+		);
+
+		let _ = todo.push(Task::build().build("Buy eggs"));
+		assert_eq!(
+			todo.entries().last(),
+			Some(Entry::Task(&Task::build().build("Buy eggs")))
+		);
+
+		todo.clear();
+		assert_eq!(todo.entries().count(), 0);
+		assert_eq!(todo.to_string(), "");
+	}
+
+	#[cfg(feature = "cli")]
+	#[test]
+	fn cli_commands() {
+		let dir = std::env::temp_dir()
+			.join(format!("tdtxt-cli-{}", std::process::id()));
+		std::fs::create_dir_all(&dir).unwrap();
+		let todo_path = dir.join("todo.txt");
+		let done_path = dir.join("done.txt");
+		let path = todo_path.to_str().unwrap();
+
+		let run = |args: &[&str]| {
+			let mut out = Vec::new();
+			crate::cli::run_with_clock(
+				std::iter::once(path).chain(args.iter().copied()),
+				&mut out,
+				crate::FixedClock(Date::from_ymd(2021, 8, 1)),
+			)
+			.map(|_| String::from_utf8(out).unwrap())
+		};
+
+		assert_eq!(
+			run(&["add", "Water", "plants"]).unwrap(),
+			"1 Water plants\n"
+		);
+		assert_eq!(run(&["add", "(A) Call Mom"]).unwrap(), "2 (A) Call Mom\n");
+		assert_eq!(run(&["add", "(B) Buy milk"]).unwrap(), "3 (B) Buy milk\n");
+		assert_eq!(
+			run(&["list"]).unwrap(),
+			"2 (A) Call Mom\n3 (B) Buy milk\n1 Water plants\n"
+		);
+		assert_eq!(
+			run(&["do", "2"]).unwrap(),
+			"2 x 2021-08-01 2021-08-01 Call Mom pri:A\n"
+		);
+		assert_eq!(
+			run(&["list"]).unwrap(),
+			"3 (B) Buy milk\n1 Water plants\n"
+		);
+		assert_eq!(
+			run(&["list", "-milk", "Water|Call"]).unwrap(),
+			"1 Water plants\n"
+		);
+		assert!(run(&["list", "pri:1"]).is_err());
+		assert!(run(&["do", "4"]).is_err());
+		assert!(run(&["unknown"]).is_err());
+
+		assert!(run(&["archive"]).unwrap().starts_with("archived 1 task(s)"));
+		assert_eq!(
+			std::fs::read_to_string(&todo_path).unwrap(),
+			"Water plants\n(B) Buy milk\n"
+		);
+		assert_eq!(
+			std::fs::read_to_string(&done_path).unwrap(),
+			"x 2021-08-01 2021-08-01 Call Mom pri:A\n"
+		);
+
+		std::fs::remove_dir_all(&dir).unwrap();
+	}
+
+	#[test]
+	fn todo_file_line_endings() {
+		use crate::file::LineEnding;
+
+		assert_eq!(
+			Task::from_str("(A) Call Mom @phone\r"),
+			Ok(Task::build().priority(Priority::A).build("Call Mom @phone"))
+		);
+
+		let input = b"Call Mom\r\nBuy milk";
+		let mut parser = Parser::new(input);
+		assert_eq!(
+			Task::parse(&mut parser),
+			Ok(Task::build().build("Call Mom"))
+		);
+		assert_eq!(
+			Task::parse(&mut parser),
+			Ok(Task::build().build("Buy milk"))
+		);
+
+		assert_eq!(LineEnding::detect(""), LineEnding::Lf);
+		assert_eq!(LineEnding::detect("a\r\nb\nc\r\n"), LineEnding::CrLf);
+		assert_eq!(LineEnding::detect("a\r\nb\nc\n"), LineEnding::Lf);
+
+		let input =
+			"# todo\r\n(A) Call Mom @phone\r\n\r\nBuy milk +errands\r\n";
+		let mut todo = TodoFile::from_str(input).unwrap();
+		assert_eq!(todo.line_ending(), LineEnding::CrLf);
+		assert_eq!(
+			todo.tasks()[1].description().projects().collect::<Vec<_>>(),
+			vec!["errands"]
+		);
+		assert_eq!(todo.to_string(), input);
+
+		todo.set_line_ending(LineEnding::Lf);
+		assert_eq!(todo.to_string(), input.replace("\r\n", "\n"));
+	}
+
+	#[test]
+	fn todo_file_id_generator() {
+		use crate::id::SequenceIdGenerator;
+
+		let mut todo = TodoFile::from_str("Call Mom id:3\nBuy milk").unwrap();
+
+		// No generator set
+		let _ = todo.add(Task::build().build("Water plants"));
+		assert_eq!(
+			todo.tasks()[2].description().description(),
+			"Water plants"
+		);
+
+		todo.set_id_generator(SequenceIdGenerator::new());
+		let _ = todo.add(Task::build().build("Call Dad"));
+		let _ = todo.add(Task::build().build(""));
+		assert_eq!(
+			todo.tasks()[3].description().custom().collect::<Vec<_>>(),
+			vec![("id", "4")]
+		);
+		assert_eq!(todo.tasks()[4].description().description(), "id:5");
+
+		let mut counter = 0;
+		todo.set_id_generator(move |_: &[Task]| {
+			counter += 1;
+			format!("custom{}", counter)
+		});
+		let _ = todo.add(Task::build().build("Call Grandma"));
+		assert_eq!(
+			todo.tasks()[5].description().description(),
+			"Call Grandma id:custom1"
+		);
+
+		todo.clear_id_generator();
+		let _ = todo.add(Task::build().build("Call Grandpa"));
+		assert_eq!(
+			todo.tasks()[6].description().description(),
+			"Call Grandpa"
+		);
+	}
+
+	#[test]
+	fn sequence_id_generator_overflow() {
+		use crate::id::SequenceIdGenerator;
+
+		let mut todo = TodoFile::from_str(
+			"Call Mom id:18446744073709551615\nBuy milk id:1\n",
+		)
+		.unwrap();
+		todo.set_id_generator(SequenceIdGenerator::new());
+
+		// The smallest unused id is used once the ids are exhausted
+		let _ = todo.add(Task::build().build("Call Dad"));
+		let _ = todo.add(Task::build().build("Call Grandma"));
+		assert_eq!(todo.tasks()[2].description().tag("id"), Some("2"));
+		assert_eq!(todo.tasks()[3].description().tag("id"), Some("3"));
+	}
+
+	#[cfg(all(feature = "ulid", feature = "uuid"))]
+	#[test]
+	fn id_generator_random() {
+		use crate::id::{IdGenerator, UlidGenerator, UuidGenerator};
+
+		let ulid = UlidGenerator.generate(&[]);
+		assert_eq!(ulid.len(), 26);
+		assert_ne!(ulid, UlidGenerator.generate(&[]));
+
+		let uuid = UuidGenerator.generate(&[]);
+		assert_eq!(uuid.len(), 36);
+		assert_ne!(uuid, UuidGenerator.generate(&[]));
+	}
+
+	#[test]
+	fn todo_file_from_bytes() {
+		use crate::decode::{DecodeOptions, InvalidUtf8Policy};
+
+		let bytes = b"\xEF\xBB\xBF(A) Call mom\n\xFF broken\nBuy milk\n";
+
+		let err =
+			TodoFile::from_bytes(bytes, DecodeOptions::new()).unwrap_err();
+		assert_eq!(
+			err.to_string(),
+			"failed to parse todo file: invalid utf-8 on line 2"
+		);
+
+		let todo = TodoFile::from_bytes(
+			bytes,
+			DecodeOptions::new().invalid_utf8(InvalidUtf8Policy::Replace),
+		)
+		.unwrap();
+		assert_eq!(todo.len(), 3);
+		assert_eq!(todo.tasks()[0].priority, Some(Priority::A));
+		assert_eq!(
+			todo.tasks()[1].description.description(),
+			"\u{FFFD} broken"
+		);
+
+		let todo = TodoFile::from_bytes(
+			bytes,
+			DecodeOptions::new().invalid_utf8(InvalidUtf8Policy::SkipLine),
+		)
+		.unwrap();
+		assert_eq!(todo.to_string(), "(A) Call mom\nBuy milk\n");
+
+		// Without skipping the BOM, it becomes part of the first description.
+		let todo = TodoFile::from_bytes(
+			b"\xEF\xBB\xBFBuy milk",
+			DecodeOptions::new().skip_bom(false),
+		)
+		.unwrap();
+		assert_eq!(
+			todo.tasks()[0].description.description(),
+			"\u{FEFF}Buy milk"
+		);
+
+		assert!(Task::from_bytes(
+			b"\xFF",
+			DecodeOptions::new().invalid_utf8(InvalidUtf8Policy::SkipLine)
+		)
+		.is_err());
+	}
+
+	#[test]
+	fn todo_file_save_atomic() {
+		let dir = std::env::temp_dir()
+			.join(format!("tdtxt-save-{}", std::process::id()));
+		std::fs::create_dir_all(&dir).unwrap();
+		let path = dir.join("todo.txt");
+
+		std::fs::write(&path, "Old task\n").unwrap();
+
+		#[cfg(unix)]
+		{
+			use std::os::unix::fs::PermissionsExt;
+
+			std::fs::set_permissions(
+				&path,
+				std::fs::Permissions::from_mode(0o600),
+			)
+			.unwrap();
+		}
+
+		let todo =
+			TodoFile::from_str("(A) Call Mom\n# notes\nBuy milk\n").unwrap();
+		todo.save_atomic(&path).unwrap();
+		todo.save_atomic(&path).unwrap();
+
+		assert_eq!(
+			std::fs::read_to_string(&path).unwrap(),
+			"(A) Call Mom\n# notes\nBuy milk\n"
+		);
+		// No temporary files are left behind.
+		assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+		#[cfg(unix)]
+		{
+			use std::os::unix::fs::PermissionsExt;
+
+			let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+			assert_eq!(mode & 0o777, 0o600);
+		}
+
+		assert!(todo
+			.save_atomic(dir.join("missing").join("todo.txt"))
+			.is_err());
+		assert!(todo.save_atomic("/").is_err());
+
+		std::fs::remove_dir_all(&dir).unwrap();
+	}
+
+	#[test]
+	fn summarize_project() {
+		let todo = TodoFile::from_str(
+			"Outline +Novel due:2021-09-01 estimate:1.5h\nDraft +Novel \
+			 +Writing due:2021-08-15 estimate:30m due:invalid\nx Title \
+			 +Novel due:2021-01-01 estimate:5h\nResearch +Novelty \
+			 estimate:3h\nEdit +Novel estimate:many\n",
+		)
+		.unwrap();
+
+		let summary = crate::summarize_project(&todo, "Novel");
+		assert_eq!(
+			summary.to_string(),
+			"+Novel open:3 done:1 due:2021-08-15 estimate:2h"
+		);
+		assert_eq!(
+			summary.description.projects().collect::<Vec<_>>(),
+			["Novel"]
+		);
+
+		assert_eq!(
+			crate::summarize_project(&todo, "+Empty").to_string(),
+			"+Empty open:0 done:0"
+		);
+
+		// Out of range estimates are ignored, large sums saturate
+		let todo = TodoFile::from_str(
+			"A +Huge estimate:1e300h
+B +Huge estimate:3e17h
+C +Huge \
+			 estimate:3e17h
+",
+		)
+		.unwrap();
+		assert_eq!(
+			crate::summarize_project(&todo, "Huge").to_string(),
+			format!("+Huge open:3 done:0 estimate:{}m", u64::MAX)
+		);
+	}
+
+	#[test]
+	fn date_add_days() {
+		use crate::date::{Date, Days};
+
+		let date = Date::from_ymd(2020, 2, 28);
+		assert_eq!(
+			date.checked_add_days(Days(1)),
+			Some(Date::from_ymd(2020, 2, 29))
+		);
+		assert_eq!(
+			date.checked_add_days(Days(2)),
+			Some(Date::from_ymd(2020, 3, 1))
+		);
+		assert_eq!(
+			date.checked_add_days(Days(366)),
+			Some(Date::from_ymd(2021, 2, 28))
+		);
+		assert_eq!(date.checked_add_days(Days(0)), Some(date));
+		assert_eq!(
+			Date::from_ymd(2000, 1, 1).checked_add_days(Days(-1)),
+			Some(Date::from_ymd(1999, 12, 31))
+		);
+		assert_eq!(
+			Date::from_ymd(1900, 3, 1).checked_add_days(Days(-1)),
+			Some(Date::from_ymd(1900, 2, 28))
+		);
+		assert_eq!(date.checked_add_days(Days(i64::MAX)), None);
+		assert_eq!(date.checked_add_days(Days(i64::MIN)), None);
+	}
+
+	#[test]
+	fn todo_file_postpone() {
+		use crate::date::{Days, DUE_TAG, THRESHOLD_TAG};
+
+		let mut todo = TodoFile::from_str(
+			"Pay rent due:2021-07-30 t:2021-07-25\nx Call Mom due:2021-07-01\nBuy \
+			 milk due:soon\nClean up\n",
+		)
+		.unwrap();
+
+		let events = std::rc::Rc::new(std::cell::Cell::new(0));
+		let counter = std::rc::Rc::clone(&events);
+		let _ = todo.subscribe(move |_| counter.set(counter.get() + 1));
+
+		// Only tasks with a valid due date are changed.
+		assert_eq!(todo.postpone(|_| true, Days(3)), 2);
+		assert_eq!(events.get(), 2);
+		assert_eq!(
+			todo.to_string(),
+			"Pay rent due:2021-08-02 t:2021-07-25\nx Call Mom \
+			 due:2021-07-04\nBuy milk due:soon\nClean up\n"
+		);
+
+		assert_eq!(
+			todo.shift_dates(
+				|task| task.state == State::Open,
+				Days(-2),
+				&[DUE_TAG, THRESHOLD_TAG]
+			),
+			1
+		);
+		assert_eq!(
+			todo.tasks()[0].to_string(),
+			"Pay rent due:2021-07-31 t:2021-07-23"
+		);
+	}
+
+	#[test]
+	fn todo_file_dirty_tracking() {
+		use crate::file::LineEnding;
+
+		let content = "(A) Call Mom\n# notes\nBuy milk\n";
+		let mut todo = TodoFile::from_str(content).unwrap();
+		assert_eq!(todo.revision(), 0);
+		assert!(!todo.is_dirty());
+		assert_eq!(todo.checksum(), TodoFile::checksum_of(content.as_bytes()));
+		assert_eq!(TodoFile::checksum_of(b""), 0xcbf2_9ce4_8422_2325);
+		assert_eq!(TodoFile::checksum_of(b"a"), 0xaf63_dc4c_8601_ec8c);
+
+		let checksum = todo.checksum();
+
+		let _ = todo.modify(1, |task| task.state = State::Done);
+		assert_eq!(todo.revision(), 1);
+		assert!(todo.is_dirty());
+		assert_ne!(todo.checksum(), checksum);
+
+		todo.mark_clean();
+		assert!(!todo.is_dirty());
+
+		// Modifications which leave the task unchanged are ignored.
+		assert_eq!(todo.modify(1, |task| task.state = State::Done), Some(()));
+		assert_eq!(todo.modify(0, |task| task.normalize()), Some(false));
+		let unchanged = todo.tasks()[1].clone();
+		let _ = todo.replace(1, unchanged);
+		assert_eq!(todo.revision(), 1);
+		assert!(!todo.is_dirty());
+
+		// Setting the same line ending is not a modification.
+		todo.set_line_ending(LineEnding::Lf);
+		assert!(!todo.is_dirty());
+		todo.set_line_ending(LineEnding::CrLf);
+		assert_eq!(todo.revision(), 2);
+
+		let _ = todo.push(Task::from_str("Water plants").unwrap());
+		let _ = todo.replace(2, Task::from_str("Water flowers").unwrap());
+		let _ = todo.remove(2);
+		assert_eq!(todo.revision(), 5);
+
+		// The revision is not part of the value.
+		let mut other = TodoFile::from_str(content).unwrap();
+		let _ = other.modify(1, |task| task.state = State::Done);
+		other.set_line_ending(LineEnding::CrLf);
+		assert_eq!(todo, other);
+
+		todo.clear();
+		assert_eq!(todo.revision(), 6);
+		todo.clear();
+		assert_eq!(todo.revision(), 6);
+	}
+
+	#[test]
+	fn todo_file_normalize_dates() {
+		use crate::file::{DateField, DateNormalization};
+
+		let mut todo = TodoFile::from_str(
+			"(A) 2021.7.1 Call Mom t:2021/7/2 due:2021-07-03\nx 2021-08-01 \
+			 2021-07-01 Done due:2021/8/1\n2021/7/1\n2021/7/1 2021/6/1\n\
+			 Clean up due:tomorrow\n",
+		)
+		.unwrap();
+
+		let report = todo.normalize_dates();
+		assert_eq!(
+			report,
+			vec![
+				DateNormalization {
+					index: 0,
+					field: DateField::Created,
+					original: "2021.7.1".into(),
+					normalized: Date::from_ymd(2021, 7, 1),
+				},
+				DateNormalization {
+					index: 0,
+					field: DateField::Tag("t".into()),
+					original: "2021/7/2".into(),
+					normalized: Date::from_ymd(2021, 7, 2),
+				},
+				DateNormalization {
+					index: 1,
+					field: DateField::Tag("due".into()),
+					original: "2021/8/1".into(),
+					normalized: Date::from_ymd(2021, 8, 1),
+				},
+			]
+		);
+
+		assert_eq!(
+			todo.tasks()[0].date_compound,
+			Some(DateCompound::created(Date::from_ymd(2021, 7, 1)))
+		);
+		assert_eq!(
+			todo.to_string(),
+			"(A) 2021-07-01 Call Mom t:2021-07-02 due:2021-07-03\nx 2021-08-01 \
+			 2021-07-01 Done due:2021-08-01\n2021/7/1\n2021/7/1 2021/6/1\nClean \
+			 up due:tomorrow\n"
+		);
+		assert_eq!(todo.revision(), 2);
+
+		assert!(todo.normalize_dates().is_empty());
+	}
+
+	#[test]
+	fn todo_file_retain_drain_done() {
+		let mut todo = TodoFile::from_str(
+			"# header\nx Call Mom +Family\nBuy milk\n\nx Water plants \
+			 +Garden\n# footer\nClean up +Garden\n",
+		)
+		.unwrap();
+		todo.build_index();
+
+		let removed = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+		let events = std::rc::Rc::clone(&removed);
+		let _ = todo.subscribe(move |event| {
+			if let crate::file::TaskEvent::Removed { index, .. } = event {
+				events.borrow_mut().push(*index);
+			}
+		});
+
+		let done = todo.drain_done();
+		assert_eq!(
+			done.iter().map(ToString::to_string).collect::<Vec<_>>(),
+			["x Call Mom +Family", "x Water plants +Garden"]
+		);
+		assert_eq!(*removed.borrow(), [2, 0]);
+		assert_eq!(
+			todo.to_string(),
+			"# header\nBuy milk\n\n# footer\nClean up +Garden\n"
+		);
+		assert_eq!(
+			todo.tasks_with_project("Garden")
+				.map(|(idx, _)| idx)
+				.collect::<Vec<_>>(),
+			[1]
+		);
+		assert!(todo.drain_done().is_empty());
+
+		let revision = todo.revision();
+		assert_eq!(todo.retain(|_| true), 0);
+		assert_eq!(todo.revision(), revision);
+
+		assert_eq!(
+			todo.retain(|task| task.description.projects().count() == 0),
+			1
+		);
+		assert_eq!(todo.to_string(), "# header\nBuy milk\n\n# footer\n");
+		assert!(todo.tasks_with_project("Garden").next().is_none());
+	}
+
+	#[test]
+	fn todo_file_sort_by_keys() {
+		use crate::sort::SortKey;
+
+		let mut todo = TodoFile::from_str(
+			"# header\nx 2021-08-02 2021-07-01 Call Mom\nbuy milk \
+			 due:2021-08-05\n(B) 2021-07-02 Pay rent due:soon\nx \
+			 2021-08-01 2021-07-03 Answer mails\n(A) Water plants \
+			 due:2021-08-03 due:2021-08-04\nBuy bread\n",
+		)
+		.unwrap();
+		let tasks = todo.tasks().to_vec();
+		let sorted = |todo: &TodoFile| {
+			todo.iter()
+				.map(|task| tasks.iter().position(|t| t == task).unwrap())
+				.collect::<Vec<_>>()
+		};
+
+		todo.sort_by_keys(&[SortKey::Priority]);
+		assert_eq!(sorted(&todo), [4, 2, 0, 1, 3, 5]);
+
+		todo.sort_by_keys(&[SortKey::Alphabetical]);
+		assert_eq!(sorted(&todo), [3, 5, 1, 0, 2, 4]);
+
+		todo.sort_by_keys(&[SortKey::DueDate, SortKey::Alphabetical]);
+		assert_eq!(sorted(&todo), [4, 1, 3, 5, 0, 2]);
+
+		todo.sort_by_keys(&[SortKey::CreatedDate]);
+		assert_eq!(sorted(&todo), [0, 2, 3, 4, 1, 5]);
+
+		todo.sort_by_keys(&[SortKey::CompletedDate]);
+		assert_eq!(sorted(&todo), [3, 0, 2, 4, 1, 5]);
+
+		todo.sort_by_keys(&[SortKey::State, SortKey::Priority]);
+		assert_eq!(sorted(&todo), [4, 2, 1, 5, 3, 0]);
+
+		// The opaque line stays in place
+		assert!(todo.to_string().starts_with("# header\n(A) Water plants"));
+
+		// Sorting an already sorted list is not a modification
+		let revision = todo.revision();
+		todo.sort_by_keys(&[SortKey::State, SortKey::Priority]);
+		todo.sort_by_keys(&[]);
+		assert_eq!(todo.revision(), revision);
+	}
+
+	#[cfg(feature = "todo_txt-compat")]
+	#[test]
+	fn todo_txt_compat() {
+		use std::convert::TryFrom;
+
+		let task = Task::from_str(
+			"x (A) 2021-08-02 2021-07-01 Call Mom +Family @phone due:2021-08-03 \
+			 t:2021-08-01 id:7",
+		)
+		.unwrap();
+
+		let other = todo_txt::Task::from(&task);
+		assert!(other.finished);
+		assert_eq!(other.priority, 'A');
+		assert_eq!(
+			other.create_date,
+			todo_txt::Date::from_ymd_opt(2021, 7, 1)
+		);
+		assert_eq!(
+			other.finish_date,
+			todo_txt::Date::from_ymd_opt(2021, 8, 2)
+		);
+		assert_eq!(other.due_date, todo_txt::Date::from_ymd_opt(2021, 8, 3));
+		assert_eq!(other.projects, ["Family"]);
+		assert_eq!(other.contexts, ["phone"]);
+		assert_eq!(other.tags.get("id").map(String::as_str), Some("7"));
+
+		assert_eq!(Task::try_from(other).unwrap(), task);
+
+		let mut other =
+			todo_txt::Task::from(Task::from_str("Buy milk").unwrap());
+		other.priority = todo_txt::Priority::from(1);
+		other.tags.insert("a".into(), "b".into());
+		assert_eq!(
+			Task::try_from(&other).unwrap().to_string(),
+			"(B) Buy milk a:b"
+		);
+
+		assert!(Task::try_from(todo_txt::Task::default()).is_err());
+	}
+
+	#[test]
+	fn todo_file_parse_report() {
+		use crate::report::ParseReport;
+
+		let (todo, report) = TodoFile::parse_with_report(
+			"# header\n(A) 2021-07-01 Call Mom +Family @phone\n\n(b) Buy \
+			 milk\nx 2021/8/1 Water plants due:2021-08-01\nx (a)\n",
+		)
+		.unwrap();
+
+		assert_eq!(todo.len(), 4);
+		assert_eq!(
+			report,
+			ParseReport {
+				lines: 6,
+				tasks: 4,
+				blank: 1,
+				comments: 1,
+				done: 2,
+				with_priority: 1,
+				with_dates: 1,
+				with_projects: 1,
+				with_contexts: 1,
+				with_custom: 1,
+				lowercase_priority_hints: 1,
+				lenient_date_hints: 1,
+			}
+		);
+
+		assert_eq!(
+			TodoFile::parse_with_report("").unwrap().1,
+			ParseReport::default()
+		);
+	}
+
+	#[test]
+	fn filter() {
+		use crate::filter::Filter;
+
+		let todo = TodoFile::from_str(
+			"(A) Call Mom +Family @phone due:2021-07-30\n(C) Buy milk \
+			 @store id:1\nx (B) Pay rent due:2021-07-01\nWater plants \
+			 +Garden due:2021-08-05 id:2\n",
+		)
+		.unwrap();
+
+		let matches = |filter: Filter| {
+			todo.filter(&filter).map(|(idx, _)| idx).collect::<Vec<_>>()
+		};
+
+		assert_eq!(matches(Filter::any()), [0, 1, 2, 3]);
+		assert_eq!(matches(Filter::default()), [0, 1, 2, 3]);
+		assert_eq!(matches(Filter::project("+Family")), [0]);
+		assert_eq!(matches(Filter::context("@store")), [1]);
+		assert_eq!(matches(Filter::context("store")), [1]);
+		assert_eq!(matches(Filter::tag("id")), [1, 3]);
+		assert_eq!(matches(Filter::tag_value("id", "2")), [3]);
+		assert_eq!(matches(Filter::text("milk")), [1]);
+		assert_eq!(matches(Filter::priority(Priority::C)), [1]);
+		assert_eq!(matches(Filter::no_priority()), [3]);
+		assert_eq!(matches(Filter::priority_at_least(Priority::B)), [0, 2]);
+		assert_eq!(matches(Filter::done()), [2]);
+		assert_eq!(matches(Filter::not_done()), [0, 1, 3]);
+		assert_eq!(
+			matches(Filter::due_before(Date::from_ymd(2021, 8, 1))),
+			[0, 2]
+		);
+		assert_eq!(matches(Filter::overdue(Date::from_ymd(2021, 8, 1))), [0]);
+		assert_eq!(
+			matches(Filter::project("Family").or(Filter::project("Garden"))),
+			[0, 3]
+		);
+		assert_eq!(matches(!Filter::tag("id")), [0, 2]);
+		assert_eq!(!!Filter::tag("id"), Filter::tag("id"));
+	}
+
+	#[test]
+	fn parse_prefix() {
+		assert!(DateCompound::from_str("2000-01-01 rest").is_err());
+		assert_eq!(
+			DateCompound::parse_prefix("2000-01-01 rest").unwrap(),
+			(DateCompound::created(Date::from_ymd(2000, 1, 1)), "rest")
+		);
+		assert_eq!(
+			DateCompound::parse_prefix("2000-01-02 2000-01-01  rest").unwrap(),
+			(
+				DateCompound::completed(
+					Date::from_ymd(2000, 1, 1),
+					Date::from_ymd(2000, 1, 2)
+				),
+				" rest"
+			)
+		);
+		assert_eq!(
+			Date::parse_prefix("2000-01-01").unwrap(),
+			(Date::from_ymd(2000, 1, 1), "")
+		);
+		assert!(Date::parse_prefix("2000-01-01rest").is_err());
+		assert!(Date::parse_prefix("rest").is_err());
+
+		assert_eq!(
+			Priority::parse_prefix("(A) Call Mom").unwrap(),
+			(Priority::A, "Call Mom")
+		);
+		assert_eq!(
+			State::parse_prefix("x (A)").unwrap(),
+			(State::Done, "(A)")
+		);
+		assert!(State::parse_prefix("xylophone").is_err());
+	}
+
+	#[test]
+	fn filter_parse() {
+		use crate::filter::Filter;
+
+		assert_eq!(Filter::parse("").unwrap(), Filter::any());
+		assert_eq!(Filter::parse("  ").unwrap(), Filter::any());
+		assert_eq!(
+			Filter::parse("+GarageSale @phone pri:A -@home").unwrap(),
+			Filter::project("GarageSale")
+				.and(Filter::context("phone"))
+				.and(Filter::priority(Priority::A))
+				.and(!Filter::context("home"))
+		);
+		assert_eq!(
+			Filter::from_str("due:2021-08-01 milk|+Shopping --x").unwrap(),
+			Filter::tag_value("due", "2021-08-01")
+				.and(Filter::text("milk").or(Filter::project("Shopping")))
+				.and(Filter::text("x"))
+		);
+		// Incomplete tokens are matched as text
+		assert_eq!(
+			Filter::parse("+ @ key: :value").unwrap(),
+			Filter::text("+")
+				.and(Filter::text("@"))
+				.and(Filter::text("key:"))
+				.and(Filter::text(":value"))
+		);
+
+		assert!(Filter::parse("-").is_err());
+		assert!(Filter::parse("a||b").is_err());
+		assert!(Filter::parse("pri:").is_err());
+		assert!(Filter::parse("pri:AB").is_err());
+
+		let todo = TodoFile::from_str(
+			"(A) Call Mom +Family @phone\n(A) Call Dad +Family @home\nBuy milk\n",
+		)
+		.unwrap();
+		let filter = Filter::parse("+Family pri:A -@home").unwrap();
+		assert_eq!(
+			todo.filter(&filter).map(|(idx, _)| idx).collect::<Vec<_>>(),
+			[0]
+		);
+	}
+
+	#[test]
+	fn task_complete() {
+		let today = Date::from_ymd(2021, 8, 1);
+
+		let mut task = Task::from_str("(A) 2021-07-30 Call Mom").unwrap();
+		assert!(task.complete(today, CompletionPriority::Keep));
+		assert_eq!(task.to_string(), "x (A) 2021-08-01 2021-07-30 Call Mom");
+		assert!(!task.complete(today, CompletionPriority::Remove));
+
+		let mut task = Task::from_str("(B) Call Mom").unwrap();
+		assert!(task.complete(today, CompletionPriority::Remove));
+		assert_eq!(task.to_string(), "x 2021-08-01 2021-08-01 Call Mom");
+
+		let mut task = Task::from_str("(C) Call Mom pri:A +Family").unwrap();
+		assert!(task.complete(today, CompletionPriority::Tag));
+		assert_eq!(
+			task.to_string(),
+			"x 2021-08-01 2021-08-01 Call Mom pri:C +Family"
+		);
+		assert_eq!(Task::from_str(&task.to_string()).unwrap(), task);
+
+		let mut task = Task::from_str("Call Mom").unwrap();
+		assert!(task.complete(today, CompletionPriority::Tag));
+		assert_eq!(task.to_string(), "x 2021-08-01 2021-08-01 Call Mom");
+	}
+
+	#[test]
+	fn task_reopen() {
+		let today = Date::from_ymd(2021, 8, 1);
+
+		for line in &[
+			"(A) 2021-07-30 Call Mom +Family",
+			"(B) Call Mom",
+			"Call Mom due:2021-08-05",
+		] {
+			let original = Task::from_str(line).unwrap();
+
+			for &policy in &[CompletionPriority::Keep, CompletionPriority::Tag]
+			{
+				let mut task = original.clone();
+				assert!(task.complete(today, policy));
+				assert!(task.reopen());
+				assert_eq!(task.state, State::Open);
+				assert_eq!(task.priority, original.priority);
+				assert!(task
+					.date_compound
+					.unwrap()
+					.date_completed()
+					.is_none());
+				assert!(!task.reopen());
+			}
+		}
+
+		let mut task =
+			Task::from_str("x 2021-08-01 2021-07-30 pri:A Call pri:B Mom")
+				.unwrap();
+		assert!(task.reopen());
+		assert_eq!(task.to_string(), "(A) 2021-07-30 Call Mom");
+
+		// Invalid priorities are kept as tags
+		let mut task = Task::from_str("x Call Mom pri:a").unwrap();
+		assert!(task.reopen());
+		assert_eq!(task.to_string(), "Call Mom pri:a");
+	}
+
+	#[test]
+	fn task_toggle_state() {
+		let policy = CompletionPolicy::new(|| Date::from_ymd(2021, 8, 1));
+
+		let mut task = Task::from_str("(A) Call Mom +Family").unwrap();
+		assert_eq!(task.toggle_state(&policy), State::Done);
+		assert_eq!(
+			task.to_string(),
+			"x 2021-08-01 2021-08-01 Call Mom +Family pri:A"
+		);
+		assert_eq!(task.toggle_state(&policy), State::Open);
+		assert_eq!(task.to_string(), "(A) 2021-08-01 Call Mom +Family");
+
+		let policy =
+			policy.stamp_dates(false).priority(CompletionPriority::Keep);
+
+		let mut task = Task::from_str("(A) 2021-07-30 Call Mom").unwrap();
+		assert_eq!(task.toggle_state(&policy), State::Done);
+		assert_eq!(task.to_string(), "x (A) 2021-07-30 Call Mom");
+		assert_eq!(task.toggle_state(&policy), State::Open);
+		assert_eq!(task.to_string(), "(A) 2021-07-30 Call Mom");
+	}
+
+	#[test]
+	fn task_to_builder() {
+		for line in &[
+			"Call Mom",
+			"(A) Call Mom",
+			"2021-07-30 Call Mom",
+			"x (B) 2021-08-01 2021-07-30 Call Mom +Family",
+		] {
+			let task = Task::from_str(line).unwrap();
+
+			assert_eq!(
+				task.to_builder().build(task.description.clone()),
+				task
+			);
+			assert_eq!(
+				TaskBuilder::from(&task).build(task.description.clone()),
+				task
+			);
+		}
+
+		let task = Task::from_str("(A) 2021-07-30 Call Mom").unwrap();
+		let mut builder = task.to_builder();
+		builder.state(State::Done);
+
+		assert_eq!(
+			builder.build("Call Dad").to_string(),
+			"x (A) 2021-07-30 Call Dad"
+		);
+	}
+
+	#[test]
+	fn task_build_checked() {
+		let created = Date::from_ymd(2021, 7, 30);
+		let completed = Date::from_ymd(2021, 8, 1);
+
+		assert!(Task::build().build_checked("Call Mom").is_ok());
+		assert!(Task::build()
+			.priority(Priority::A)
+			.date_compound(created)
+			.build_checked("Call Mom +Family")
+			.is_ok());
+		assert!(Task::build()
+			.state(State::Done)
+			.date_compound(DateCompound::completed(created, created))
+			.build_checked("Call Mom")
+			.is_ok());
+
+		assert_eq!(
+			Task::build()
+				.date_compound(DateCompound::completed(created, completed))
+				.build_checked("Call Mom"),
+			Err(BuildError::CompletedNotDone)
+		);
+		assert_eq!(
+			Task::build()
+				.state(State::Done)
+				.date_compound(DateCompound::completed(completed, created))
+				.build_checked("Call Mom"),
+			Err(BuildError::CompletedBeforeCreated)
+		);
+		assert_eq!(
+			Task::build().build_checked("Call Mom\nBuy milk"),
+			Err(BuildError::MultilineDescription)
+		);
+		assert_eq!(
+			Task::build().build_checked("Call Mom\r"),
+			Err(BuildError::MultilineDescription)
+		);
+
+		assert!(Task::build().rec("+1w").build_checked("Call Mom").is_ok());
+		for recurrence in &["", "1 w", "1w\nx", "a:b"] {
+			assert_eq!(
+				Task::build().rec(*recurrence).build_checked("Call Mom"),
+				Err(BuildError::InvalidRecurrence((*recurrence).to_owned()))
+			);
+		}
+
+		for description in
+			&["x marks the spot", "(A) Call Mom", "2021-08-01", ""]
+		{
+			assert!(matches!(
+				Task::build().build_checked(*description),
+				Err(BuildError::RoundTrip(_))
+			));
+		}
+	}
+
+	#[test]
+	fn task_builder_tags() {
+		let mut builder = Task::build();
+		builder
+			.priority(Priority::A)
+			.due(Date::from_ymd(2021, 8, 1))
+			.rec("+1m");
+
+		let task = builder.build("Pay rent +Home");
+		assert_eq!(
+			task.to_string(),
+			"(A) Pay rent +Home due:2021-08-01 rec:+1m"
+		);
+		assert_eq!(
+			task.description.custom().collect::<Vec<_>>(),
+			[("due", "2021-08-01"), ("rec", "+1m")]
+		);
+
+		// The builder can be reused and overwrites existing tags
+		let task = builder
+			.threshold(Date::from_ymd(2021, 7, 25))
+			.build("Pay rent due:2021-09-01");
+		assert_eq!(
+			task.to_string(),
+			"(A) Pay rent due:2021-08-01 t:2021-07-25 rec:+1m"
+		);
+
+		assert_eq!(
+			Task::build()
+				.due(Date::from_ymd(2021, 8, 1))
+				.build("")
+				.to_string(),
+			"due:2021-08-01"
+		);
+	}
+
+	#[test]
+	fn task_priority_mutation() {
+		let mut task = Task::from_str("Call Mom").unwrap();
+
+		assert_eq!(task.set_priority(Priority::Y), None);
+		assert_eq!(task.to_string(), "(Y) Call Mom");
+		assert_eq!(task.bump_priority(PriorityStep::Lower), Some(Priority::Z));
+		assert_eq!(task.bump_priority(PriorityStep::Lower), Some(Priority::Z));
+		assert_eq!(task.set_priority(Priority::B), Some(Priority::Z));
+		assert_eq!(
+			task.bump_priority(PriorityStep::Higher),
+			Some(Priority::A)
+		);
+		assert_eq!(
+			task.bump_priority(PriorityStep::Higher),
+			Some(Priority::A)
+		);
+		assert_eq!(task.to_string(), "(A) Call Mom");
+		assert_eq!(task.clear_priority(), Some(Priority::A));
+		assert_eq!(task.clear_priority(), None);
+		assert_eq!(task.to_string(), "Call Mom");
+
+		assert_eq!(Priority::M.step(PriorityStep::Higher), Priority::L);
+		assert_eq!(Priority::M.step(PriorityStep::Lower), Priority::N);
+	}
+
+	#[test]
+	fn priority_stepping() {
+		let all = Priority::all().collect::<Vec<_>>();
+
+		assert_eq!(all.len(), 26);
+		assert_eq!(Priority::all().next_back(), Some(Priority::Z));
+
+		for (idx, priority) in all.iter().enumerate() {
+			assert_eq!(priority.as_char(), char::from(b'A' + idx as u8));
+			assert_eq!(priority.higher(), all[idx.saturating_sub(1)]);
+			assert_eq!(priority.lower(), all[(idx + 1).min(25)]);
+		}
+	}
+
+	#[test]
+	fn task_ord() {
+		use std::cmp::Ordering;
+
+		use crate::description::DescriptionOptions;
+		use crate::sort::{SortKey, TaskOrder};
+
+		let lines = [
+			"x (A) 2021-08-01 2021-07-01 Call Mom",
+			"x (A) 2021-08-02 2021-07-01 Call Mom",
+			"Buy milk",
+			"(B) Call Mom",
+			"(A) 2021-07-02 Call Mom",
+			"(A) 2021-07-01 Call Dad",
+			"(A) Call Mom",
+		];
+		let mut tasks = lines
+			.iter()
+			.map(|line| Task::from_str(line).unwrap())
+			.collect::<Vec<_>>();
+
+		tasks.sort();
+		assert_eq!(
+			tasks.iter().map(ToString::to_string).collect::<Vec<_>>(),
+			[
+				"(A) 2021-07-01 Call Dad",
+				"(A) 2021-07-02 Call Mom",
+				"(A) Call Mom",
+				"(B) Call Mom",
+				"Buy milk",
+				"x (A) 2021-08-01 2021-07-01 Call Mom",
+				"x (A) 2021-08-02 2021-07-01 Call Mom",
+			]
+		);
+
+		// Consistent with `Eq`
+		for a in &tasks {
+			for b in &tasks {
+				assert_eq!(a.cmp(b) == Ordering::Equal, a == b);
+			}
+		}
+
+		// Same effective priority or text, but not equal
+		let with_priority =
+			Task::from_str("x (A) 2021-08-01 2021-07-01 Call pri:A").unwrap();
+		let without_priority =
+			Task::from_str("x 2021-08-01 2021-07-01 Call pri:A").unwrap();
+		assert_ne!(with_priority, without_priority);
+		assert_eq!(with_priority.cmp(&without_priority), Ordering::Less);
+
+		let mut with_options = without_priority.clone();
+		with_options
+			.description
+			.set_options(DescriptionOptions::new().colons_in_values(true));
+		assert_ne!(with_options, without_priority);
+		assert_ne!(with_options.cmp(&without_priority), Ordering::Equal);
+
+		let order = TaskOrder::new([SortKey::Alphabetical])
+			.then(SortKey::CompletedDate);
+		order.sort(&mut tasks);
+		assert_eq!(tasks[0].to_string(), "Buy milk");
+		assert_eq!(tasks[1].to_string(), "(A) 2021-07-01 Call Dad");
+		assert_eq!(
+			order.keys(),
+			[SortKey::Alphabetical, SortKey::CompletedDate]
+		);
+		assert_eq!(TaskOrder::default().keys(), TaskOrder::CANONICAL_KEYS);
+	}
+
+	#[test]
+	fn task_age() {
+		use crate::date::Days;
+
+		let today = Date::from_ymd(2021, 3, 1);
+
+		for (line, age) in &[
+			("2021-03-01 Call Mom", Some(0)),
+			("2021-02-28 Call Mom", Some(1)),
+			("2020-03-01 Call Mom", Some(365)),
+			("2019-03-01 Call Mom", Some(731)),
+			("1970-01-01 Call Mom", Some(18_687)),
+			("x 2021-03-01 2021-03-02 Call Mom", Some(-1)),
+			("Call Mom", None),
+		] {
+			assert_eq!(
+				Task::from_str(line).unwrap().age(today),
+				age.map(Days),
+				"{}",
+				line
+			);
+		}
+	}
+
+	#[test]
+	fn task_due() {
+		use crate::date::Days;
+
+		let today = Date::from_ymd(2021, 8, 1);
+
+		let task = Task::from_str("Pay rent due:2021-08-05 due:foo").unwrap();
+		assert_eq!(task.due_date(), Some(Date::from_ymd(2021, 8, 5)));
+		assert!(!task.is_overdue(today));
+		assert!(task.is_due_within(today, Days(4)));
+		assert!(!task.is_due_within(today, Days(3)));
+		assert!(task.is_overdue(Date::from_ymd(2021, 8, 6)));
+		assert!(!task.is_due_within(Date::from_ymd(2021, 8, 6), Days(7)));
+
+		let task =
+			Task::from_str("Pay rent due:2021-08-05 due:2021-07-01").unwrap();
+		assert_eq!(task.due_date(), Some(Date::from_ymd(2021, 7, 1)));
+		assert!(task.is_overdue(today));
+
+		let task = Task::from_str("x Pay rent due:2021-08-01").unwrap();
+		assert!(!task.is_due_within(today, Days(0)));
+		assert!(!task.is_overdue(Date::from_ymd(2021, 9, 1)));
+
+		let task = Task::from_str("Pay rent due:soon").unwrap();
+		assert_eq!(task.due_date(), None);
+		assert!(!task.is_overdue(today));
+		assert!(!task.is_due_within(today, Days(100)));
+	}
+
+	#[test]
+	fn urgency() {
+		use crate::date::Days;
+		use crate::urgency::Urgency;
+
+		let today = Date::from_ymd(2021, 8, 1);
+		let urgency = Urgency::default();
+		let score =
+			|line: &str| urgency.score(&Task::from_str(line).unwrap(), today);
+		let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+
+		assert!(close(score("Call Mom"), 0.0));
+		assert!(close(score("x (A) Call Mom +Family @phone"), 0.0));
+		assert!(close(score("(A) Call Mom"), 6.0));
+		assert!(close(score("(Z) Call Mom"), 6.0 / 26.0));
+		assert!(close(score("Call Mom +Family @phone"), 2.0));
+		assert!(close(score("Call Mom due:2021-09-01"), 12.0 * 0.2));
+		assert!(close(score("Call Mom due:2021-07-01"), 12.0));
+		assert!(close(score("Call Mom due:2021-07-25"), 12.0));
+		assert!(close(score("Call Mom due:2021-08-15"), 12.0 * 0.2));
+		assert!(close(
+			score("Call Mom due:2021-08-08"),
+			12.0 * (0.2 + 0.8 / 3.0)
+		));
+		assert!(close(score("2021-07-01 Call Mom"), 2.0 * 31.0 / 365.0));
+		assert!(close(score("2019-07-01 Call Mom"), 2.0));
+
+		let urgency = Urgency { max_age: Days(0), project: 5.0, ..urgency };
+		assert!(close(
+			urgency.score(
+				&Task::from_str("2021-07-01 Call +Mom").unwrap(),
+				today
+			),
+			7.0
+		));
+	}
+
+	#[test]
+	fn task_diff() {
+		use crate::diff::TaskChange;
+
+		let old =
+			Task::from_str("(A) Call Mom +Family +Phone @home key:a").unwrap();
+		let new = Task::from_str(
+			"x 2021-08-01 2021-07-30 Call Mom @phone +Family @home key:a key:b",
+		)
+		.unwrap();
+
+		assert_eq!(
+			old.diff(&new),
+			[
+				TaskChange::State { from: State::Open, to: State::Done },
+				TaskChange::Priority { from: Some(Priority::A), to: None },
+				TaskChange::DateCompound {
+					from: None,
+					to: Some(DateCompound::completed(
+						Date::from_ymd(2021, 7, 30),
+						Date::from_ymd(2021, 8, 1)
+					)),
+				},
+				TaskChange::Description {
+					from: "Call Mom +Family +Phone @home key:a".into(),
+					to: "Call Mom @phone +Family @home key:a key:b".into(),
+				},
+				TaskChange::ProjectRemoved("Phone".into()),
+				TaskChange::ContextAdded("phone".into()),
+				TaskChange::TagAdded { key: "key".into(), value: "b".into() },
+			]
+		);
+
+		// Reordering components only changes the description
+		let old = Task::from_str("Call Mom +Family @phone").unwrap();
+		let new = Task::from_str("Call Mom @phone +Family").unwrap();
+		assert_eq!(old.diff(&new).len(), 1);
+
+		let mut new = old.clone();
+		new.priority = Some(Priority::B);
+		assert_eq!(
+			old.diff(&new),
+			[TaskChange::Priority { from: None, to: Some(Priority::B) }]
+		);
+	}
+
+	#[test]
+	fn task_patch() {
+		use crate::description::BuildDescriptionError;
+		use crate::patch::{PatchOp, TaskPatch};
+
+		let task =
+			Task::from_str("(B) Call Mom +Family +Phone @phone key:a key:b")
+				.unwrap();
+
+		let patch = TaskPatch::new()
+			.set_state(State::Done)
+			.clear_priority()
+			.add_project("Family")
+			.add_project("Home")
+			.remove_project("Phone")
+			.add_context("home")
+			.remove_context("phone")
+			.set_tag("key", "c")
+			.add_tag("due", "2021-08-01")
+			.push(PatchOp::RemoveTag {
+				key: "due".into(),
+				value: Some("x".into()),
+			});
+		assert_eq!(patch.ops().len(), 10);
+
+		let patched = patch.apply(&task).unwrap();
+		assert_eq!(
+			patched.to_string(),
+			"x Call Mom +Family key:c key:c +Home @home due:2021-08-01"
+		);
+
+		let patched =
+			TaskPatch::new().remove_tag("key").apply(&patched).unwrap();
+		assert_eq!(
+			patched.to_string(),
+			"x Call Mom +Family +Home @home due:2021-08-01"
+		);
+
+		// Patches created from diffs
+		for (from, to) in &[
+			("(A) Call Mom", "x 2021-08-01 2021-07-30 Call Dad +Family"),
+			("Call Mom", "Call Mom"),
+			("x Call Mom due:2021-08-01", "(C) Call Mom"),
+		] {
+			let from = Task::from_str(from).unwrap();
+			let to = Task::from_str(to).unwrap();
+
+			let patch = TaskPatch::from_diff(&from, &to);
+			assert_eq!(patch.apply(&from), Ok(to.clone()));
+			assert_eq!(patch.is_empty(), from == to);
+
+			let mut undone = to.clone();
+			TaskPatch::from_diff(&to, &from)
+				.apply_in_place(&mut undone)
+				.unwrap();
+			assert_eq!(undone, from);
+		}
+
+		// Invalid projects, contexts and tags are rejected
+		let task = Task::from_str("Pay rent due:2021-08-01").unwrap();
+		for (op, error) in &[
+			(
+				PatchOp::AddProject("Two words".into()),
+				BuildDescriptionError::InvalidProject("Two words".into()),
+			),
+			(
+				PatchOp::AddContext("@".into()),
+				BuildDescriptionError::InvalidContext(String::new()),
+			),
+			(
+				PatchOp::SetTag {
+					key: "due".into(),
+					value: "next week".into(),
+				},
+				BuildDescriptionError::InvalidTag {
+					key: "due".into(),
+					value: "next week".into(),
+				},
+			),
+			(
+				PatchOp::AddTag { key: "a:b".into(), value: "c".into() },
+				BuildDescriptionError::InvalidTag {
+					key: "a:b".into(),
+					value: "c".into(),
+				},
+			),
+		] {
+			let patch = TaskPatch::new().add_project("Home").push(op.clone());
+			assert_eq!(patch.apply(&task), Err(error.clone()));
+
+			// No operation is applied if any is invalid
+			let mut patched = task.clone();
+			assert_eq!(patch.apply_in_place(&mut patched), Err(error.clone()));
+			assert_eq!(patched.to_string(), "Pay rent due:2021-08-01");
+		}
+	}
+
+	#[cfg(feature = "serde")]
+	#[test]
+	fn task_patch_serde() {
+		use crate::patch::TaskPatch;
+
+		let patch = TaskPatch::new()
+			.set_priority(Priority::A)
+			.set_due(Date::from_ymd(2021, 8, 1))
+			.remove_tag("t");
+
+		let json = serde_json::to_string(&patch).unwrap();
+		assert_eq!(serde_json::from_str::<TaskPatch>(&json).unwrap(), patch);
+	}
+
+	#[test]
+	fn task_normalize() {
+		for (line, normalized) in &[
+			("Call Mom", "Call Mom"),
+			("Call\t Mom ", "Call Mom"),
+			(
+				"Call @phone Mom key:value +Family @phone +Mom",
+				"Call @phone Mom +Family +Mom @phone key:value",
+			),
+			("+Family  @phone  Call  Mom", "+Family @phone Call Mom"),
+			("a:b @c +d", "+d @c a:b"),
+			(
+				"x (A) 2021-08-01 2021-07-30  Call Mom  ",
+				"x (A) 2021-08-01 2021-07-30 Call Mom",
+			),
+		] {
+			let mut task = Task::from_str(line).unwrap();
+			let changed = task.normalize();
+
+			assert_eq!(task.to_string(), *normalized);
+			assert_eq!(changed, line != normalized);
+			assert!(!task.normalize());
+			assert_eq!(Task::from_str(&task.to_string()).unwrap(), task);
+		}
+	}
+
+	#[test]
+	fn task_display_preserves_raw_prefix() {
+		for line in &[
+			"x  How:you doin",
+			"x\t(A)\t2021-01-01  Hello",
+			"(B) 2021-01-02\t2021-01-01 World",
+			"x (A)\u{a0}Hello",
+		] {
+			let task = Task::from_str(line).unwrap();
+			assert_eq!(task.to_string(), *line);
+		}
+
+		let mut task = Task::from_str("x\t(A)\tHello").unwrap();
+		task.description.set_text("World");
+		assert_eq!(task.to_string(), "x\t(A)\tWorld");
+		assert_eq!(task, Task::from_str("x (A) World").unwrap());
+
+		task.priority = Some(Priority::B);
+		assert_eq!(task.to_string(), "x (B) World");
+		task.priority = Some(Priority::A);
+		assert_eq!(task.to_string(), "x\t(A)\tWorld");
+
+		// Canonical lines are not kept
+		for line in
+			&["x (A) 2021-01-02 2021-01-01 Call Mom", "2021-01-01 Call"]
+		{
+			let mut task = Task::from_str(line).unwrap();
+			assert!(!task.normalize(), "{}", line);
+			assert_eq!(task.to_string(), *line);
+		}
+
+		// Replacing the description drops the original line
+		let mut task = Task::from_str("x\t(A)\tHello").unwrap();
+		task.description = Description::new("World");
+		assert_eq!(task.to_string(), "x (A) World");
+
+		let mut task =
+			Task::from_str("x\t(A)  2021-01-01   Call Mom").unwrap();
+		assert!(task.normalize());
+		assert_eq!(task.to_string(), "x (A) 2021-01-01 Call Mom");
+
+		let mut task = Task::from_str("x\t(A) Call Mom").unwrap();
+		assert!(task.normalize());
+		assert_eq!(task.to_string(), "x (A) Call Mom");
+		assert!(!task.normalize());
+	}
+
+	#[test]
+	fn task_map_description() {
+		let mut task =
+			Task::from_str("(A) Call Mom +family due:2021-01-01").unwrap();
+
+		let replaced = task.map_description(|text| {
+			let replaced = text.replace("+family", "+home @phone");
+			*text = replaced;
+			text.len()
+		});
+
+		assert_eq!(replaced, 36);
+		assert_eq!(
+			task.description().projects().collect::<Vec<_>>(),
+			["home"]
+		);
+		assert_eq!(
+			task.description().contexts().collect::<Vec<_>>(),
+			["phone"]
+		);
+		assert_eq!(task.due_date(), Some(Date::from_ymd(2021, 1, 1)));
+
+		let task = task.with_description("Call Dad");
+		assert_eq!(task.to_string(), "(A) Call Dad");
+		assert_eq!(task.description().projects().count(), 0);
+	}
+
+	#[test]
+	fn task_template() {
+		use crate::template::{InstantiateError, TaskTemplate};
+
+		let template =
+			TaskTemplate::from_str("{pri} {date} Water {{plants}} +{project}")
+				.unwrap();
+
+		assert_eq!(
+			template.placeholders().collect::<Vec<_>>(),
+			["pri", "date", "project"]
+		);
+		assert_eq!(
+			template.to_string(),
+			"{pri} {date} Water {{plants}} +{project}"
+		);
+
+		let task = template
+			.instantiate_with(vec![
+				("pri", "(B)".to_string()),
+				("date", Date::from_ymd(2021, 7, 1).to_string()),
+				("project", "Garden".to_string()),
+			])
+			.unwrap();
+		assert_eq!(task.to_string(), "(B) 2021-07-01 Water {plants} +Garden");
+		assert_eq!(
+			task.date_compound,
+			Some(DateCompound::created(Date::from_ymd(2021, 7, 1)))
+		);
+
+		assert_eq!(
+			template.instantiate_with(vec![("pri", "(A)")]),
+			Err(InstantiateError::MissingValue("date".to_string()))
+		);
+
+		for invalid in &["{open", "close}", "{}", "{two words}"] {
+			assert!(TaskTemplate::from_str(invalid).is_err());
+		}
+	}
+
+	#[test]
+	fn task_recurrence() {
+		use crate::recurrence::{Recurrence, RecurrenceUnit};
+
+		assert_eq!(
+			Recurrence::from_str("10d"),
+			Ok(Recurrence::new(10, RecurrenceUnit::Day))
+		);
+		assert_eq!(Recurrence::from_str("1y").unwrap().to_string(), "1y");
+		for invalid in &["", "d", "0w", "2", "2x", "1w2", "99999999999m"] {
+			assert!(Recurrence::from_str(invalid).is_err(), "{}", invalid);
+		}
+
+		let today = Date::from_ymd(2020, 2, 29);
+		assert_eq!(
+			Recurrence::new(1, RecurrenceUnit::Year).apply(today),
+			Some(Date::from_ymd(2021, 2, 28))
+		);
+		assert_eq!(
+			Recurrence::new(3, RecurrenceUnit::Month).apply(today),
+			Some(Date::from_ymd(2020, 5, 29))
+		);
+
+		let task = Task::from_str("Water plants rec:foo rec:3d").unwrap();
+		assert_eq!(
+			task.recurrence(),
+			Some(Recurrence::new(3, RecurrenceUnit::Day))
+		);
+		assert_eq!(
+			task.next_occurrence(today).unwrap().to_string(),
+			"Water plants rec:foo rec:3d due:2020-03-03"
+		);
+
+		let task = Task::from_str("x Mow lawn t:2020-02-01 rec:1w").unwrap();
+		assert_eq!(
+			task.next_occurrence(today).unwrap().to_string(),
+			"Mow lawn t:2020-03-07 rec:1w"
+		);
+
+		assert_eq!(
+			Task::from_str("Call Mom").unwrap().next_occurrence(today),
+			None
+		);
+	}
+
+	#[test]
+	fn task_recurrence_strict() {
+		use crate::recurrence::{Recurrence, RecurrenceUnit};
+
+		assert_eq!(
+			Recurrence::from_str("+2w"),
+			Ok(Recurrence::new(2, RecurrenceUnit::Week).strict(true))
+		);
+		assert!(Recurrence::from_str("+").is_err());
+		assert!(Recurrence::from_str("++1d").is_err());
+
+		let today = Date::from_ymd(2021, 8, 10);
+
+		let task =
+			Task::from_str("Water plants due:2021-08-01 t:2021-07-30 rec:+1w")
+				.unwrap();
+		assert_eq!(
+			task.next_occurrence(today).unwrap().to_string(),
+			"Water plants due:2021-08-08 t:2021-08-06 rec:+1w"
+		);
+
+		let task = Task::from_str("Mow lawn t:2021-08-01 rec:+1w").unwrap();
+		assert_eq!(
+			task.next_occurrence(today).unwrap().to_string(),
+			"Mow lawn t:2021-08-08 rec:+1w"
+		);
+
+		let task = Task::from_str("Call Mom rec:+3d").unwrap();
+		assert_eq!(
+			task.next_occurrence(today).unwrap().to_string(),
+			"Call Mom rec:+3d due:2021-08-13"
+		);
+	}
+
+	#[test]
+	fn task_hidden() {
+		use crate::filter::Filter;
+
+		let hidden = Task::from_str("x +GarageSale @phone h:1").unwrap();
+		let visible = Task::from_str("Call Mom h:2 hh:1").unwrap();
+
+		assert!(hidden.is_hidden());
+		assert!(!visible.is_hidden());
+
+		assert!(Filter::hidden().matches(&hidden));
+		assert!(!Filter::hidden().matches(&visible));
+		assert!(!Filter::not_hidden().matches(&hidden));
+		assert!(Filter::not_hidden().matches(&visible));
+		assert_eq!(!Filter::not_hidden(), Filter::hidden());
+	}
+
+	#[test]
+	fn dependency_graph() {
+		use crate::dependency::DependencyGraph;
+
+		let todo = TodoFile::from_str(
+			"Plan trip id:1\n\
+			 Book flight id:2 dep:1\n\
+			 Book hotel id:2 p:1 dep:1\n\
+			 Pack dep:2 dep:3\n\
+			 x Renew passport id:3\n",
+		)
+		.unwrap();
+
+		let graph = todo.dependency_graph();
+
+		assert_eq!(graph.len(), 5);
+		assert!(graph.blockers_of(0).is_empty());
+		assert_eq!(graph.blockers_of(2), [0]);
+		assert_eq!(graph.blockers_of(3), [1, 2, 4]);
+		assert_eq!(graph.dependents_of(0), [1, 2]);
+		assert_eq!(graph.dependents_of(4), [3]);
+		assert!(graph.blockers_of(42).is_empty());
+
+		assert!(!graph.is_blocked(0));
+		assert!(graph.is_blocked(1));
+		assert!(graph.is_blocked(3));
+		assert!(!graph.is_blocked(4));
+
+		assert!(DependencyGraph::new(&[]).is_empty());
+	}
+
+	#[test]
+	fn dependency_topological_order() {
+		let todo = TodoFile::from_str(
+			"D id:4 dep:2 dep:3\n\
+			 C id:3 dep:1\n\
+			 B id:2 dep:1\n\
+			 A id:1\n\
+			 E\n",
+		)
+		.unwrap();
+
+		let order = todo
+			.dependency_graph()
+			.topological_order()
+			.collect::<Result<Vec<_>, _>>()
+			.unwrap();
+		assert_eq!(order, [3, 1, 2, 0, 4]);
+
+		let todo = TodoFile::from_str(
+			"A id:1\n\
+			 B id:2 dep:1 dep:4\n\
+			 C id:3 dep:2\n\
+			 D id:4 dep:3\n\
+			 E id:5 dep:5\n",
+		)
+		.unwrap();
+
+		let graph = todo.dependency_graph();
+		let mut order = graph.topological_order();
+
+		assert_eq!(order.next(), Some(Ok(0)));
+		let cycle = order.next().unwrap().unwrap_err();
+		assert_eq!(cycle.tasks(), [1, 3, 2]);
+		assert_eq!(
+			cycle.to_string(),
+			"dependency cycle between the tasks 1, 3, 2"
+		);
+		assert_eq!(order.next(), None);
+	}
+
+	#[test]
+	fn task_effective_priority() {
+		use std::cmp::Ordering;
+
+		use crate::sort::SortKey;
+
+		let mut task = Task::from_str("(B) 2021-07-30 Call Mom").unwrap();
+		assert_eq!(task.effective_priority(), Some(Priority::B));
+
+		task.complete(Date::from_ymd(2021, 8, 1), CompletionPriority::Tag);
+		assert_eq!(task.priority, None);
+		assert_eq!(task.effective_priority(), Some(Priority::B));
+
+		let reparsed = Task::from_str(&task.to_string()).unwrap();
+		assert_eq!(reparsed.effective_priority(), Some(Priority::B));
+
+		let other = Task::from_str("x (C) Buy milk pri:A").unwrap();
+		assert_eq!(other.effective_priority(), Some(Priority::C));
+		assert_eq!(SortKey::Priority.compare(&task, &other), Ordering::Less);
+
+		let invalid = Task::from_str("x Buy milk pri:AB pri:1").unwrap();
+		assert_eq!(invalid.effective_priority(), None);
+	}
+
+	#[test]
+	fn description_add_remove_project() {
+		let mut description = Description::new("Call Mom");
+
+		assert_eq!(description.add_project("Family"), Ok(true));
+		assert_eq!(description.add_project("+Phone"), Ok(true));
+		assert_eq!(description.add_project("+Family"), Ok(false));
+		assert_eq!(description.description(), "Call Mom +Family +Phone");
+		assert_eq!(
+			description.projects().collect::<Vec<_>>(),
+			["Family", "Phone"]
+		);
+
+		let mut description = Description::new("+Family Call Mom +Family");
+		assert!(description.remove_project("+Family"));
+		assert!(!description.remove_project("Family"));
+		assert_eq!(description.description(), "Call Mom");
+		assert_eq!(description.projects().count(), 0);
+
+		let mut description = Description::new("");
+		assert_eq!(description.add_project("Novel"), Ok(true));
+		assert_eq!(description.description(), "+Novel");
+	}
+
+	#[test]
+	fn description_add_project_invalid() {
+		use crate::description::BuildDescriptionError;
+
+		let mut description = Description::new("Call Mom");
+
+		for project in &["+Two words", "+", ""] {
+			assert_eq!(
+				description.add_project(project),
+				Err(BuildDescriptionError::InvalidProject(
+					project.trim_start_matches('+').to_owned()
+				))
+			);
+		}
+		assert_eq!(description.description(), "Call Mom");
+	}
+
+	#[test]
+	fn description_add_remove_context() {
+		let mut description = Description::new("Call Mom +Family");
+
+		assert_eq!(description.add_context("phone"), Ok(true));
+		assert_eq!(description.add_context("@home"), Ok(true));
+		assert_eq!(description.add_context("@phone"), Ok(false));
+		assert_eq!(
+			description.add_context("at home"),
+			Err(crate::description::BuildDescriptionError::InvalidContext(
+				"at home".to_owned()
+			))
+		);
+		assert_eq!(description.description(), "Call Mom +Family @phone @home");
+		assert_eq!(
+			description.contexts().collect::<Vec<_>>(),
+			["phone", "home"]
+		);
+
+		let mut description = Description::new("Call @phone Mom @phone");
+		assert!(description.remove_context("phone"));
+		assert!(!description.remove_context("@phone"));
+		assert_eq!(description.description(), "Call Mom");
+		assert_eq!(description.contexts().count(), 0);
+	}
+
+	#[test]
+	fn description_set_replace_remove_tag() {
+		let mut description =
+			Description::new("due:2024-05-01 Pay rent due:x @home");
+
+		assert_eq!(description.replace_tag("due", "2024-06-01"), Ok(true));
+		assert_eq!(
+			description.description(),
+			"due:2024-06-01 Pay rent due:2024-06-01 @home"
+		);
+		assert_eq!(
+			description.custom().collect::<Vec<_>>(),
+			[("due", "2024-06-01"), ("due", "2024-06-01")]
+		);
+
+		assert_eq!(description.set_tag("rec", "1m"), Ok(()));
+		assert_eq!(
+			description.description(),
+			"due:2024-06-01 Pay rent due:2024-06-01 @home rec:1m"
+		);
+
+		assert!(description.remove_tag("due"));
+		assert!(!description.remove_tag("due"));
+		assert_eq!(description.description(), "Pay rent @home rec:1m");
+		assert_eq!(description.contexts().collect::<Vec<_>>(), ["home"]);
+
+		for (key, value) in
+			&[("", "a"), ("a b", "c"), ("+a", "b"), ("a", ""), ("a", "b:c")]
+		{
+			let mut description = Description::new("Pay rent");
+			let error =
+				crate::description::BuildDescriptionError::InvalidTag {
+					key: (*key).to_owned(),
+					value: (*value).to_owned(),
+				};
+
+			assert_eq!(description.set_tag(key, value), Err(error.clone()));
+			assert_eq!(description.replace_tag(key, value), Err(error));
+			assert_eq!(description.description(), "Pay rent");
+		}
+	}
+
+	#[test]
+	fn description_tag_lookup() {
+		let description = Description::new(
+			"Pay rent due:2024-06-01 t:2024-05-25 due:x +Home",
+		);
+
+		assert_eq!(description.tag("due"), Some("2024-06-01"));
+		assert_eq!(description.tag("t"), Some("2024-05-25"));
+		assert_eq!(description.tag("Home"), None);
+		assert_eq!(
+			description.tags("due").collect::<Vec<_>>(),
+			["2024-06-01", "x"]
+		);
+		assert_eq!(description.tags("rec").count(), 0);
+	}
+
+	#[test]
+	fn description_membership() {
+		let description = Description::new(
+			"Call Mom +Family @phone +Family2 due:2024-06-01",
+		);
+
+		assert!(description.has_project("Family"));
+		assert!(description.has_project("+Family2"));
+		assert!(!description.has_project("Fam"));
+		assert!(!description.has_project("phone"));
+
+		assert!(description.has_context("phone"));
+		assert!(description.has_context("@phone"));
+		assert!(!description.has_context("Family"));
+
+		assert!(description.has_tag("due"));
+		assert!(!description.has_tag("2024-06-01"));
+	}
+
+	#[test]
+	fn description_unique_components() {
+		let description =
+			Description::new("+Garden @home Water +Garden plants @home +Yard");
+
+		assert_eq!(
+			description.projects_unique().collect::<Vec<_>>(),
+			["Garden", "Yard"]
+		);
+		assert_eq!(
+			description.contexts_unique().collect::<Vec<_>>(),
+			["home"]
+		);
+
+		let todo = TodoFile::from_str(
+			"Water plants +Garden @home\n\
+			 Mow lawn +Yard +Garden @home @outside\n",
+		)
+		.unwrap();
+
+		assert_eq!(
+			todo.projects().into_iter().collect::<Vec<_>>(),
+			["Garden", "Yard"]
+		);
+		assert_eq!(
+			todo.contexts().into_iter().collect::<Vec<_>>(),
+			["home", "outside"]
+		);
+	}
+
+	#[test]
+	fn description_from_components() {
+		use std::iter::FromIterator;
+
+		use crate::description::Component;
+
+		for input in &[
+			"Call Mom +Family @phone due:2024-06-01",
+			"+Family Call  Mom @phone",
+			"",
+		] {
+			let description = Description::new(*input);
+			assert_eq!(
+				Description::from_components(description.components()),
+				description
+			);
+		}
+
+		let description = Description::new("  Call @phone Mom +Family  ");
+		let stripped = description
+			.components()
+			.filter(|component| !matches!(component, Component::Context(_)))
+			.collect::<Description>();
+		assert_eq!(stripped.description(), "Call Mom +Family");
+
+		let built = Description::from_iter(vec![
+			Component::Text("Pay"),
+			Component::Text("   "),
+			Component::Text("rent+"),
+			Component::Project("+Home"),
+			Component::Custom { key: "due", separator: ":", value: "x" },
+		]);
+		assert_eq!(built.description(), "Pay rent+ +Home due:x");
+		assert_eq!(built.projects().collect::<Vec<_>>(), ["Home"]);
+		assert_eq!(built.tag("due"), Some("x"));
+	}
+
+	#[test]
+	fn description_builder() {
+		use crate::description::{BuildDescriptionError, DescriptionBuilder};
+
+		let description = DescriptionBuilder::new()
+			.push_project("+Garden")
+			.push_text("Water the  plants")
+			.push_context("home")
+			.push_tag("rec", "1w")
+			.build()
+			.unwrap();
+
+		assert_eq!(
+			description.description(),
+			"+Garden Water the  plants @home rec:1w"
+		);
+		assert_eq!(description.projects().collect::<Vec<_>>(), ["Garden"]);
+		assert_eq!(description.tag("rec"), Some("1w"));
+		assert_eq!(Description::builder().build(), Ok(Description::new("")));
+
+		let errors = [
+			(
+				Description::builder().push_text(" Water").build(),
+				BuildDescriptionError::InvalidText(" Water".to_string()),
+			),
+			(
+				Description::builder().push_project("+").build(),
+				BuildDescriptionError::InvalidProject(String::new()),
+			),
+			(
+				Description::builder().push_context("at home").build(),
+				BuildDescriptionError::InvalidContext("at home".to_string()),
+			),
+			(
+				Description::builder()
+					.push_tag("due", "2021-08-01")
+					.push_tag("@due", "x")
+					.push_text("\n")
+					.build(),
+				BuildDescriptionError::InvalidTag {
+					key: "@due".to_string(),
+					value: "x".to_string(),
+				},
+			),
+		];
+
+		for (result, error) in errors.iter() {
+			assert_eq!(result.as_ref(), Err(error));
+		}
+	}
+
+	#[test]
+	fn description_spans() {
+		use crate::span::{BytePos, ByteSpan};
+
+		let description =
+			Description::new("+Garden Water @home plants rec:1w @home");
+		let text = description.description();
+
+		let projects = description.project_spans().collect::<Vec<_>>();
+		assert_eq!(
+			projects,
+			[(
+				ByteSpan::new(BytePos::from_usize(0), BytePos::from_usize(7)),
+				"Garden"
+			)]
+		);
+
+		let contexts = description
+			.context_spans()
+			.map(|(span, name)| (&text[span.to_range_usize()], name))
+			.collect::<Vec<_>>();
+		assert_eq!(contexts, [("@home", "home"), ("@home", "home")]);
+
+		let range = description.custom_ranges()[0];
+		assert_eq!(&text[range.full().to_range_usize()], "rec:1w");
+		assert_eq!(&text[range.key().to_range_usize()], "rec");
+		assert_eq!(&text[range.separator().to_range_usize()], ":");
+		assert_eq!(&text[range.value().to_range_usize()], "1w");
+		assert_eq!(
+			description.custom_spans().next(),
+			Some((*range.full(), ("rec", "1w")))
+		);
+
+		let range = description.project_ranges()[0];
+		assert_eq!(&text[range.project().to_range_usize()], "Garden");
+		assert_eq!(description.context_ranges().len(), 2);
+	}
+
+	#[test]
+	fn description_plain_text() {
+		let description = Description::new("+Garden  water @home plants h:1 ");
+
+		assert_eq!(description.plain_text(), "water plants");
+		assert_eq!(
+			description.plain_words().collect::<Vec<_>>(),
+			["water", "plants"]
+		);
+		assert_eq!(Description::new("+Garden @home").plain_text(), "");
+	}
+
+	#[test]
+	fn description_set_text() {
+		let mut description = Description::new("+Garden water @home plants");
+
+		description.set_text("  Weed   beds ");
+		assert_eq!(description.description(), "+Garden Weed   beds @home");
+		assert_eq!(description.projects().collect::<Vec<_>>(), ["Garden"]);
+
+		description.set_text("");
+		assert_eq!(description.description(), "+Garden @home");
+
+		// Without words the text is put in front
+		description.set_text("Mow lawn");
+		assert_eq!(description.description(), "Mow lawn +Garden @home");
+
+		let mut description = Description::new("Water plants");
+		description.set_text("Mow lawn +Garden");
+		assert_eq!(description.description(), "Mow lawn +Garden");
+		assert!(description.has_project("Garden"));
+
+		// Components of the new text are not repeated
+		let mut description = Description::new(
+			"Water +Garden plants @home due:2021-08-01 +Lawn",
+		);
+		description.set_text("Mow +Lawn @home due:2021-08-02");
+		assert_eq!(
+			description.description(),
+			"Mow +Lawn @home due:2021-08-02 +Garden due:2021-08-01"
+		);
+		assert_eq!(
+			description.projects().collect::<Vec<_>>(),
+			["Lawn", "Garden"]
+		);
+
+		// Metadata and the whitespace around it stay in place
+		let mut description = Description::new(
+			"Call  Mom +Family  about\tdinner @phone  due:2021-08-01  later",
+		);
+		description.set_text("Call Dad");
+		assert_eq!(
+			description.description(),
+			"Call Dad +Family @phone  due:2021-08-01"
+		);
+
+		let mut description =
+			Description::new("  +Family  Call  Mom @phone  ");
+		description.set_text("Call Dad");
+		assert_eq!(description.description(), "  +Family  Call Dad @phone  ");
+	}
+
+	#[test]
+	fn description_unicode_whitespace() {
+		// no-break space, ideographic space and em space
+		let description = Description::new(
+			"Grüße\u{a0}+Familie\u{3000}@Telefon\u{2003}fällig:morgen\u{a0}x",
+		);
+
+		assert_eq!(description.projects().collect::<Vec<_>>(), ["Familie"]);
+		assert_eq!(description.contexts().collect::<Vec<_>>(), ["Telefon"]);
+		assert_eq!(
+			description.custom().collect::<Vec<_>>(),
+			[("fällig", "morgen")]
+		);
+		assert_eq!(description.plain_text(), "Grüße x");
+
+		// a lone sigil followed by unicode whitespace is plain text
+		let description = Description::new("+\u{a0}@\u{3000}a:\u{a0}b");
+		assert_eq!(description.projects().count(), 0);
+		assert_eq!(description.contexts().count(), 0);
+		assert_eq!(description.custom().count(), 0);
+
+		let description = Description::new("日本語 +プロジェクト");
+		assert_eq!(
+			description.projects().collect::<Vec<_>>(),
+			["プロジェクト"]
+		);
+	}
+
+	#[test]
+	fn description_colons_in_values() {
+		use crate::description::DescriptionOptions;
+
+		let input = "Read link:https://example.com/a:b also:not:valid a::b";
+		let options = DescriptionOptions::new().colons_in_values(true);
+
+		assert_eq!(Description::new(input).custom().count(), 0);
+
+		let mut description = Description::with_options(input, options);
+		assert_eq!(
+			description.custom().collect::<Vec<_>>(),
+			[("link", "https://example.com/a:b"), ("also", "not:valid")]
+		);
+
+		// Options survive modifications
+		assert_eq!(description.set_tag("src", "ftp://host"), Ok(()));
+		assert_eq!(description.add_project("Reading"), Ok(true));
+		assert_eq!(description.tag("src"), Some("ftp://host"));
+		assert_eq!(description.options(), options);
+
+		description.set_options(DescriptionOptions::new());
+		assert_eq!(description.custom().count(), 0);
+	}
+
+	#[test]
+	fn filter_ignore_case() {
+		use crate::filter::Filter;
+
+		let task = Task::from_str("Call +GarageSale @Phone").unwrap();
+
+		assert!(!Filter::project("garagesale").matches(&task));
+		assert!(Filter::project("garagesale").ignore_case().matches(&task));
+		assert!(Filter::context("PHONE").ignore_case().matches(&task));
+		assert!(!(!Filter::context("phone")).ignore_case().matches(&task));
+		assert!(Filter::text("x")
+			.or(Filter::project("GARAGESALE"))
+			.ignore_case()
+			.matches(&task));
+
+		assert!(crate::eq_ignore_case("Phone", "pHONE"));
+		assert!(!crate::eq_ignore_case("Phone", "Phones"));
+
+		#[cfg(feature = "caseless")]
+		assert!(crate::eq_ignore_case("Straße", "STRASSE"));
+		#[cfg(not(feature = "caseless"))]
+		assert!(!crate::eq_ignore_case("Straße", "STRASSE"));
+	}
+
+	#[test]
+	fn description_lazy_index() {
+		fn assert_send_sync<T: Send + Sync>() {}
+		assert_send_sync::<Description>();
+
+		let description = Description::new("Call Mom +Family @phone due:x");
+		let clone = description.clone();
+
+		// Equality does not depend on whether the components were located
+		assert_eq!(description.projects().collect::<Vec<_>>(), ["Family"]);
+		assert_eq!(description, clone);
+		assert_eq!(clone.custom().collect::<Vec<_>>(), [("due", "x")]);
+		assert_eq!(description.clone().contexts().count(), 1);
+	}
+
+	#[test]
+	fn description_many_components() {
+		// More components than are stored inline
+		let description =
+			Description::new("+a +b +c +d @e @f @g @h i:1 j:2 k:3 +l");
+
+		assert_eq!(
+			description.projects().collect::<Vec<_>>(),
+			["a", "b", "c", "d", "l"]
+		);
+		assert_eq!(description.context_ranges().len(), 4);
+		assert_eq!(
+			description.custom().collect::<Vec<_>>(),
+			[("i", "1"), ("j", "2"), ("k", "3")]
+		);
+		assert_eq!(description.components().count(), 23);
+	}
+
+	#[test]
+	fn description_ref() {
+		use std::borrow::Cow;
+
+		use crate::description_ref::DescriptionRef;
+
+		let owned = Description::new("Call Mom +Family @phone due:x");
+		let _ = owned.projects().count();
+
+		let borrowed = DescriptionRef::from(&owned);
+		assert!(borrowed.is_borrowed());
+		assert_eq!(borrowed.description(), owned.description());
+		assert_eq!(
+			borrowed.components().collect::<Vec<_>>(),
+			owned.components().collect::<Vec<_>>()
+		);
+		assert!(borrowed.has_context("@phone"));
+		assert!(borrowed.has_tag("due"));
+		assert_eq!(Description::from(borrowed), owned);
+
+		let from_owned =
+			DescriptionRef::new(Cow::Owned(String::from("Pay rent +Home")));
+		assert!(!from_owned.is_borrowed());
+		assert_eq!(from_owned.to_string(), "Pay rent +Home");
+		assert_eq!(
+			from_owned.into_owned().projects().collect::<Vec<_>>(),
+			["Home"]
+		);
+	}
+
+	#[test]
+	fn task_ref() {
+		use crate::task_ref::TaskRef;
+
+		let input = "x\t(A) 2021-08-01 Call Mom +Family @phone\r\n";
+		let task_ref = TaskRef::parse(input).unwrap();
+
+		assert_eq!(task_ref.state, State::Done);
+		assert_eq!(task_ref.priority, Some(Priority::A));
+		assert_eq!(
+			task_ref.date_compound,
+			Some(DateCompound::created(Date::from_ymd(2021, 8, 1)))
+		);
+		assert!(task_ref.description.is_borrowed());
+		assert_eq!(
+			task_ref.description.description(),
+			"Call Mom +Family @phone"
+		);
+		assert_eq!(task_ref.to_string(), input.trim_end());
+
+		let task = task_ref.into_owned();
+		assert_eq!(task, Task::from_str(input).unwrap());
+		assert_eq!(task.to_string(), input.trim_end());
+
+		assert_eq!(
+			TaskRef::parse("x\tCall Mom"),
+			TaskRef::parse("x Call Mom")
+		);
+		assert_eq!(TaskRef::parse(""), Err(ParseTaskError));
+	}
+
+	#[test]
+	fn description_components_double_ended() {
+		for input in [
+			"",
+			" ",
+			"Call Mom",
+			"+Family",
+			"  Call +Family @phone  due:x Mom ",
+			"+a @b c:d",
+			"x +a",
+		] {
+			let description = Description::new(input);
+			let forward = description.components().collect::<Vec<_>>();
+			let mut backward =
+				description.components().rev().collect::<Vec<_>>();
+			backward.reverse();
+
+			assert_eq!(forward, backward, "{:?}", input);
+			assert_eq!(description.components().len(), forward.len());
+
+			// Meet in the middle
+			let mut components = description.components();
+			let mut front = Vec::new();
+			let mut back = Vec::new();
+			while let Some(component) = components.next() {
+				front.push(component);
+				assert_eq!(
+					components.len(),
+					forward.len() - front.len() - back.len()
+				);
+				back.extend(components.next_back());
+			}
+			back.reverse();
+			front.extend(back);
+			assert_eq!(front, forward, "{:?}", input);
+		}
+	}
+
+	#[test]
+	fn component_display() {
+		assert_eq!(Component::Text("Call Mom ").to_string(), "Call Mom ");
+		assert_eq!(Component::Project("+Family").as_str(), Some("+Family"));
+		assert_eq!(Component::Context("@phone").to_string(), "@phone");
+
+		let custom =
+			Component::Custom { key: "due", separator: ":", value: "x" };
+		assert_eq!(custom.to_string(), "due:x");
+		assert_eq!(custom.as_str(), None);
+	}
+
+	#[test]
+	fn description_components_spanned() {
+		let input = "Grüße +Familie @Telefon due:x ";
+		let description = Description::new(input);
+
+		let spanned = description.components_spanned().collect::<Vec<_>>();
+		assert_eq!(spanned.len(), description.components().len());
+
+		let mut end = 0;
+		for (component, span) in &spanned {
+			// The spans are contiguous and cover the whole description
+			assert_eq!(span.low().as_usize(), end);
+			assert_eq!(input[span.to_range_usize()], component.to_string());
+			end = span.high().as_usize();
+		}
+		assert_eq!(end, input.len());
+
+		let mut reversed =
+			description.components_spanned().rev().collect::<Vec<_>>();
+		reversed.reverse();
+		assert_eq!(reversed, spanned);
+	}
+
+	#[test]
+	fn description_custom_grouped() {
+		let description =
+			Description::new("a:1 Text b:2 +Project a:3 c:4 b:5 a:6");
+
+		let grouped = description
+			.custom_grouped()
+			.map(|(key, values)| (key, values.collect::<Vec<_>>()))
+			.collect::<Vec<_>>();
+
+		assert_eq!(
+			grouped,
+			vec![
+				("a", vec!["1", "3", "6"]),
+				("b", vec!["2", "5"]),
+				("c", vec!["4"]),
+			]
+		);
+
+		assert_eq!(Description::new("No tags").custom_grouped().count(), 0);
+	}
+
+	#[test]
+	fn duplicate_tag_policy() {
+		use crate::{DuplicateTagPolicy, Recurrence};
+
+		let task = Task::from_str(
+			"Pay rent due:2024-06-01 due:invalid due:2024-01-01 rec:1w",
+		)
+		.unwrap();
+
+		assert_eq!(
+			task.description().tag_with("due", DuplicateTagPolicy::Last),
+			Ok(Some("2024-01-01"))
+		);
+
+		let err = task
+			.description()
+			.tag_with("due", DuplicateTagPolicy::Error)
+			.unwrap_err();
+		assert_eq!(err.key(), "due");
+
+		// Invalid values are ignored by the typed accessors
+		assert_eq!(
+			task.due_date_with(DuplicateTagPolicy::First),
+			Ok(Some(Date::from_ymd(2024, 6, 1)))
+		);
+		assert_eq!(
+			task.recurrence_with(DuplicateTagPolicy::Error),
+			Ok(Some(Recurrence::from_str("1w").unwrap()))
+		);
+		assert_eq!(task.due_date(), Some(Date::from_ymd(2024, 1, 1)));
+	}
+
+	#[cfg(feature = "regex")]
+	#[test]
+	fn regex_search() {
+		use regex::Regex;
+
+		let file =
+			TodoFile::from_str("Call Mom +Family\nx Grüße an Mom\nPay rent\n")
+				.unwrap();
+		let regex = Regex::new(r"\bMom\b").unwrap();
+
+		let matches = file
+			.grep(&regex)
+			.map(|m| {
+				let spans = m
+					.spans
+					.iter()
+					.map(|span| &m.task.description()[span.to_range_usize()])
+					.collect::<Vec<_>>();
+
+				(m.index, spans)
+			})
+			.collect::<Vec<_>>();
+
+		assert_eq!(matches, vec![(0, vec!["Mom"]), (1, vec!["Mom"])]);
+		assert_eq!(file.grep(&Regex::new("^Pay").unwrap()).count(), 1);
+		assert_eq!(file.grep(&Regex::new("Dad").unwrap()).count(), 0);
+	}
+
+	#[test]
+	fn description_words() {
+		let input = "\tGrüße\u{3000}an +Familie  @Telefon x:1:2 ";
+		let description = Description::new(input);
+
+		let words = description
+			.words()
+			.map(|(word, span)| {
+				assert_eq!(input[span.to_range_usize()], word.to_string());
+				word
+			})
+			.collect::<Vec<_>>();
+
+		assert_eq!(
+			words,
+			vec![
+				Component::Text("Grüße"),
+				Component::Text("an"),
+				Component::Project("+Familie"),
+				Component::Context("@Telefon"),
+				Component::Text("x:1:2"),
+			]
+		);
+
+		assert_eq!(Description::new("  ").words().next(), None);
+	}
+
+	#[test]
+	fn description_push_str_incremental() {
+		let parts = [
+			"Call Mom +Fam",
+			"ily",
+			" @",
+			"phone due",
+			":2021-08-01",
+			"\u{3000}x:1 ",
+			"+Work",
+			"",
+		];
+
+		let mut description = Description::new("");
+		let mut raw = String::new();
+
+		for part in &parts {
+			// Locate the components before pushing
+			let _ = description.project_ranges();
+
+			description.push_str(part);
+			raw.push_str(part);
+
+			let expected = Description::new(raw.as_str());
+			assert_eq!(description.description(), raw);
+			assert_eq!(
+				description.project_ranges(),
+				expected.project_ranges()
+			);
+			assert_eq!(
+				description.context_ranges(),
+				expected.context_ranges()
+			);
+			assert_eq!(description.custom_ranges(), expected.custom_ranges());
+		}
+
+		let mut description = Description::new("Call Mom");
+		description.append_component(Component::Text("  "));
+		description.append_component(Component::Context("@phone"));
+		assert_eq!(description, Description::new("Call Mom @phone"));
+		assert_eq!(description.contexts().collect::<Vec<_>>(), ["phone"]);
+	}
+
+	#[cfg(feature = "unicode-normalization")]
+	#[test]
+	fn description_normalized_names() {
+		use crate::NormalizationForm;
+
+		let description =
+			Description::new("Order +cafe\u{301} @\u{fb01}eld ke\u{301}y:1");
+
+		assert!(description
+			.has_project_normalized("caf\u{e9}", NormalizationForm::Nfc));
+		assert!(description
+			.has_project_normalized("+cafe\u{301}", NormalizationForm::Nfkc));
+		assert!(!description
+			.has_context_normalized("field", NormalizationForm::Nfc));
+		assert!(description
+			.has_context_normalized("field", NormalizationForm::Nfkc));
+		assert_eq!(
+			description.tag_normalized("k\u{e9}y", NormalizationForm::Nfc),
+			Some("1")
+		);
+		assert_eq!(
+			description.tag_normalized("key", NormalizationForm::Nfkc),
+			None
+		);
+	}
+
+	#[test]
+	fn description_edits_preserve_whitespace() {
+		let mut description = Description::new(
+			"@phone  Call\tMom  @phone  +Family\tdue:2021-08-01  a:1 ",
+		);
+
+		assert!(description.remove_context("phone"));
+		assert_eq!(
+			description.description(),
+			"Call\tMom  +Family\tdue:2021-08-01  a:1 "
+		);
+
+		assert_eq!(description.set_tag("due", "2021-09-01"), Ok(()));
+		assert!(description.remove_project("Family"));
+		assert_eq!(
+			description.description(),
+			"Call\tMom\tdue:2021-09-01  a:1 "
+		);
+
+		assert_eq!(description.add_context("home"), Ok(true));
+		assert_eq!(
+			description.description(),
+			"Call\tMom\tdue:2021-09-01  a:1 @home"
+		);
+
+		assert!(description.remove_tag("due"));
+		assert!(description.remove_tag("a"));
+		assert_eq!(description.description(), "Call\tMom @home");
+	}
+
+	#[test]
+	fn description_typed_tag() {
+		use std::time::Duration;
+
+		use crate::TagValue;
+
+		let description = Description::new(
+			"Task due:2021-08-01 n:-3 e:1.5h w:2w d:1d x:1.h y:h z:next-week",
+		);
+
+		assert_eq!(
+			description.typed_tag("due"),
+			Some(TagValue::Date(Date::from_ymd(2021, 8, 1)))
+		);
+		assert_eq!(description.typed_tag("n"), Some(TagValue::Integer(-3)));
+		assert_eq!(
+			description.typed_tag("e").and_then(|v| v.as_duration()),
+			Some(Duration::from_secs(90 * 60))
+		);
+		assert_eq!(
+			description.typed_tag("w").and_then(|v| v.as_duration()),
+			Some(Duration::from_secs(14 * 24 * 60 * 60))
+		);
+		assert_eq!(
+			description.typed_tag("d").and_then(|v| v.as_duration()),
+			Some(Duration::from_secs(24 * 60 * 60))
+		);
+		assert_eq!(
+			description.typed_tag("x").and_then(|v| v.as_duration()),
+			Some(Duration::from_secs(60 * 60))
+		);
+		assert_eq!(description.typed_tag("y"), Some(TagValue::Text("h")));
+		assert_eq!(
+			description.typed_tag("z").and_then(|v| v.as_text()),
+			Some("next-week")
+		);
+		assert_eq!(description.typed_tag("missing"), None);
+
+		// Out of range for a duration
+		let description = Description::new("Huge d:99999999999999999999999w");
+		assert_eq!(
+			description.typed_tag("d"),
+			Some(TagValue::Text("99999999999999999999999w"))
+		);
+	}
+
+	#[test]
+	fn description_known_tags() {
+		use crate::{KnownTag, Recurrence};
+
+		let description = Description::new(
+			"Task t:2021-07-25 pri:b pri:C rec:2w dep:1 dep:2 h:0 h:yes \
+			 estimate:2h",
+		);
+
+		assert_eq!(
+			description.known_tags().collect::<Vec<_>>(),
+			vec![
+				KnownTag::Threshold(Date::from_ymd(2021, 7, 25)),
+				KnownTag::Priority(Priority::C),
+				KnownTag::Recurrence(Recurrence::from_str("2w").unwrap()),
+				KnownTag::Dependency("1"),
+				KnownTag::Dependency("2"),
+				KnownTag::Hidden(false),
+			]
+		);
+
+		for tag in description.known_tags() {
+			assert!(description.has_tag(tag.key()));
+		}
+	}
+
+	#[test]
+	fn tag_registry() {
+		use crate::TagRegistry;
+
+		let mut registry = TagRegistry::new();
+		let _ = registry
+			.register("size", |value| {
+				value.parse::<u8>().map(drop).map_err(|err| err.to_string())
+			})
+			.register("due", |_| Ok(()));
+
+		assert!(registry.is_registered("size"));
+		assert!(registry.unregister("due"));
+		assert!(!registry.is_registered("due"));
+
+		let description = Description::new("Box size:300 due:x size:3 size:-");
+		let warnings = registry.validate(&description);
+
+		assert_eq!(
+			warnings
+				.iter()
+				.map(|w| (
+					w.value.as_str(),
+					&description[w.span.to_range_usize()]
+				))
+				.collect::<Vec<_>>(),
+			vec![("300", "size:300"), ("-", "size:-")]
+		);
+
+		let known = TagRegistry::with_known_tags();
+		assert!(known
+			.validate(&Description::new("due:2021-08-01 rec:1w h:1 pri:A"))
+			.is_empty());
+		assert_eq!(
+			known.validate(&Description::new("rec:often pri:AA h:2")).len(),
+			3
+		);
+	}
+
+	#[test]
+	fn project_paths() {
+		use crate::ProjectPath;
+
+		let path = ProjectPath::new("+A/B/C");
+
+		assert_eq!(
+			path.ancestors().map(|p| p.to_string()).collect::<Vec<_>>(),
+			["A/B", "A"]
+		);
+		assert_eq!(ProjectPath::new("A").parent(), None);
+		assert_eq!(ProjectPath::new("A").depth(), 1);
+		assert_eq!(ProjectPath::from("A").child("B").child("C"), path);
+		assert!(!path.is_ancestor_of(&path));
+		assert!(path.contains("A/B/C"));
+		assert!(!ProjectPath::new("A/B").contains("A"));
+
+		let description = Description::new("Task +A/B/C +D");
+		assert_eq!(
+			description.project_paths().map(|p| p.depth()).collect::<Vec<_>>(),
+			[3, 1]
+		);
+
+		let input = "x +A/B\n+AB\n+A\nTask\n+D +A/C\n";
+		let mut file = TodoFile::from_str(input).unwrap();
+
+		for _ in 0..2 {
+			let indices = file
+				.tasks_under_project("A")
+				.map(|(idx, _)| idx)
+				.collect::<Vec<_>>();
+			assert_eq!(indices, [0, 2, 4]);
+
+			file.build_index();
+		}
+	}
+
+	#[test]
+	fn task_eq_semantic() {
+		let a = Task::from_str("x 2021-08-01 2021-07-30 Call Mom +A @b c:d")
+			.unwrap();
+		let b = Task::from_str("x 2021-08-01 2021-07-30 c:d Call\tMom @b +A ")
+			.unwrap();
+
+		assert_ne!(a, b);
+		assert!(a.eq_semantic(&b));
+		assert!(b.eq_semantic(&a));
+
+		for other in &[
+			"2021-08-01 2021-07-30 Call Mom +A @b c:d",
+			"x 2021-08-02 2021-07-30 Call Mom +A @b c:d",
+			"x 2021-08-01 2021-07-30 Call Mom +A @b c:e",
+			"x 2021-08-01 2021-07-30 Call Mom +A @b",
+			"x 2021-08-01 2021-07-30 Call Dad +A @b c:d",
+		] {
+			assert!(
+				!a.eq_semantic(&Task::from_str(other).unwrap()),
+				"{}",
+				other
+			);
+		}
+	}
+
+	#[test]
+	fn span_positions() {
+		use crate::{BytePos, LineCol};
+
+		let text = "a\u{e9}\n\u{1F600}b\n";
+
+		let positions = (0..=text.len())
+			.map(|idx| {
+				let pos = BytePos::from_usize(idx);
+				(pos.to_utf16_offset(text), pos.to_line_col(text))
+			})
+			.collect::<Vec<_>>();
+
+		let lc = |line, column, utf16_column| {
+			Some(LineCol { line, column, utf16_column })
+		};
+
+		assert_eq!(positions[0], (Some(0), lc(0, 0, 0)));
+		assert_eq!(positions[2], (None, None));
+		assert_eq!(positions[3], (Some(2), lc(0, 2, 2)));
+		assert_eq!(positions[4], (Some(3), lc(1, 0, 0)));
+		assert_eq!(positions[6], (None, None));
+		assert_eq!(positions[8], (Some(5), lc(1, 1, 2)));
+		assert_eq!(positions[10], (Some(7), lc(2, 0, 0)));
+		assert_eq!(BytePos::from_usize(11).to_line_col(text), None);
+
+		for (idx, (utf16, _)) in positions.iter().enumerate() {
+			if let Some(utf16) = utf16 {
+				assert_eq!(
+					BytePos::from_utf16_offset(text, *utf16),
+					Some(BytePos::from_usize(idx))
+				);
+			}
+		}
+
+		// Within the surrogate pair of the emoji
+		assert_eq!(BytePos::from_utf16_offset(text, 4), None);
+		assert_eq!(BytePos::from_utf16_offset(text, 8), None);
+	}
+
+	#[test]
+	fn span_from_range() {
+		use std::convert::TryFrom;
+
+		use crate::{BytePos, ByteSpan};
+
+		let span = |low: u32, high: u32| {
+			Some(ByteSpan::new(
+				BytePos::from_u32(low),
+				BytePos::from_u32(high),
+			))
+		};
+
+		assert_eq!(ByteSpan::from_range(0_usize..=4_usize), span(0, 5));
+		assert_eq!(ByteSpan::from_range(0_usize..4_usize), span(0, 4));
+		assert_eq!(ByteSpan::from_range(2_usize..), span(2, u32::MAX));
+		assert_eq!(ByteSpan::from_range(..3_u32), span(0, 3));
+		assert_eq!(ByteSpan::from_range(..=3_u32), span(0, 4));
+		assert_eq!(ByteSpan::from_range::<u32, _>(..), span(0, u32::MAX));
+		assert_eq!(
+			ByteSpan::from_range((
+				std::ops::Bound::Excluded(1_u32),
+				std::ops::Bound::Included(4_u32),
+			)),
+			span(2, 5)
+		);
+		assert_eq!(ByteSpan::from_range(0_u32..=u32::MAX), None);
+		if let Some(too_large) = (u32::MAX as usize).checked_add(1) {
+			assert_eq!(ByteSpan::from_range(0..too_large), None);
+			assert!(BytePos::try_from(too_large).is_err());
+		}
+
+		// Offsets are checked instead of wrapping
+		let pos = BytePos::from_u32(1);
+		assert_eq!(pos.checked_offset(-1), Some(BytePos::MIN));
+		assert_eq!(pos.checked_offset(-2), None);
+		assert_eq!(BytePos::MAX.checked_offset(1), None);
+		assert_eq!(span(1, 3).unwrap().checked_offset(-1), span(0, 2));
+		assert_eq!(span(1, 3).unwrap().checked_offset_low(-2), None);
+		assert_eq!(span(1, 3).unwrap().checked_offset_high(2), span(1, 5));
+		assert_eq!(BytePos::try_from(7_usize), Ok(BytePos::from_u32(7)));
+	}
+
+	#[test]
+	fn highlight_tokens() {
+		use crate::{highlight, TokenKind};
+
+		let tokens = |line: &'static str| {
+			highlight(line)
+				.map(|(kind, span)| (kind, &line[span.to_range_usize()]))
+				.collect::<Vec<_>>()
+		};
+
+		assert_eq!(
+			tokens("(a) 2021-07-30 @home x:1:2 +"),
+			vec![
+				(TokenKind::Text, "(a)"),
+				(TokenKind::Text, "2021-07-30"),
+				(TokenKind::Context, "@home"),
+				(TokenKind::Text, "x:1:2"),
+				(TokenKind::Text, "+"),
+			]
+		);
+		assert_eq!(
+			tokens("x 2021-07-30  Grüße"),
+			vec![
+				(TokenKind::State, "x"),
+				(TokenKind::CreationDate, "2021-07-30"),
+				(TokenKind::Text, "Grüße"),
+			]
+		);
+		assert_eq!(tokens("(B)"), vec![(TokenKind::Priority, "(B)")]);
+		assert_eq!(tokens("xylophone"), vec![(TokenKind::Text, "xylophone")]);
+		assert_eq!(tokens(""), vec![]);
+	}
+
+	#[cfg(feature = "time")]
+	#[test]
+	fn date_time_backend() {
+		let inner =
+			time::Date::from_calendar_date(2021, time::Month::August, 1)
+				.unwrap();
+		let date = Date::from(inner);
+
+		assert_eq!(date, Date::from_ymd(2021, 8, 1));
+		assert_eq!(time::Date::from(date), inner);
+		assert_eq!(date.to_string(), "2021-08-01");
+		assert_eq!(Date::from_ymd_opt(2021, 2, 29), None);
+		assert_eq!(
+			date.checked_add_months(-6),
+			Some(Date::from_ymd(2021, 2, 1))
+		);
+		assert_eq!(date.checked_add_days(crate::Days(i64::MAX)), None);
+	}
+
+	#[test]
+	fn simple_date_arithmetic() {
+		use crate::SimpleDate;
+
+		let date = SimpleDate::from_ymd(2021, 12, 31);
+
+		assert_eq!(date.succ(), Some(SimpleDate::from_ymd(2022, 1, 1)));
+		assert_eq!(date.pred(), Some(SimpleDate::from_ymd(2021, 12, 30)));
+		assert_eq!(
+			date.sub_days(365),
+			Some(SimpleDate::from_ymd(2020, 12, 31))
+		);
+		assert_eq!(
+			SimpleDate::from_ymd(1900, 2, 28).add_days(1),
+			Some(SimpleDate::from_ymd(1900, 3, 1))
+		);
+		assert_eq!(
+			SimpleDate::from_ymd(2000, 2, 28).add_days(1),
+			Some(SimpleDate::from_ymd(2000, 2, 29))
+		);
+		assert_eq!(SimpleDate::from_ymd(2000, 3, 1) - date, -7975);
+		assert_eq!(SimpleDate::from_ymd(i16::MAX, 12, 31).succ(), None);
+		assert_eq!(date.add_days(i64::MAX), None);
+
+		// Conversions are available with every backend
+		assert_eq!(Date::from(date), Date::from_ymd(2021, 12, 31));
+		assert_eq!(SimpleDate::from(Date::from(date)), date);
+	}
+
+	#[test]
+	fn date_calendar_validation() {
+		use std::str::FromStr;
+
+		assert!(Date::from_str("2020-02-29").is_ok());
+		assert!(Date::from_str("2021-02-29").is_err());
+		assert!(Date::from_str("1900-02-29").is_err());
+		assert!(Date::from_str("2021-06-31").is_err());
+		assert_eq!(Date::from_ymd_opt(2021, 2, 30), None);
+
+		let task = Task::from_str("2021-02-30 Call Mom").unwrap();
+		assert_eq!(task.date_compound(), None);
+		assert_eq!(task.description().description(), "2021-02-30 Call Mom");
+	}
+
+	#[test]
+	fn date_accessors() {
+		use crate::Weekday;
+
+		let date = Date::from_ymd(2020, 12, 31);
+
+		assert_eq!((date.year(), date.month(), date.day()), (2020, 12, 31));
+		assert_eq!(date.ordinal(), 366);
+		assert_eq!(date.weekday(), Weekday::Thursday);
+		assert_eq!(Date::from_ymd(1969, 12, 29).weekday(), Weekday::Monday);
+		assert_eq!(Date::from_ymd(2021, 1, 1).ordinal(), 1);
+		assert_eq!(Date::from_ymd(2021, 3, 1).ordinal(), 60);
+		assert_eq!(Weekday::from_monday(13), Weekday::Sunday);
+		assert_eq!(Weekday::Sunday.days_from_monday(), 6);
+
+		// Years of backing dates outside of `i16`
+		#[cfg(feature = "chrono")]
+		{
+			let date = Date::from(
+				chrono::NaiveDate::from_ymd_opt(40000, 3, 1).unwrap(),
+			);
+			assert_eq!(date.year(), 40000);
+			assert_eq!(date.ordinal(), 61);
+		}
+	}
+
+	#[test]
+	fn date_parse_relative() {
+		// A monday
+		let today = Date::from_ymd(2021, 1, 25);
+		let parse = |s: &str| Date::parse_relative(s, today);
+
+		assert_eq!(parse(" Today "), Some(today));
+		assert_eq!(parse("yesterday"), Some(Date::from_ymd(2021, 1, 24)));
+		assert_eq!(parse("mon"), Some(Date::from_ymd(2021, 2, 1)));
+		assert_eq!(parse("SUNDAY"), Some(Date::from_ymd(2021, 1, 31)));
+		assert_eq!(parse("3d"), Some(Date::from_ymd(2021, 1, 28)));
+		assert_eq!(parse("-1w"), Some(Date::from_ymd(2021, 1, 18)));
+		assert_eq!(parse("+1m"), Some(Date::from_ymd(2021, 2, 25)));
+		assert_eq!(parse("-1y"), Some(Date::from_ymd(2020, 1, 25)));
+		assert_eq!(parse("2021/2/1"), Some(Date::from_ymd(2021, 2, 1)));
+		assert_eq!(parse("+-1d"), None);
+		assert_eq!(parse("+d"), None);
+		assert_eq!(parse("1x"), None);
+		assert_eq!(parse("mo"), None);
+		assert_eq!(parse(""), None);
+		assert_eq!(parse("+99999999999999999999d"), None);
+	}
+
+	#[test]
+	fn date_relative_to() {
+		let today = Date::from_ymd(2021, 8, 1);
+		let relative = |y, m, d| Date::from_ymd(y, m, d).relative_to(today);
+
+		assert_eq!(relative(2021, 8, 1).to_string(), "today");
+		assert_eq!(relative(2021, 7, 31).to_string(), "yesterday");
+		assert_eq!(relative(2021, 7, 26).to_string(), "6 days ago");
+		assert_eq!(relative(2021, 8, 8).to_string(), "in 1 week");
+		assert_eq!(relative(2021, 8, 31).to_string(), "in 1 month");
+		assert_eq!(relative(2021, 3, 1).to_string(), "5 months ago");
+		assert_eq!(relative(2023, 8, 1).to_string(), "in 2 years");
+		assert_eq!(relative(2021, 3, 1).days(), -153);
+	}
+
+	#[test]
+	fn date_today() {
+		use crate::Clock;
+
+		// The date may change between the calls (e.g. at midnight)
+		let before = Date::today();
+		let relative = Date::parse_relative_today("today").unwrap();
+		let clock = CompletionPolicy::default().clock.today();
+		let after = Date::today();
+
+		assert!(before.year() >= 2021);
+		assert!(before <= after);
+		for date in &[relative, clock] {
+			assert!(*date == before || *date == after, "{}", date);
+		}
+	}
+
+	#[test]
+	fn clock_completion_policy() {
+		use crate::{Clock, FixedClock};
+
+		let clock = FixedClock(Date::from_ymd(2021, 8, 1));
+		let policy = CompletionPolicy::new(clock);
+
+		let mut task = Task::from_str("2021-07-30 Call Mom").unwrap();
+		assert_eq!(task.toggle_state(&policy), State::Done);
+		assert_eq!(task.to_string(), "x 2021-08-01 2021-07-30 Call Mom");
+
+		let later = CompletionPolicy::new(|| {
+			clock.today().checked_add_days(crate::Days(1)).unwrap()
+		});
+		let mut task = Task::from_str("Call Mom").unwrap();
+		assert_eq!(task.toggle_state(&later), State::Done);
+		assert_eq!(
+			task.date_compound().and_then(|dates| dates.date_completed()),
+			Some(&Date::from_ymd(2021, 8, 2))
+		);
+	}
+
+	#[test]
+	fn date_order_validation() {
+		use crate::{DecodeOptions, TodoFile};
+
+		let bytes = b"x 2021-07-31 2021-08-01 Call Mom\n";
+
+		assert!(Task::from_bytes(bytes, DecodeOptions::new()).is_ok());
+		assert!(TodoFile::from_bytes(bytes, DecodeOptions::new()).is_ok());
+
+		let options = DecodeOptions::new().validate_dates(true);
+		assert!(Task::from_bytes(bytes, options).is_err());
+		assert_eq!(
+			TodoFile::from_bytes(bytes, options).unwrap_err().to_string(),
+			"failed to parse todo file: completion date 2021-07-31 is before \
+			 creation date 2021-08-01 on line 1"
+		);
+
+		let bytes = b"x 2021-08-01 2021-08-01 Call Mom\n2021-08-01 Pay rent\n";
+		assert!(Task::from_bytes(b"2021-08-01 Pay rent", options).is_ok());
+		assert!(TodoFile::from_bytes(bytes, options).is_ok());
+
+		let err = DateCompound::completed_checked(
+			Date::from_ymd(2021, 8, 1),
+			Date::from_ymd(2021, 7, 31),
+		)
+		.unwrap_err();
+		assert_eq!(err.created(), &Date::from_ymd(2021, 8, 1));
+		assert_eq!(err.completed(), &Date::from_ymd(2021, 7, 31));
+	}
+
+	#[test]
+	fn filter_date_ranges() {
+		use std::ops::Bound;
+
+		use crate::{DateRange, Filter};
+
+		let start = Date::from_ymd(2021, 8, 1);
+		let end = Date::from_ymd(2021, 8, 31);
+		let task =
+			Task::from_str("2021-08-31 Pay rent due:2021-08-01").unwrap();
+
+		assert!(Filter::due_within(start..=end).matches(&task));
+		assert!(!Filter::due_within(..start).matches(&task));
+		assert!(Filter::due_within(start..).matches(&task));
+		assert!(Filter::created_in(start..=end).matches(&task));
+		assert!(!Filter::created_in(start..end).matches(&task));
+		assert!(!Filter::completed_in(..).matches(&task));
+		assert!(!Filter::due_within(..)
+			.matches(&Task::from_str("Call Mom").unwrap()));
+
+		let range = DateRange::new((Bound::Excluded(start), Bound::Unbounded));
+		assert!(!range.contains(&start));
+		assert!(range.contains(&end));
+		assert_eq!(DateRange::default(), DateRange::new(..));
+	}
+
+	#[test]
+	fn date_compound_ordering() {
+		let first = Date::from_ymd(2021, 8, 1);
+		let second = Date::from_ymd(2021, 8, 2);
+
+		assert!(first < second);
+		assert!(DateCompound::created(first) < DateCompound::created(second));
+		assert!(
+			DateCompound::created(first)
+				< DateCompound::completed(first, first)
+		);
+		assert!(
+			DateCompound::completed(first, second)
+				< DateCompound::created(second)
+		);
+		assert!(
+			DateCompound::completed(first, first)
+				< DateCompound::completed(first, second)
+		);
+	}
+
+	#[test]
+	fn date_format_with() {
+		let date = Date::from_ymd(2005, 12, 9);
+		let format = |pattern| date.format_with(pattern).to_string();
+
+		assert_eq!(format("yyyy-mm-dd"), date.to_string());
+		assert_eq!(format("dddd, d. mmmm yyyy"), "Friday, 9. December 2005");
+		assert_eq!(format("yy/m/d"), "05/12/9");
+		assert_eq!(format("yyy ddddd Y M D ü"), "yyy ddddd Y M D ü");
+		assert_eq!(format(""), "");
+	}
+
+	#[test]
+	fn date_iso_week() {
+		use crate::IsoWeek;
+
+		let week = |y, m, d| {
+			let week = Date::from_ymd(y, m, d).iso_week();
+			(week.year, week.week)
+		};
+
+		assert_eq!(week(2016, 1, 1), (2015, 53));
+		assert_eq!(week(2021, 1, 4), (2021, 1));
+		assert_eq!(week(2021, 8, 1), (2021, 30));
+		assert_eq!(week(2024, 12, 30), (2025, 1));
+		assert_eq!(week(2026, 12, 31), (2026, 53));
+		assert_eq!(week(2000, 1, 2), (1999, 52));
+		assert_eq!(IsoWeek::weeks_in_year(2021), 52);
+		assert_eq!(IsoWeek::weeks_in_year(2004), 53);
+		assert!(week(2020, 12, 31) < week(2021, 1, 4));
+
+		let date = Date::from_ymd(2021, 8, 4);
+		assert_eq!(date.week_start(), Some(Date::from_ymd(2021, 8, 2)));
+		assert_eq!(date.week_end(), Some(Date::from_ymd(2021, 8, 8)));
+	}
+
+	#[cfg(feature = "datetime")]
+	#[test]
+	fn tag_datetime() {
+		use crate::description::DescriptionOptions;
+		use crate::{DateTimeTag, TagValue};
+
+		let description = Description::with_options(
+			"Call Mom due:2016-05-30T14:00:30 t:2016-05-29",
+			DescriptionOptions::new().colons_in_values(true),
+		);
+
+		let due = description.typed_tag("due").and_then(|v| v.as_datetime());
+		assert_eq!(
+			due,
+			DateTimeTag::new(Date::from_ymd(2016, 5, 30), 14, 0, 30)
+		);
+		assert_eq!(due.unwrap().to_string(), "2016-05-30T14:00:30");
+		assert_eq!(
+			description.typed_tag("t"),
+			Some(TagValue::Date(Date::from_ymd(2016, 5, 29)))
+		);
+
+		for value in &["2016-05-30T14", "2016-05-30T14:60", "2016-05-30 14:00"]
+		{
+			assert!(DateTimeTag::from_str(value).is_err());
+			assert!(!matches!(TagValue::parse(value), TagValue::DateTime(_)));
+		}
+
+		// Parsed tasks
+		let options = crate::DecodeOptions::new().description_options(
+			DescriptionOptions::new().colons_in_values(true),
+		);
+		let task = Task::from_bytes(b"Call Mom due:2016-05-30T14:00", options)
+			.unwrap();
+		assert_eq!(
+			task.description.typed_tag("due").and_then(|v| v.as_datetime()),
+			DateTimeTag::new(Date::from_ymd(2016, 5, 30), 14, 0, 0)
+		);
+	}
+
+	#[cfg(all(feature = "serde", feature = "chrono"))]
+	#[test]
+	fn serde_chrono_helpers() {
+		#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+		struct Dates {
+			#[serde(with = "crate::serde_chrono")]
+			date: Date,
+
+			#[serde(with = "crate::serde_chrono::option")]
+			optional: Option<Date>,
+		}
+
+		let dates = Dates {
+			date: Date::from_ymd(2021, 8, 1),
+			optional: Some(Date::from_ymd(2021, 8, 2)),
+		};
+		let json = serde_json::to_string(&dates).unwrap();
+
+		assert_eq!(json, r#"{"date":"2021-08-01","optional":"2021-08-02"}"#);
+		assert_eq!(serde_json::from_str::<Dates>(&json).unwrap(), dates);
+		assert!(serde_json::from_str::<Dates>(
+			r#"{"date":"+40000-01-01","optional":null}"#
+		)
+		.is_err());
+	}
+
+	#[test]
+	fn date_days_until() {
+		let date = Date::from_ymd(1969, 12, 31);
+
+		assert_eq!(date.days_until(&Date::from_ymd(1970, 1, 1)), 1);
+		assert_eq!(date.days_until(&Date::from_ymd(2000, 3, 1)), 11018);
+		assert_eq!(Date::from_ymd(2000, 3, 1).days_until(&date), -11018);
+		assert_eq!(
+			Date::from_ymd(2021, 8, 1).days_until(&Date::from_ymd(2021, 8, 4)),
+			Date::from_ymd(2021, 8, 4)
+				.relative_to(Date::from_ymd(2021, 8, 1))
+				.days()
+		);
+	}
+
+	#[test]
+	fn todo_file_index_sigils() {
+		let mut todo =
+			TodoFile::from_str("Call Mom +Family @phone\nBuy milk\n").unwrap();
+
+		let queries = |todo: &TodoFile| {
+			[
+				todo.tasks_with_project("Family").count(),
+				todo.tasks_with_project("+Family").count(),
+				todo.tasks_with_context("phone").count(),
+				todo.tasks_with_context("@phone").count(),
+			]
+		};
+
+		let scanned = queries(&todo);
+		todo.build_index();
+		let indexed = queries(&todo);
+
+		assert_eq!(scanned, [1, 1, 1, 1]);
+		assert_eq!(indexed, scanned);
+	}
+
+	#[test]
+	fn decode_description_options() {
+		use crate::{DecodeOptions, DescriptionOptions, TodoFile};
+
+		let bytes = b"Read link:https://example.com\n";
+		let options = DecodeOptions::new().description_options(
+			DescriptionOptions::new().colons_in_values(true),
+		);
+
+		let task = Task::from_bytes(bytes, DecodeOptions::new()).unwrap();
+		assert_eq!(task.description.tag("link"), None);
+
+		let task = Task::from_bytes(bytes, options).unwrap();
+		assert_eq!(task.description.tag("link"), Some("https://example.com"));
+		assert_eq!(task.to_string(), "Read link:https://example.com");
+
+		let todo = TodoFile::from_bytes(bytes, options).unwrap();
+		assert_eq!(
+			todo.tasks()[0].description.tag("link"),
+			Some("https://example.com")
+		);
+	}
+
+	#[test]
+	fn description_size() {
+		use std::mem::size_of;
+
+		// The text, the lazily located components and the source line
+		assert!(size_of::<Description>() <= 7 * size_of::<usize>());
+	}
+
+	#[test]
+	fn cursor_whitespace() {
+		// The ASCII fast path agrees with `char::is_whitespace`
+		for byte in 0..0x80_u8 {
+			let bytes = [b'a', byte];
+			let mut cursor = Cursor::new(&bytes);
+
+			assert_eq!(
+				cursor.is_whitespace_at(1),
+				char::from(byte).is_whitespace(),
+				"{:#04x}",
+				byte
+			);
+
+			cursor.consume_non_whitespaces();
+			let expected =
+				if char::from(byte).is_whitespace() { 1 } else { 2 };
+			assert_eq!(cursor.index(), expected, "{:#04x}", byte);
+		}
+
+		// Non-ASCII whitespace is decoded
+		let input = "a\u{a0}\u{3000} \u{e9}b";
+		let mut cursor = Cursor::new(input.as_bytes());
+		cursor.consume_non_whitespaces();
+		assert_eq!(cursor.index(), 1);
+		assert!(cursor.is_whitespace_at(0));
+		cursor.consume_whitespaces();
+		assert_eq!(&input[cursor.index()..], "\u{e9}b");
+		assert!(!cursor.is_whitespace_at(0));
+	}
+
+	#[test]
+	fn date_order_validation_skipped_lines() {
+		use crate::{DecodeOptions, InvalidUtf8Policy, TodoFile};
+
+		let bytes =
+			b"# notes\n\xFF broken\nx 2021-08-02 2021-08-01 Call Mom\n\
+		              \xFF broken\nx 2021-07-31 2021-08-01 Pay rent\n";
+		let options = DecodeOptions::new()
+			.invalid_utf8(InvalidUtf8Policy::SkipLine)
+			.validate_dates(true);
+
+		// Line numbers refer to the input, including the skipped lines.
+		assert_eq!(
+			TodoFile::from_bytes(bytes, options).unwrap_err().to_string(),
+			"failed to parse todo file: completion date 2021-07-31 is before \
+			 creation date 2021-08-01 on line 5"
+		);
+
+		let todo = TodoFile::from_bytes(bytes, options.validate_dates(false))
+			.unwrap();
+		assert_eq!(todo.check_date_order().unwrap_err().0, 3);
+	}
 }
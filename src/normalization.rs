@@ -0,0 +1,38 @@
+use unicode_normalization::UnicodeNormalization;
+
+/// A unicode normalization form used to compare names, e.g. of projects.
+///
+/// Depending on the platform and editor the same text may be stored with
+/// different code points, e.g. `café` with a precomposed `é` or with an `e`
+/// followed by a combining accent. Comparing the normalized forms treats
+/// both as equal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum NormalizationForm {
+	/// Canonical composition (NFC).
+	Nfc,
+
+	/// Compatibility composition (NFKC), which additionally treats e.g. the
+	/// ligature `ﬁ` as `fi`.
+	Nfkc,
+}
+
+/// Returns `true` if `a` and `b` are equal after normalizing both according
+/// to `form`.
+///
+/// # Examples
+///
+/// ```rust
+/// use tdtxt::{eq_normalized, NormalizationForm};
+///
+/// assert!(eq_normalized("caf\u{e9}", "cafe\u{301}", NormalizationForm::Nfc));
+/// assert!(!eq_normalized("\u{fb01}le", "file", NormalizationForm::Nfc));
+/// assert!(eq_normalized("\u{fb01}le", "file", NormalizationForm::Nfkc));
+/// ```
+pub fn eq_normalized(a: &str, b: &str, form: NormalizationForm) -> bool {
+	match form {
+		NormalizationForm::Nfc => a.nfc().eq(b.nfc()),
+		NormalizationForm::Nfkc => a.nfkc().eq(b.nfkc()),
+	}
+}
@@ -101,6 +101,41 @@ macro_rules! impl_fromstr {
 	};
 }
 
+/// Implements `parse_prefix` for a type which implements
+/// [`Parse`](`crate::parse::Parse`).
+///
+/// # Notes
+///
+/// The same requirements as for [`impl_fromstr`] apply.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! impl_parse_prefix {
+	( $ty:ty ) => {
+		impl $ty {
+			#[doc = concat!("Parses a leading [`", stringify!($ty), "`] from `s` and returns it together with the remaining input.")]
+			///
+			/// Like within a task, the component must be followed by a
+			/// whitespace or the end of the input. The separating whitespace
+			/// is not part of the remaining input.
+			pub fn parse_prefix(
+				s: &str,
+			) -> ::std::result::Result<(Self, &str), <Self as Parse>::Error> {
+				let mut parser = Parser::new(s.as_bytes());
+
+				let tmp = <$ty>::parse(&mut parser)?;
+
+				if parser.is_eof() || parser.expect_whitespace().is_some() {
+					Ok((tmp, s.split_at(parser.index()).1))
+				} else {
+					Err(<Self as Parse>::Error::with_msg(
+						"missing whitespace after component",
+					))
+				}
+			}
+		}
+	};
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Parser<'a> {
 	cursor: Cursor<'a>,
@@ -115,6 +150,14 @@ impl<'a> Parser<'a> {
 		self.cursor.is_eof()
 	}
 
+	pub const fn index(&self) -> usize {
+		self.cursor.index()
+	}
+
+	pub fn slice(&self, start: usize, end: usize) -> &'a [u8] {
+		&self.cursor.bytes[start..end]
+	}
+
 	pub fn parse_u8(&mut self) -> Option<u8> {
 		self.cursor.consume()
 	}
@@ -156,7 +199,7 @@ impl<'a> Parser<'a> {
 		}
 	}
 
-	pub fn parse_until(&mut self, terminator: u8) -> Option<&[u8]> {
+	pub fn parse_until(&mut self, terminator: u8) -> Option<&'a [u8]> {
 		if self.cursor.is_eof() {
 			None
 		} else {
@@ -222,6 +265,13 @@ impl<'a> Cursor<'a> {
 		Self { bytes, index: 0 }
 	}
 
+	/// Creates a cursor which starts at `index`.
+	pub fn with_index(bytes: &'a [u8], index: usize) -> Self {
+		let mut cursor = Self::new(bytes);
+		cursor.advance_to(index);
+		cursor
+	}
+
 	pub fn consume(&mut self) -> Option<u8> {
 		let byte = self.first()?;
 		self.advance(1);
@@ -251,12 +301,72 @@ impl<'a> Cursor<'a> {
 		}
 	}
 
+	/// Consumes all (unicode) whitespace characters.
 	pub fn consume_whitespaces(&mut self) {
-		self.consume_while(|b| b.is_ascii_whitespace());
+		while let Some(len) = self.whitespace_len() {
+			self.advance(len);
+		}
 	}
 
+	/// Consumes all bytes up to the next (unicode) whitespace character.
 	pub fn consume_non_whitespaces(&mut self) {
-		self.consume_while(|b| !b.is_ascii_whitespace());
+		self.consume_word_while(|_| true);
+	}
+
+	/// Consumes bytes matching `predicate` up to the next (unicode)
+	/// whitespace character.
+	pub fn consume_word_while<P>(&mut self, predicate: P)
+	where
+		P: Fn(u8) -> bool,
+	{
+		while let Some(byte) = self.first() {
+			if self.is_whitespace_at(0) || !predicate(byte) {
+				break;
+			}
+
+			self.advance(1);
+		}
+	}
+
+	/// Returns `true` if a (unicode) whitespace character starts `offset`
+	/// bytes after the current position.
+	pub fn is_whitespace_at(&self, offset: usize) -> bool {
+		self.whitespace_len_at(self.index + offset).is_some()
+	}
+
+	/// Returns the length in bytes of the whitespace character at the
+	/// current position.
+	fn whitespace_len(&self) -> Option<usize> {
+		self.whitespace_len_at(self.index)
+	}
+
+	/// Returns the length in bytes of the whitespace character starting at
+	/// `index`.
+	#[inline(always)]
+	fn whitespace_len_at(&self, index: usize) -> Option<usize> {
+		let byte = self.get(index)?;
+
+		// Only decode characters outside of ASCII
+		if byte.is_ascii() {
+			return is_ascii_whitespace(byte).then_some(1);
+		}
+
+		self.char_at(index).filter(|c| c.is_whitespace()).map(char::len_utf8)
+	}
+
+	/// Decodes the character starting at `index`. Returns `None` if `index`
+	/// is out of bounds or not at the start of a valid UTF-8 sequence.
+	fn char_at(&self, index: usize) -> Option<char> {
+		let rest = self.bytes.get(index..)?;
+		let bytes = &rest[..rest.len().min(4)];
+
+		let valid = match std::str::from_utf8(bytes) {
+			Ok(valid) => valid,
+			Err(err) => std::str::from_utf8(&bytes[..err.valid_up_to()])
+				.expect("valid prefix"),
+		};
+
+		valid.chars().next()
 	}
 
 	pub const fn first(&self) -> Option<u8> {
@@ -303,7 +413,7 @@ impl<'a> Cursor<'a> {
 	}
 
 	#[inline(always)]
-	fn index_mut(&mut self) -> &mut usize {
+	const fn index_mut(&mut self) -> &mut usize {
 		&mut self.index
 	}
 
@@ -317,3 +427,12 @@ impl<'a> Cursor<'a> {
 		self.index >= self.bytes.len()
 	}
 }
+
+/// Returns `true` if the ASCII byte `byte` is a whitespace character
+/// according to [`char::is_whitespace`].
+///
+/// Unlike [`u8::is_ascii_whitespace`] this includes the vertical tab.
+#[inline(always)]
+const fn is_ascii_whitespace(byte: u8) -> bool {
+	byte.is_ascii_whitespace() || byte == 0x0b
+}
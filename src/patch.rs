@@ -0,0 +1,306 @@
+use std::iter::FromIterator;
+
+use crate::date::{Date, DateCompound, DUE_TAG};
+use crate::description::BuildDescriptionError;
+use crate::diff::TaskChange;
+use crate::priority::Priority;
+use crate::state::State;
+use crate::task::Task;
+
+/// A single operation of a [`TaskPatch`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PatchOp {
+	/// Sets the state.
+	SetState(State),
+
+	/// Sets or removes the priority.
+	SetPriority(Option<Priority>),
+
+	/// Sets or removes the creation and completion date.
+	SetDateCompound(Option<DateCompound>),
+
+	/// Replaces the whole description.
+	SetDescription(String),
+
+	/// Appends the project (with or without `+`) if the task does not
+	/// contain it.
+	AddProject(String),
+
+	/// Removes all occurrences of the project (without `+`).
+	RemoveProject(String),
+
+	/// Appends the context (with or without `@`) if the task does not
+	/// contain it.
+	AddContext(String),
+
+	/// Removes all occurrences of the context (without `@`).
+	RemoveContext(String),
+
+	/// Sets the value of all custom tags with the key or appends the tag if
+	/// there is none.
+	SetTag {
+		/// The key of the tag.
+		key: String,
+
+		/// The new value of the tag.
+		value: String,
+	},
+
+	/// Appends the custom tag.
+	AddTag {
+		/// The key of the tag.
+		key: String,
+
+		/// The value of the tag.
+		value: String,
+	},
+
+	/// Removes all custom tags with the key and, if given, the value.
+	RemoveTag {
+		/// The key of the tag.
+		key: String,
+
+		/// The value of the tag.
+		value: Option<String>,
+	},
+}
+
+impl PatchOp {
+	/// Applies the operation to `task`.
+	///
+	/// # Errors
+	///
+	/// Returns an error if a project, context or tag is invalid (see
+	/// [`Description::add_project`](`crate::Description::add_project`),
+	/// [`Description::add_context`](`crate::Description::add_context`) and
+	/// [`Description::set_tag`](`crate::Description::set_tag`)).
+	fn apply(&self, task: &mut Task) -> Result<(), BuildDescriptionError> {
+		let description = &mut task.description;
+
+		match self {
+			Self::SetState(state) => task.state = *state,
+			Self::SetPriority(priority) => task.priority = *priority,
+			Self::SetDateCompound(date_compound) => {
+				task.date_compound = *date_compound;
+			}
+			Self::SetDescription(text) => {
+				description.edit(|raw| *raw = text.clone());
+			}
+			Self::AddProject(project) => {
+				let _ = description.add_project(project)?;
+			}
+			Self::RemoveProject(project) => {
+				let _ = description.remove_project(project);
+			}
+			Self::AddContext(context) => {
+				let _ = description.add_context(context)?;
+			}
+			Self::RemoveContext(context) => {
+				let _ = description.remove_context(context);
+			}
+			Self::SetTag { key, value } => description.set_tag(key, value)?,
+			Self::AddTag { key, value } => description.push_tag(key, value)?,
+			Self::RemoveTag { key, value } => {
+				let _ = description.remove_tags(key, value.as_deref());
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// A list of operations which can be applied to a [`Task`].
+///
+/// Patches can either be created from individual operations or from the
+/// differences of two tasks (see [`TaskPatch::from_diff`]), e.g. to implement
+/// undo.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::str::FromStr;
+///
+/// use tdtxt::{Date, Priority, Task, TaskPatch};
+///
+/// let task = Task::from_str("(B) Call Mom +Family @phone").unwrap();
+///
+/// let patch = TaskPatch::new()
+///     .set_priority(Priority::A)
+///     .remove_context("phone")
+///     .set_due(Date::from_ymd(2021, 8, 1));
+///
+/// let patched = patch.apply(&task)?;
+/// assert_eq!(patched.to_string(), "(A) Call Mom +Family due:2021-08-01");
+///
+/// let undo = TaskPatch::from_diff(&patched, &task);
+/// assert_eq!(undo.apply(&patched)?, task);
+///
+/// assert!(TaskPatch::new().add_project("Two words").apply(&task).is_err());
+/// # Ok::<(), tdtxt::BuildDescriptionError>(())
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(transparent)
+)]
+pub struct TaskPatch {
+	ops: Vec<PatchOp>,
+}
+
+impl TaskPatch {
+	/// Creates an empty patch.
+	pub const fn new() -> Self {
+		Self { ops: Vec::new() }
+	}
+
+	/// Creates a patch which turns `from` into `to`.
+	///
+	/// Applying the patch to `from` results in a task equal to `to`.
+	pub fn from_diff(from: &Task, to: &Task) -> Self {
+		let ops = from
+			.diff(to)
+			.into_iter()
+			.filter_map(|change| match change {
+				TaskChange::State { to, .. } => Some(PatchOp::SetState(to)),
+				TaskChange::Priority { to, .. } => {
+					Some(PatchOp::SetPriority(to))
+				}
+				TaskChange::DateCompound { to, .. } => {
+					Some(PatchOp::SetDateCompound(to))
+				}
+				TaskChange::Description { to, .. } => {
+					Some(PatchOp::SetDescription(to))
+				}
+				// Already covered by the description
+				_ => None,
+			})
+			.collect();
+
+		Self { ops }
+	}
+
+	/// Appends `op` to the patch.
+	pub fn push(mut self, op: PatchOp) -> Self {
+		self.ops.push(op);
+		self
+	}
+
+	/// Appends [`PatchOp::SetState`].
+	pub fn set_state(self, state: State) -> Self {
+		self.push(PatchOp::SetState(state))
+	}
+
+	/// Appends [`PatchOp::SetPriority`] which sets the priority.
+	pub fn set_priority(self, priority: Priority) -> Self {
+		self.push(PatchOp::SetPriority(Some(priority)))
+	}
+
+	/// Appends [`PatchOp::SetPriority`] which removes the priority.
+	pub fn clear_priority(self) -> Self {
+		self.push(PatchOp::SetPriority(None))
+	}
+
+	/// Appends [`PatchOp::AddProject`].
+	pub fn add_project<S: Into<String>>(self, project: S) -> Self {
+		self.push(PatchOp::AddProject(project.into()))
+	}
+
+	/// Appends [`PatchOp::RemoveProject`].
+	pub fn remove_project<S: Into<String>>(self, project: S) -> Self {
+		self.push(PatchOp::RemoveProject(project.into()))
+	}
+
+	/// Appends [`PatchOp::AddContext`].
+	pub fn add_context<S: Into<String>>(self, context: S) -> Self {
+		self.push(PatchOp::AddContext(context.into()))
+	}
+
+	/// Appends [`PatchOp::RemoveContext`].
+	pub fn remove_context<S: Into<String>>(self, context: S) -> Self {
+		self.push(PatchOp::RemoveContext(context.into()))
+	}
+
+	/// Appends [`PatchOp::SetTag`].
+	pub fn set_tag<K, V>(self, key: K, value: V) -> Self
+	where
+		K: Into<String>,
+		V: Into<String>,
+	{
+		self.push(PatchOp::SetTag { key: key.into(), value: value.into() })
+	}
+
+	/// Appends [`PatchOp::AddTag`].
+	pub fn add_tag<K, V>(self, key: K, value: V) -> Self
+	where
+		K: Into<String>,
+		V: Into<String>,
+	{
+		self.push(PatchOp::AddTag { key: key.into(), value: value.into() })
+	}
+
+	/// Appends [`PatchOp::RemoveTag`] which removes all tags with the key
+	/// `key`.
+	pub fn remove_tag<K: Into<String>>(self, key: K) -> Self {
+		self.push(PatchOp::RemoveTag { key: key.into(), value: None })
+	}
+
+	/// Appends [`PatchOp::SetTag`] which sets the due date (`due:` tag).
+	pub fn set_due(self, due: Date) -> Self {
+		self.set_tag(DUE_TAG, due.to_string())
+	}
+
+	/// Returns the operations of the patch.
+	pub fn ops(&self) -> &[PatchOp] {
+		&self.ops
+	}
+
+	/// Returns `true` if the patch contains no operations.
+	pub const fn is_empty(&self) -> bool {
+		self.ops.is_empty()
+	}
+
+	/// Applies all operations in order to a copy of `task` and returns it.
+	///
+	/// # Errors
+	///
+	/// Returns an error if an operation adds an invalid project, context or
+	/// tag, e.g. one containing whitespace.
+	pub fn apply(&self, task: &Task) -> Result<Task, BuildDescriptionError> {
+		let mut task = task.clone();
+
+		for op in &self.ops {
+			op.apply(&mut task)?;
+		}
+
+		Ok(task)
+	}
+
+	/// Applies all operations in order to `task`.
+	///
+	/// # Errors
+	///
+	/// See [`Self::apply`]. The task is left untouched if an operation is
+	/// invalid.
+	pub fn apply_in_place(
+		&self,
+		task: &mut Task,
+	) -> Result<(), BuildDescriptionError> {
+		*task = self.apply(task)?;
+
+		Ok(())
+	}
+}
+
+impl From<Vec<PatchOp>> for TaskPatch {
+	fn from(ops: Vec<PatchOp>) -> Self {
+		Self { ops }
+	}
+}
+
+impl FromIterator<PatchOp> for TaskPatch {
+	fn from_iter<I: IntoIterator<Item = PatchOp>>(iter: I) -> Self {
+		Self { ops: iter.into_iter().collect() }
+	}
+}
@@ -1,8 +1,17 @@
 use std::cmp::Ordering;
+use std::collections::BTreeMap;
 use std::convert::TryFrom;
 
 use crate::parse::{Parse, Parser};
 
+/// The key of the custom tag holding the priority of a completed task (e.g.
+/// `pri:A`).
+///
+/// As completed tasks usually do not keep their priority, some applications
+/// move it into this tag (see
+/// [`CompletionPriority::Tag`](`crate::CompletionPriority::Tag`)).
+pub const PRIORITY_TAG: &str = "pri";
+
 macro_rules! priorities {
 	(
 		$(
@@ -20,6 +29,7 @@ macro_rules! priorities {
 		#[repr(u8)]
 		#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 		#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+		#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 		pub enum Priority {
 			$(
 				$( #[doc = $doc] )*
@@ -28,6 +38,9 @@ macro_rules! priorities {
 		}
 
 		impl Priority {
+			/// All priorities, from the highest to the lowest.
+			const ALL: &'static [Self] = &[ $( Self::$name ),+ ];
+
 			/// Returns the representing character for the priority.
 			pub const fn as_char(&self) -> char {
 				match self {
@@ -125,15 +138,99 @@ priorities! {
 
 impl PartialOrd<Self> for Priority {
 	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-		Some(std::cmp::Ord::cmp(self, other))
+		Some(Ord::cmp(self, other))
 	}
 }
 
 impl Ord for Priority {
 	fn cmp(&self, other: &Self) -> Ordering {
 		// Switched (other with self) so that `0` is the highest priority
-		std::cmp::Ord::cmp(&(*other as u8), &(*self as u8))
+		Ord::cmp(&(*other as u8), &(*self as u8))
+	}
+}
+
+impl Priority {
+	/// Returns the priority `step` away from this one, saturating at
+	/// [`Priority::A`] and [`Priority::Z`].
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::{Priority, PriorityStep};
+	///
+	/// assert_eq!(Priority::B.step(PriorityStep::Higher), Priority::A);
+	/// assert_eq!(Priority::A.step(PriorityStep::Higher), Priority::A);
+	/// assert_eq!(Priority::Y.step(PriorityStep::Lower), Priority::Z);
+	/// assert_eq!(Priority::Z.step(PriorityStep::Lower), Priority::Z);
+	/// ```
+	pub const fn step(self, step: PriorityStep) -> Self {
+		let code = match step {
+			PriorityStep::Higher => (self as u8).saturating_sub(1),
+			PriorityStep::Lower if (self as u8) < (Self::Z as u8) => {
+				self as u8 + 1
+			}
+			PriorityStep::Lower => Self::Z as u8,
+		};
+
+		Self::ALL[code as usize]
 	}
+
+	/// Returns the next higher priority, saturating at [`Priority::A`].
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::Priority;
+	///
+	/// assert_eq!(Priority::C.higher(), Priority::B);
+	/// assert_eq!(Priority::A.higher(), Priority::A);
+	/// ```
+	pub const fn higher(self) -> Self {
+		self.step(PriorityStep::Higher)
+	}
+
+	/// Returns the next lower priority, saturating at [`Priority::Z`].
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::Priority;
+	///
+	/// assert_eq!(Priority::C.lower(), Priority::D);
+	/// assert_eq!(Priority::Z.lower(), Priority::Z);
+	/// ```
+	pub const fn lower(self) -> Self {
+		self.step(PriorityStep::Lower)
+	}
+
+	/// Returns an iterator over all priorities, from the highest
+	/// ([`Priority::A`]) to the lowest ([`Priority::Z`]).
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::Priority;
+	///
+	/// let all = Priority::all().collect::<Vec<_>>();
+	///
+	/// assert_eq!(all.len(), 26);
+	/// assert_eq!(all.first(), Some(&Priority::A));
+	/// assert_eq!(all.last(), Some(&Priority::Z));
+	/// ```
+	pub fn all() -> impl DoubleEndedIterator<Item = Self> + ExactSizeIterator {
+		Self::ALL.iter().copied()
+	}
+}
+
+/// The direction in which a [`Priority`] is changed by [`Priority::step`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PriorityStep {
+	/// Towards [`Priority::A`].
+	Higher,
+
+	/// Towards [`Priority::Z`].
+	Lower,
 }
 
 crate::parse_error!(ParsePriorityError: "priority");
@@ -156,3 +253,162 @@ impl Parse for Priority {
 }
 
 crate::impl_fromstr!(Priority);
+crate::impl_parse_prefix!(Priority);
+
+/// Describes how a single [`Priority`] should be presented to a user.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PriorityAppearance {
+	/// Human readable label, e.g. `A` or `urgent`.
+	pub label: String,
+
+	/// Optional symbol (e.g. an emoji) displayed alongside the label.
+	#[cfg_attr(
+		feature = "serde",
+		serde(skip_serializing_if = "Option::is_none", default)
+	)]
+	pub symbol: Option<String>,
+
+	/// Optional color as an ANSI 256-color palette index.
+	#[cfg_attr(
+		feature = "serde",
+		serde(skip_serializing_if = "Option::is_none", default)
+	)]
+	pub color: Option<u8>,
+}
+
+impl PriorityAppearance {
+	/// Creates a new appearance with only a label.
+	pub fn new<S>(label: S) -> Self
+	where
+		S: Into<String>,
+	{
+		Self { label: label.into(), symbol: None, color: None }
+	}
+
+	/// Sets the symbol of the appearance.
+	pub fn with_symbol<S>(mut self, symbol: S) -> Self
+	where
+		S: Into<String>,
+	{
+		self.symbol = Some(symbol.into());
+		self
+	}
+
+	/// Sets the color (ANSI 256-color palette index) of the appearance.
+	pub const fn with_color(mut self, color: u8) -> Self {
+		self.color = Some(color);
+		self
+	}
+}
+
+/// A map from each [`Priority`] to its [`PriorityAppearance`].
+///
+/// This allows applications to share one concept of how priorities look
+/// instead of hard coding labels or escape codes.
+///
+/// # Defaults
+///
+/// Every priority is labeled with its character. Additionally [`Priority::A`]
+/// is red (`1`), [`Priority::B`] is yellow (`3`) and [`Priority::C`] is green
+/// (`2`), each with a matching colored circle as symbol.
+///
+/// # Examples
+///
+/// ```rust
+/// use tdtxt::{Priority, PriorityAppearance, PriorityStyle};
+///
+/// let mut style = PriorityStyle::default();
+/// assert_eq!(style.label(Priority::A), "A");
+/// assert_eq!(style.color(Priority::A), Some(1));
+///
+/// style.set(Priority::A, PriorityAppearance::new("urgent").with_color(9));
+/// assert_eq!(style.label(Priority::A), "urgent");
+/// assert_eq!(style.color(Priority::A), Some(9));
+/// assert_eq!(style.symbol(Priority::A), None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(transparent)
+)]
+pub struct PriorityStyle {
+	appearances: BTreeMap<Priority, PriorityAppearance>,
+}
+
+impl PriorityStyle {
+	/// Creates a new style without any appearances.
+	pub const fn empty() -> Self {
+		Self { appearances: BTreeMap::new() }
+	}
+
+	/// Returns the appearance for `priority` if one is set.
+	pub fn get(&self, priority: Priority) -> Option<&PriorityAppearance> {
+		self.appearances.get(&priority)
+	}
+
+	/// Sets the appearance for `priority` and returns the previous one.
+	pub fn set(
+		&mut self,
+		priority: Priority,
+		appearance: PriorityAppearance,
+	) -> Option<PriorityAppearance> {
+		self.appearances.insert(priority, appearance)
+	}
+
+	/// Removes the appearance for `priority` and returns it.
+	pub fn remove(
+		&mut self,
+		priority: Priority,
+	) -> Option<PriorityAppearance> {
+		self.appearances.remove(&priority)
+	}
+
+	/// Returns the label for `priority`.
+	///
+	/// Falls back to the character of the priority if no appearance is set.
+	pub fn label(&self, priority: Priority) -> std::borrow::Cow<'_, str> {
+		match self.get(priority) {
+			Some(appearance) => appearance.label.as_str().into(),
+			None => priority.as_char().to_string().into(),
+		}
+	}
+
+	/// Returns the symbol for `priority` if one is set.
+	pub fn symbol(&self, priority: Priority) -> Option<&str> {
+		self.get(priority)?.symbol.as_deref()
+	}
+
+	/// Returns the color for `priority` if one is set.
+	pub fn color(&self, priority: Priority) -> Option<u8> {
+		self.get(priority)?.color
+	}
+}
+
+impl Default for PriorityStyle {
+	fn default() -> Self {
+		let mut style = Self::empty();
+
+		for priority in Priority::all() {
+			let appearance = PriorityAppearance::new(priority.as_char());
+
+			let appearance = match priority {
+				Priority::A => {
+					appearance.with_symbol("\u{1F534}").with_color(1)
+				}
+				Priority::B => {
+					appearance.with_symbol("\u{1F7E1}").with_color(3)
+				}
+				Priority::C => {
+					appearance.with_symbol("\u{1F7E2}").with_color(2)
+				}
+				_ => appearance,
+			};
+
+			let _ = style.set(priority, appearance);
+		}
+
+		style
+	}
+}
@@ -0,0 +1,136 @@
+use std::fmt;
+
+/// The separator between the levels of a hierarchical project name.
+pub const PROJECT_SEPARATOR: char = '/';
+
+/// A hierarchical project name, e.g. `Work/Reports/Q3` for the project
+/// `+Work/Reports/Q3`.
+///
+/// Nesting projects by separating their levels with a `/` is a widespread
+/// convention. The format itself does not know about it, so a nested
+/// project is a regular project for all other parts of the crate.
+///
+/// # Examples
+///
+/// ```rust
+/// use tdtxt::ProjectPath;
+///
+/// let path = ProjectPath::new("+Work/Reports/Q3");
+///
+/// assert_eq!(path.as_str(), "Work/Reports/Q3");
+/// assert_eq!(path.levels().collect::<Vec<_>>(), ["Work", "Reports", "Q3"]);
+/// assert_eq!(path.name(), "Q3");
+/// assert_eq!(path.depth(), 3);
+///
+/// let parent = path.parent().unwrap();
+/// assert_eq!(parent.as_str(), "Work/Reports");
+/// assert!(parent.is_ancestor_of(&path));
+/// assert_eq!(parent.child("Q4").as_str(), "Work/Reports/Q4");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct ProjectPath {
+	path: String,
+}
+
+impl ProjectPath {
+	/// Creates a path from the project `project` (with or without the
+	/// leading `+`).
+	pub fn new<S>(project: S) -> Self
+	where
+		S: AsRef<str>,
+	{
+		let project = project.as_ref();
+		let project = project.strip_prefix('+').unwrap_or(project);
+
+		Self { path: project.to_owned() }
+	}
+
+	/// Returns the whole project name (without the leading `+`).
+	pub fn as_str(&self) -> &str {
+		&self.path
+	}
+
+	/// Returns an iterator over all levels of the path, starting with the
+	/// top level.
+	pub fn levels(&self) -> impl Iterator<Item = &str> + '_ {
+		self.path.split(PROJECT_SEPARATOR)
+	}
+
+	/// Returns the number of levels of the path.
+	pub fn depth(&self) -> usize {
+		self.levels().count()
+	}
+
+	/// Returns the last level of the path, e.g. `Q3` for `Work/Reports/Q3`.
+	pub fn name(&self) -> &str {
+		self.path
+			.rsplit(PROJECT_SEPARATOR)
+			.next()
+			.expect("split yields at least one item")
+	}
+
+	/// Returns the path without its last level, or `None` for a top level
+	/// project.
+	pub fn parent(&self) -> Option<Self> {
+		self.path
+			.rfind(PROJECT_SEPARATOR)
+			.map(|idx| Self { path: self.path[..idx].to_owned() })
+	}
+
+	/// Returns an iterator over all ancestors of the path, starting with the
+	/// direct parent.
+	pub fn ancestors(&self) -> impl Iterator<Item = Self> {
+		std::iter::successors(self.parent(), Self::parent)
+	}
+
+	/// Returns the path extended by the level `name`.
+	pub fn child(&self, name: &str) -> Self {
+		Self { path: format!("{}{}{}", self.path, PROJECT_SEPARATOR, name) }
+	}
+
+	/// Returns `true` if `other` is nested (at any depth) below the path.
+	pub fn is_ancestor_of(&self, other: &Self) -> bool {
+		other.path.len() > self.path.len() && is_under(&other.path, &self.path)
+	}
+
+	/// Returns `true` if the project `project` (with or without the leading
+	/// `+`) is the path itself or is nested below it.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::ProjectPath;
+	///
+	/// let work = ProjectPath::new("Work");
+	///
+	/// assert!(work.contains("+Work"));
+	/// assert!(work.contains("Work/Reports"));
+	/// assert!(!work.contains("Workshop"));
+	/// ```
+	pub fn contains(&self, project: &str) -> bool {
+		let project = project.strip_prefix('+').unwrap_or(project);
+
+		is_under(project, &self.path)
+	}
+}
+
+impl fmt::Display for ProjectPath {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(&self.path)
+	}
+}
+
+impl From<&str> for ProjectPath {
+	fn from(project: &str) -> Self {
+		Self::new(project)
+	}
+}
+
+/// Returns `true` if `project` is `ancestor` or nested below it.
+pub fn is_under(project: &str, ancestor: &str) -> bool {
+	project.strip_prefix(ancestor).is_some_and(|rest| {
+		rest.is_empty() || rest.starts_with(PROJECT_SEPARATOR)
+	})
+}
@@ -0,0 +1,179 @@
+use std::convert::TryFrom;
+use std::fmt;
+
+use crate::date::{Date, Days};
+use crate::parse::{Parse, Parser};
+
+/// The unit of a [`Recurrence`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum RecurrenceUnit {
+	/// Days (representation: `d`).
+	Day,
+
+	/// Weeks (representation: `w`).
+	Week,
+
+	/// Months (representation: `m`).
+	Month,
+
+	/// Years (representation: `y`).
+	Year,
+}
+
+impl RecurrenceUnit {
+	/// Returns the character representing the unit.
+	pub const fn as_char(self) -> char {
+		match self {
+			Self::Day => 'd',
+			Self::Week => 'w',
+			Self::Month => 'm',
+			Self::Year => 'y',
+		}
+	}
+}
+
+/// The interval in which a task recurs, e.g. `3m` for every three months.
+///
+/// It is read from the [`RECURRENCE_TAG`](`crate::RECURRENCE_TAG`) of a task
+/// (e.g. `rec:2w`). See [`Task::next_occurrence`](`crate::Task::next_occurrence`)
+/// for how the follow-up task is created.
+///
+/// # Strict and relative recurrence
+///
+/// Like in other todo.txt clients (e.g. Simpletask or topydo) a recurrence
+/// is either:
+///
+/// - relative (e.g. `rec:1w`): the next occurrence is based on the date the
+///   task was completed, or
+/// - strict (e.g. `rec:+1w`): the next occurrence is based on the previous
+///   due date, regardless of when the task was completed.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::str::FromStr;
+///
+/// use tdtxt::{Date, Recurrence, RecurrenceUnit};
+///
+/// let recurrence = Recurrence::from_str("2w").unwrap();
+///
+/// assert_eq!(recurrence, Recurrence::new(2, RecurrenceUnit::Week));
+/// assert_eq!(
+///     recurrence.apply(Date::from_ymd(2021, 12, 25)),
+///     Some(Date::from_ymd(2022, 1, 8))
+/// );
+///
+/// let recurrence = Recurrence::from_str("+1m").unwrap();
+///
+/// assert!(recurrence.strict);
+/// assert_eq!(recurrence.to_string(), "+1m");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct Recurrence {
+	/// The number of units between two occurrences.
+	pub amount: u32,
+
+	/// The unit of [`Self::amount`].
+	pub unit: RecurrenceUnit,
+
+	/// Whether the recurrence is strict (`+` prefix) or relative.
+	pub strict: bool,
+}
+
+impl Recurrence {
+	/// Creates a new relative recurrence.
+	pub const fn new(amount: u32, unit: RecurrenceUnit) -> Self {
+		Self { amount, unit, strict: false }
+	}
+
+	/// Sets whether the recurrence is strict.
+	pub const fn strict(mut self, strict: bool) -> Self {
+		self.strict = strict;
+		self
+	}
+
+	/// Returns `date` advanced by one interval. Returns `None` if the
+	/// resulting date would be out of range.
+	///
+	/// Months and years are added by calendar (see
+	/// [`Date::checked_add_months`]).
+	pub fn apply(self, date: Date) -> Option<Date> {
+		let amount = i64::from(self.amount);
+
+		match self.unit {
+			RecurrenceUnit::Day => date.checked_add_days(Days(amount)),
+			RecurrenceUnit::Week => date.checked_add_days(Days(amount * 7)),
+			RecurrenceUnit::Month => {
+				date.checked_add_months(i32::try_from(amount).ok()?)
+			}
+			RecurrenceUnit::Year => date.checked_add_months(
+				i32::try_from(amount.checked_mul(12)?).ok()?,
+			),
+		}
+	}
+}
+
+impl fmt::Display for Recurrence {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if self.strict {
+			f.write_str("+")?;
+		}
+
+		write!(f, "{}{}", self.amount, self.unit.as_char())
+	}
+}
+
+crate::parse_error!(ParseRecurrenceError: "recurrence");
+
+impl Parse for Recurrence {
+	type Error = ParseRecurrenceError;
+
+	fn parse(parser: &mut Parser<'_>) -> Result<Self, Self::Error> {
+		let strict = parser.expect_u8(b'+').is_some();
+		let mut amount: Option<u32> = None;
+
+		while let Some(digit) = parser.parse_digit() {
+			amount = Some(
+				amount
+					.unwrap_or_default()
+					.checked_mul(10)
+					.and_then(|amount| amount.checked_add(u32::from(digit)))
+					.ok_or_else(|| {
+						ParseRecurrenceError::with_msg("amount too large")
+					})?,
+			);
+		}
+
+		let amount = match amount {
+			Some(0) => {
+				return Err(ParseRecurrenceError::with_msg(
+					"amount must not be zero",
+				))
+			}
+			Some(amount) => amount,
+			None => {
+				return Err(ParseRecurrenceError::with_msg("missing amount"))
+			}
+		};
+
+		let unit = match parser.parse_alpha_lower() {
+			Some('d') => RecurrenceUnit::Day,
+			Some('w') => RecurrenceUnit::Week,
+			Some('m') => RecurrenceUnit::Month,
+			Some('y') => RecurrenceUnit::Year,
+			_ => {
+				return Err(ParseRecurrenceError::with_msg(
+					"expected one of the units `d`, `w`, `m` or `y`",
+				))
+			}
+		};
+
+		Ok(Self { amount, unit, strict })
+	}
+}
+
+crate::impl_fromstr!(Recurrence);
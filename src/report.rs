@@ -0,0 +1,121 @@
+use std::str::FromStr;
+
+use crate::date::Date;
+use crate::file::{Entry, TodoFile};
+use crate::state::State;
+use crate::task::Task;
+
+/// Statistics collected while parsing a [`TodoFile`].
+///
+/// This is returned by [`TodoFile::parse_with_report`] and can be used to
+/// warn users about unusual files.
+///
+/// # Hints
+///
+/// Some lines are valid tasks but look like they were meant to be written
+/// differently, e.g. `(a) Call Mom` is parsed as a task without priority and
+/// the description `(a) Call Mom`. Such lines are counted in the `*_hints`
+/// fields.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParseReport {
+	/// Number of lines.
+	pub lines: usize,
+
+	/// Number of lines which are tasks.
+	pub tasks: usize,
+
+	/// Number of blank lines.
+	pub blank: usize,
+
+	/// Number of comment lines (starting with `#`).
+	pub comments: usize,
+
+	/// Number of done tasks.
+	pub done: usize,
+
+	/// Number of tasks with a priority.
+	pub with_priority: usize,
+
+	/// Number of tasks with a creation date.
+	pub with_dates: usize,
+
+	/// Number of tasks with at least one project.
+	pub with_projects: usize,
+
+	/// Number of tasks with at least one context.
+	pub with_contexts: usize,
+
+	/// Number of tasks with at least one custom tag.
+	pub with_custom: usize,
+
+	/// Number of tasks without priority whose description starts with a
+	/// lowercase priority (e.g. `(a) Call Mom`).
+	pub lowercase_priority_hints: usize,
+
+	/// Number of tasks without dates whose description starts with a date in
+	/// an alternative format (see [`Date::parse_lenient`]).
+	pub lenient_date_hints: usize,
+}
+
+impl ParseReport {
+	/// Collects the statistics of `todo`.
+	pub(crate) fn collect(todo: &TodoFile) -> Self {
+		let mut report = Self::default();
+
+		for entry in todo.entries() {
+			report.lines += 1;
+
+			match entry {
+				Entry::Task(task) => report.add_task(task),
+				Entry::Opaque(line) if line.trim().is_empty() => {
+					report.blank += 1;
+				}
+				Entry::Opaque(_) => report.comments += 1,
+			}
+		}
+
+		report
+	}
+
+	fn add_task(&mut self, task: &Task) {
+		fn count(counter: &mut usize, condition: bool) {
+			*counter += usize::from(condition);
+		}
+
+		let description = &task.description;
+		let text = description.description();
+
+		self.tasks += 1;
+		count(&mut self.done, task.state == State::Done);
+		count(&mut self.with_priority, task.priority.is_some());
+		count(&mut self.with_dates, task.date_compound.is_some());
+		count(
+			&mut self.with_projects,
+			description.projects().next().is_some(),
+		);
+		count(
+			&mut self.with_contexts,
+			description.contexts().next().is_some(),
+		);
+		count(&mut self.with_custom, description.custom().next().is_some());
+
+		count(
+			&mut self.lowercase_priority_hints,
+			task.priority.is_none()
+				&& matches!(
+					text.as_bytes(),
+					[b'(', b'a'..=b'z', b')', b' ', ..]
+				),
+		);
+
+		count(
+			&mut self.lenient_date_hints,
+			task.date_compound.is_none()
+				&& text.split_whitespace().next().is_some_and(|word| {
+					Date::from_str(word).is_err()
+						&& Date::parse_lenient(word).is_some()
+				}),
+		);
+	}
+}
@@ -0,0 +1,103 @@
+//! Serde helpers which (de)serialize a [`Date`] with the serde
+//! implementation of [`chrono::NaiveDate`].
+//!
+//! By default a [`Date`] is always (de)serialized as `yyyy-mm-dd` string.
+//! These helpers can be used with `#[serde(with = "...")]` to stay
+//! compatible with existing schemas which were written for chrono dates.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use tdtxt::Date;
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Reminder {
+//!     #[serde(with = "tdtxt::serde_chrono")]
+//!     date: Date,
+//!
+//!     #[serde(with = "tdtxt::serde_chrono::option")]
+//!     snoozed: Option<Date>,
+//! }
+//!
+//! let reminder = Reminder { date: Date::from_ymd(2021, 8, 1), snoozed: None };
+//! let json = serde_json::to_string(&reminder).unwrap();
+//!
+//! assert_eq!(json, r#"{"date":"2021-08-01","snoozed":null}"#);
+//! assert_eq!(serde_json::from_str::<Reminder>(&json).unwrap(), reminder);
+//! ```
+
+use std::convert::TryFrom;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::date::Date;
+
+/// Serializes `date` as [`chrono::NaiveDate`].
+///
+/// # Errors
+///
+/// Returns the errors of the serializer.
+pub fn serialize<S>(date: &Date, serializer: S) -> Result<S::Ok, S::Error>
+where
+	S: Serializer,
+{
+	chrono::NaiveDate::from(*date).serialize(serializer)
+}
+
+/// Deserializes a [`chrono::NaiveDate`] as date.
+///
+/// # Errors
+///
+/// Returns the errors of the deserializer or an error if the year of the
+/// date does not fit into an `i16` (see [`Date::from_ymd_opt`]).
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Date, D::Error>
+where
+	D: Deserializer<'de>,
+{
+	let date = chrono::NaiveDate::deserialize(deserializer)?;
+
+	i16::try_from(chrono::Datelike::year(&date))
+		.map(|_| Date::from(date))
+		.map_err(|_| serde::de::Error::custom("year out of range"))
+}
+
+/// Serde helpers for optional dates, see [the parent module](`super`).
+pub mod option {
+	use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+	use crate::date::Date;
+
+	/// Serializes `date` as optional [`chrono::NaiveDate`].
+	///
+	/// # Errors
+	///
+	/// Returns the errors of the serializer.
+	pub fn serialize<S>(
+		date: &Option<Date>,
+		serializer: S,
+	) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		date.map(chrono::NaiveDate::from).serialize(serializer)
+	}
+
+	/// Deserializes an optional [`chrono::NaiveDate`] as date.
+	///
+	/// # Errors
+	///
+	/// See [`super::deserialize`].
+	pub fn deserialize<'de, D>(
+		deserializer: D,
+	) -> Result<Option<Date>, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		#[derive(Deserialize)]
+		struct Wrapper(#[serde(with = "super")] Date);
+
+		Option::<Wrapper>::deserialize(deserializer)
+			.map(|date| date.map(|Wrapper(date)| date))
+	}
+}
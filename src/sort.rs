@@ -0,0 +1,226 @@
+use std::cmp::{Ordering, Reverse};
+
+use crate::task::Task;
+
+/// A key by which [`Task`]s can be sorted.
+///
+/// Multiple keys can be combined with [`SortKey::compare_all`] or
+/// [`TodoFile::sort_by_keys`](`crate::TodoFile::sort_by_keys`), in which case
+/// later keys are only used to break ties of earlier ones.
+///
+/// # Missing values
+///
+/// Tasks without a value for a key (e.g. no priority or no due date) are
+/// always ordered after tasks with a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SortKey {
+	/// Orders by priority, from the highest (`A`) to the lowest (`Z`).
+	///
+	/// The priority of done tasks is taken from their `pri:` tag if they
+	/// have none (see [`Task::effective_priority`]).
+	Priority,
+
+	/// Orders by creation date, from the oldest to the newest.
+	CreatedDate,
+
+	/// Orders by completion date, from the oldest to the newest.
+	CompletedDate,
+
+	/// Orders by the due date (`due:` tag), from the earliest to the latest.
+	///
+	/// If a task has multiple due dates the earliest one is used. Values
+	/// which are not valid dates are ignored.
+	DueDate,
+
+	/// Orders open tasks before done tasks.
+	State,
+
+	/// Orders by the description text, ignoring case.
+	Alphabetical,
+}
+
+impl SortKey {
+	/// Compares two tasks by this key.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::cmp::Ordering;
+	/// use std::str::FromStr;
+	///
+	/// use tdtxt::{SortKey, Task};
+	///
+	/// let a = Task::from_str("(B) Call Mom").unwrap();
+	/// let b = Task::from_str("Buy milk").unwrap();
+	///
+	/// assert_eq!(SortKey::Priority.compare(&a, &b), Ordering::Less);
+	/// assert_eq!(SortKey::Alphabetical.compare(&a, &b), Ordering::Greater);
+	/// ```
+	pub fn compare(&self, a: &Task, b: &Task) -> Ordering {
+		match self {
+			// The highest priority is the greatest
+			Self::Priority => compare_opt(
+				a.effective_priority().map(Reverse),
+				b.effective_priority().map(Reverse),
+			),
+			Self::CreatedDate => compare_opt(
+				a.date_compound.map(|dc| *dc.date_created()),
+				b.date_compound.map(|dc| *dc.date_created()),
+			),
+			Self::CompletedDate => compare_opt(
+				a.date_compound.and_then(|dc| dc.date_completed().copied()),
+				b.date_compound.and_then(|dc| dc.date_completed().copied()),
+			),
+			Self::DueDate => compare_opt(a.due_date(), b.due_date()),
+			Self::State => a.state.cmp(&b.state),
+			Self::Alphabetical => {
+				let a = a.description.description();
+				let b = b.description.description();
+
+				a.chars()
+					.flat_map(char::to_lowercase)
+					.cmp(b.chars().flat_map(char::to_lowercase))
+					.then_with(|| a.cmp(b))
+			}
+		}
+	}
+
+	/// Compares two tasks by all `keys` in order.
+	///
+	/// Returns [`Ordering::Equal`] if `keys` is empty.
+	pub fn compare_all(keys: &[Self], a: &Task, b: &Task) -> Ordering {
+		keys.iter()
+			.map(|key| key.compare(a, b))
+			.find(|ordering| ordering.is_ne())
+			.unwrap_or(Ordering::Equal)
+	}
+}
+
+/// Compares two optional values, ordering `None` last.
+fn compare_opt<T: Ord>(a: Option<T>, b: Option<T>) -> Ordering {
+	match (a, b) {
+		(Some(a), Some(b)) => a.cmp(&b),
+		(Some(_), None) => Ordering::Less,
+		(None, Some(_)) => Ordering::Greater,
+		(None, None) => Ordering::Equal,
+	}
+}
+
+/// A custom ordering of [`Task`]s by multiple [`SortKey`]s.
+///
+/// Later keys are only used to break ties of earlier ones. The default
+/// ordering is the canonical ordering used by the [`Ord`] implementation of
+/// [`Task`] (see [`TaskOrder::CANONICAL_KEYS`]).
+///
+/// # Examples
+///
+/// ```rust
+/// use std::str::FromStr;
+///
+/// use tdtxt::{SortKey, Task, TaskOrder};
+///
+/// let mut tasks = [
+///     "(B) Call Mom due:2021-08-01",
+///     "(A) Pay rent due:2021-08-03",
+///     "Buy milk due:2021-07-31",
+/// ]
+/// .iter()
+/// .map(|line| Task::from_str(line).unwrap())
+/// .collect::<Vec<_>>();
+///
+/// TaskOrder::new([SortKey::DueDate]).sort(&mut tasks);
+/// assert_eq!(tasks[0].to_string(), "Buy milk due:2021-07-31");
+///
+/// tasks.sort();
+/// assert_eq!(tasks[0].to_string(), "(A) Pay rent due:2021-08-03");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TaskOrder {
+	keys: Vec<SortKey>,
+}
+
+impl TaskOrder {
+	/// The keys of the canonical ordering of tasks.
+	///
+	/// Tasks are ordered by their state (open first), priority (highest
+	/// first), creation date (oldest first) and description (alphabetical).
+	/// Ties are broken by the completion date.
+	pub const CANONICAL_KEYS: &'static [SortKey] = &[
+		SortKey::State,
+		SortKey::Priority,
+		SortKey::CreatedDate,
+		SortKey::Alphabetical,
+		SortKey::CompletedDate,
+	];
+
+	/// Creates an ordering by `keys`.
+	pub fn new<I>(keys: I) -> Self
+	where
+		I: IntoIterator<Item = SortKey>,
+	{
+		Self { keys: keys.into_iter().collect() }
+	}
+
+	/// Creates the canonical ordering (see [`TaskOrder::CANONICAL_KEYS`]).
+	pub fn canonical() -> Self {
+		Self::new(Self::CANONICAL_KEYS.iter().copied())
+	}
+
+	/// Appends `key`, which is used to break ties of all previous keys.
+	pub fn then(mut self, key: SortKey) -> Self {
+		self.keys.push(key);
+		self
+	}
+
+	/// Returns the keys of the ordering.
+	pub fn keys(&self) -> &[SortKey] {
+		&self.keys
+	}
+
+	/// Compares two tasks by this ordering.
+	pub fn compare(&self, a: &Task, b: &Task) -> Ordering {
+		SortKey::compare_all(&self.keys, a, b)
+	}
+
+	/// Sorts `tasks` by this ordering.
+	///
+	/// The sort is stable.
+	pub fn sort(&self, tasks: &mut [Task]) {
+		tasks.sort_by(|a, b| self.compare(a, b));
+	}
+}
+
+impl Default for TaskOrder {
+	fn default() -> Self {
+		Self::canonical()
+	}
+}
+
+impl PartialOrd for Task {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+/// Orders tasks canonically (see [`TaskOrder::CANONICAL_KEYS`]).
+///
+/// Remaining ties are broken by the priority of the task itself (which can
+/// differ from the effective priority used by [`SortKey::Priority`]) and the
+/// options of the description, so that the ordering is consistent with the
+/// equality of tasks.
+impl Ord for Task {
+	fn cmp(&self, other: &Self) -> Ordering {
+		SortKey::compare_all(TaskOrder::CANONICAL_KEYS, self, other)
+			.then_with(|| {
+				compare_opt(
+					self.priority.map(Reverse),
+					other.priority.map(Reverse),
+				)
+			})
+			.then_with(|| {
+				self.description.options().cmp(&other.description.options())
+			})
+	}
+}
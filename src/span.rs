@@ -1,35 +1,142 @@
+use std::convert::{TryFrom, TryInto};
 use std::fmt;
-use std::ops::{Bound, Deref, DerefMut, Index, Range, RangeBounds};
+use std::num::TryFromIntError;
+use std::ops::{Bound, Deref, Index, Range, RangeBounds};
 
 type PosWidth = u32;
 
+/// A byte offset into a text, e.g. the text of a
+/// [`Description`](`crate::Description`).
 #[derive(
 	Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash,
 )]
 pub struct BytePos(PosWidth);
 
 impl BytePos {
+	/// The largest representable offset.
 	pub const MAX: Self = Self(PosWidth::MAX);
+
+	/// The smallest representable offset (`0`).
 	pub const MIN: Self = Self(PosWidth::MIN);
 
+	/// Creates an offset from a `u32`.
 	pub const fn from_u32(value: u32) -> Self {
 		Self(value)
 	}
 
-	pub const fn from_usize(value: usize) -> Self {
-		Self(value as u32)
+	/// Creates an offset from a `usize`.
+	///
+	/// # Panics
+	///
+	/// Panics if `value` does not fit into a `u32`, see
+	/// [`BytePos::try_from`] for a fallible conversion.
+	pub(crate) const fn from_usize(value: usize) -> Self {
+		assert!(value <= PosWidth::MAX as usize, "offset exceeds u32::MAX");
+		Self(value as PosWidth)
 	}
 
+	/// Returns the offset as `u32`.
 	pub const fn as_u32(&self) -> u32 {
 		self.0
 	}
 
+	/// Returns the offset as `usize`.
 	pub const fn as_usize(&self) -> usize {
 		self.0 as usize
 	}
 
-	pub const fn offset(&self, offset: i32) -> Self {
-		Self((self.0 as i32 + offset) as PosWidth)
+	/// Returns the offset moved by `offset` bytes, or `None` if the result
+	/// is negative or exceeds [`BytePos::MAX`].
+	pub const fn checked_offset(&self, offset: i32) -> Option<Self> {
+		match self.0.checked_add_signed(offset) {
+			Some(value) => Some(Self(value)),
+			None => None,
+		}
+	}
+
+	/// Returns the text before the offset, or `None` if the offset is out
+	/// of bounds or not at a character boundary of `text`.
+	fn prefix<'a>(&self, text: &'a str) -> Option<&'a str> {
+		text.get(..self.as_usize())
+	}
+
+	/// Returns the offset in characters (unicode scalar values) within
+	/// `text`.
+	///
+	/// Returns `None` if the offset is out of bounds or not at a character
+	/// boundary of `text`.
+	pub fn to_char_offset(&self, text: &str) -> Option<usize> {
+		self.prefix(text).map(|prefix| prefix.chars().count())
+	}
+
+	/// Returns the offset in UTF-16 code units within `text`, e.g. for
+	/// editors and the language server protocol.
+	///
+	/// Returns `None` if the offset is out of bounds or not at a character
+	/// boundary of `text`.
+	pub fn to_utf16_offset(&self, text: &str) -> Option<usize> {
+		self.prefix(text).map(|prefix| prefix.encode_utf16().count())
+	}
+
+	/// Creates a byte offset from an offset in UTF-16 code units within
+	/// `text`.
+	///
+	/// Returns `None` if the offset is out of bounds or points into the
+	/// middle of a surrogate pair.
+	pub fn from_utf16_offset(text: &str, offset: usize) -> Option<Self> {
+		let mut utf16 = 0;
+
+		for (idx, c) in text.char_indices() {
+			if utf16 == offset {
+				return Self::try_from(idx).ok();
+			} else if utf16 > offset {
+				return None;
+			}
+
+			utf16 += c.len_utf16();
+		}
+
+		if utf16 == offset {
+			Self::try_from(text.len()).ok()
+		} else {
+			None
+		}
+	}
+
+	/// Returns the line and column of the offset within `text`.
+	///
+	/// Returns `None` if the offset is out of bounds or not at a character
+	/// boundary of `text`.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::convert::TryFrom;
+	///
+	/// use tdtxt::{BytePos, LineCol};
+	///
+	/// let text = "Grüße +Familie\n(A) Call Mom @\u{1F4DE}";
+	/// let pos = BytePos::try_from(text.find('@').unwrap()).unwrap();
+	///
+	/// assert_eq!(
+	///     pos.to_line_col(text),
+	///     Some(LineCol { line: 1, column: 13, utf16_column: 13 })
+	/// );
+	///
+	/// let end = BytePos::try_from(text.len()).unwrap();
+	/// assert_eq!(end.to_line_col(text).map(|lc| lc.column), Some(15));
+	/// assert_eq!(end.to_line_col(text).map(|lc| lc.utf16_column), Some(16));
+	/// ```
+	pub fn to_line_col(&self, text: &str) -> Option<LineCol> {
+		let prefix = self.prefix(text)?;
+		let line_start = prefix.rfind('\n').map_or(0, |idx| idx + 1);
+		let column = &prefix[line_start..];
+
+		Some(LineCol {
+			line: prefix[..line_start].matches('\n').count(),
+			column: column.chars().count(),
+			utf16_column: column.encode_utf16().count(),
+		})
 	}
 }
 
@@ -45,9 +152,11 @@ impl From<u32> for BytePos {
 	}
 }
 
-impl From<usize> for BytePos {
-	fn from(value: usize) -> Self {
-		Self::from_usize(value)
+impl TryFrom<usize> for BytePos {
+	type Error = TryFromIntError;
+
+	fn try_from(value: usize) -> Result<Self, Self::Error> {
+		PosWidth::try_from(value).map(Self)
 	}
 }
 
@@ -71,20 +180,31 @@ impl Deref for BytePos {
 	}
 }
 
-impl DerefMut for BytePos {
-	fn deref_mut(&mut self) -> &mut Self::Target {
-		&mut self.0
-	}
-}
+/// A zero-based position within a text, e.g. of a [`BytePos`] (see
+/// [`BytePos::to_line_col`]).
+///
+/// Lines are separated by `\n`, columns are counted in characters (unicode
+/// scalar values) and in UTF-16 code units.
+#[derive(
+	Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+pub struct LineCol {
+	/// The line.
+	pub line: usize,
 
-impl Index<BytePos> for &str {
-	type Output = u8;
+	/// The column in characters.
+	pub column: usize,
 
-	fn index(&self, index: BytePos) -> &Self::Output {
-		&self.as_bytes()[usize::from(index)]
-	}
+	/// The column in UTF-16 code units, as used by the language server
+	/// protocol.
+	pub utf16_column: usize,
 }
 
+/// A half-open range of bytes (`low..high`) within a text, e.g. the text of
+/// a [`Description`](`crate::Description`).
+///
+/// A span can be used to index into the text it was created for (e.g.
+/// `&text[span]`, see [`ByteSpan::to_range_usize`]).
 #[derive(
 	Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash,
 )]
@@ -94,6 +214,8 @@ pub struct ByteSpan {
 }
 
 impl ByteSpan {
+	/// Creates a span from `low` to `high` (exclusive). The bounds are
+	/// swapped if `low` is greater than `high`.
 	pub fn new(mut low: BytePos, mut high: BytePos) -> Self {
 		if low > high {
 			std::mem::swap(&mut low, &mut high);
@@ -102,46 +224,87 @@ impl ByteSpan {
 		Self { low, high }
 	}
 
-	pub fn from_range<B, R>(range: R) -> Self
+	/// Creates a span from a range of offsets. Unbounded ends are mapped to
+	/// [`BytePos::MIN`] and [`BytePos::MAX`].
+	///
+	/// Returns `None` if a bound is not representable as [`BytePos`].
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::ByteSpan;
+	///
+	/// let span = ByteSpan::from_range(2_usize..=4).unwrap();
+	/// assert_eq!(span.to_range_usize(), 2..5);
+	///
+	/// assert!(ByteSpan::from_range(..=u32::MAX).is_none());
+	/// ```
+	pub fn from_range<B, R>(range: R) -> Option<Self>
 	where
-		B: Clone + Into<BytePos>,
+		B: Clone + TryInto<BytePos>,
 		R: RangeBounds<B>,
 	{
+		let pos = |bound: &B| bound.clone().try_into().ok();
+
 		let low = match range.start_bound() {
-			Bound::Included(included) => included.clone().into(),
-			Bound::Excluded(excluded) => excluded.clone().into().offset(1),
+			Bound::Included(included) => pos(included)?,
+			Bound::Excluded(excluded) => pos(excluded)?.checked_offset(1)?,
 			Bound::Unbounded => BytePos::MIN,
 		};
 
 		let high = match range.end_bound() {
-			Bound::Included(included) => included.clone().into().offset(-1),
-			Bound::Excluded(excluded) => excluded.clone().into(),
+			Bound::Included(included) => pos(included)?.checked_offset(1)?,
+			Bound::Excluded(excluded) => pos(excluded)?,
 			Bound::Unbounded => BytePos::MAX,
 		};
 
-		Self::new(low, high)
+		Some(Self::new(low, high))
+	}
+
+	/// Creates a span from a range of `usize` offsets.
+	///
+	/// # Panics
+	///
+	/// Panics if a bound does not fit into a `u32`.
+	pub(crate) fn from_usize_range(range: Range<usize>) -> Self {
+		Self::new(
+			BytePos::from_usize(range.start),
+			BytePos::from_usize(range.end),
+		)
 	}
 
+	/// Returns the span with the start replaced by `low`.
 	pub const fn with_low(&self, low: BytePos) -> Self {
 		Self { low, high: self.high }
 	}
 
+	/// Returns the span with the end replaced by `high`.
 	pub const fn with_high(&self, high: BytePos) -> Self {
 		Self { low: self.low, high }
 	}
 
-	pub const fn offset_low(&self, offset: i32) -> Self {
-		Self { low: self.low.offset(offset), high: self.high }
+	/// Returns the span with the start moved by `offset` bytes, or `None`
+	/// if the start is out of range (see [`BytePos::checked_offset`]).
+	pub fn checked_offset_low(&self, offset: i32) -> Option<Self> {
+		Some(Self::new(self.low.checked_offset(offset)?, self.high))
 	}
 
-	pub const fn offset_high(&self, offset: i32) -> Self {
-		Self { low: self.low, high: self.high.offset(offset) }
+	/// Returns the span with the end moved by `offset` bytes, or `None` if
+	/// the end is out of range (see [`BytePos::checked_offset`]).
+	pub fn checked_offset_high(&self, offset: i32) -> Option<Self> {
+		Some(Self::new(self.low, self.high.checked_offset(offset)?))
 	}
 
-	pub const fn offset(&self, offset: i32) -> Self {
-		Self { low: self.low.offset(offset), high: self.high.offset(offset) }
+	/// Returns the span with both bounds moved by `offset` bytes, or `None`
+	/// if a bound is out of range (see [`BytePos::checked_offset`]).
+	pub fn checked_offset(&self, offset: i32) -> Option<Self> {
+		Some(Self {
+			low: self.low.checked_offset(offset)?,
+			high: self.high.checked_offset(offset)?,
+		})
 	}
 
+	/// Returns the smallest span containing both spans.
 	pub fn union(&self, other: &Self) -> Self {
 		Self {
 			low: std::cmp::min(self.low, other.low),
@@ -149,21 +312,64 @@ impl ByteSpan {
 		}
 	}
 
+	/// Returns the number of bytes within the span.
 	pub const fn len(&self) -> usize {
 		self.high.as_usize() - self.low.as_usize()
 	}
 
+	/// Returns `true` if the span contains no bytes.
+	pub const fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Returns the start of the span (inclusive).
 	pub const fn low(&self) -> &BytePos {
 		&self.low
 	}
 
+	/// Returns the end of the span (exclusive).
 	pub const fn high(&self) -> &BytePos {
 		&self.high
 	}
 
+	/// Returns the span as range of `usize` offsets, e.g. for slicing.
 	pub fn to_range_usize(self) -> Range<usize> {
 		self.low.into()..self.high.into()
 	}
+
+	/// Returns the span as range of character offsets within `text` (see
+	/// [`BytePos::to_char_offset`]).
+	pub fn to_char_range(self, text: &str) -> Option<Range<usize>> {
+		Some(self.low.to_char_offset(text)?..self.high.to_char_offset(text)?)
+	}
+
+	/// Returns the span as range of UTF-16 offsets within `text` (see
+	/// [`BytePos::to_utf16_offset`]).
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::Description;
+	///
+	/// let description = Description::new("\u{1F4DE} Mom +Family");
+	/// let span = *description.project_ranges()[0].full();
+	///
+	/// assert_eq!(span.to_range_usize(), 9..16);
+	/// assert_eq!(span.to_char_range(description.description()), Some(6..13));
+	/// assert_eq!(span.to_utf16_range(description.description()), Some(7..14));
+	/// ```
+	pub fn to_utf16_range(self, text: &str) -> Option<Range<usize>> {
+		Some(
+			self.low.to_utf16_offset(text)?
+				..self.high.to_utf16_offset(text)?,
+		)
+	}
+
+	/// Returns the start and end of the span as lines and columns within
+	/// `text` (see [`BytePos::to_line_col`]).
+	pub fn to_line_cols(self, text: &str) -> Option<(LineCol, LineCol)> {
+		Some((self.low.to_line_col(text)?, self.high.to_line_col(text)?))
+	}
 }
 
 impl fmt::Display for ByteSpan {
@@ -174,7 +380,7 @@ impl fmt::Display for ByteSpan {
 
 impl RangeBounds<BytePos> for ByteSpan {
 	fn start_bound(&self) -> Bound<&BytePos> {
-		Bound::Included(&self.high)
+		Bound::Included(&self.low)
 	}
 
 	fn end_bound(&self) -> Bound<&BytePos> {
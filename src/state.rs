@@ -3,10 +3,14 @@ use std::fmt;
 use crate::parse::{Parse, Parser};
 
 /// Represents the state of [`Task`](`crate::Task`).
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(
+	Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum State {
 	/// The task is still open e.g. not done (no representation).
+	#[default]
 	Open,
 
 	/// The task is done (representation: `x`).
@@ -25,12 +29,6 @@ impl State {
 	}
 }
 
-impl Default for State {
-	fn default() -> Self {
-		Self::Open
-	}
-}
-
 impl fmt::Display for State {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 		match self {
@@ -55,3 +53,4 @@ impl Parse for State {
 }
 
 crate::impl_fromstr!(State);
+crate::impl_parse_prefix!(State);
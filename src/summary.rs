@@ -0,0 +1,140 @@
+use std::str::FromStr;
+
+use crate::date::{Date, DUE_TAG};
+use crate::state::State;
+use crate::task::Task;
+
+/// The key of the custom tag holding the estimated effort of a task (e.g.
+/// `estimate:2h`).
+pub const ESTIMATE_TAG: &str = "estimate";
+
+/// Summarizes all tasks of the project `project` into a single task.
+///
+/// The project can be given with or without the leading `+`. The summary
+/// task contains the following parts:
+///
+/// - `open:<n>`: The number of open tasks.
+/// - `done:<n>`: The number of done tasks.
+/// - `due:<date>`: The earliest due date of all open tasks (omitted if
+///   no open task has a valid due date).
+/// - `estimate:<effort>`: The accumulated estimate of all open tasks
+///   (omitted if no open task has a valid estimate).
+///
+/// Estimates are numbers with an optional unit of `m` (minutes) or `h`
+/// (hours). Estimates without unit are interpreted as hours. The
+/// accumulated estimate is written in hours if it is a whole number of
+/// hours, otherwise in minutes.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::str::FromStr;
+///
+/// use tdtxt::{summarize_project, Task};
+///
+/// let tasks = [
+///     "Outline chapters +Novel due:2021-09-01 estimate:2h",
+///     "Write chapter 1 +Novel due:2021-08-15 estimate:90m",
+///     "x Find a title +Novel estimate:1h",
+///     "Buy milk",
+/// ]
+/// .iter()
+/// .map(|line| Task::from_str(line).unwrap())
+/// .collect::<Vec<_>>();
+///
+/// let summary = summarize_project(&tasks, "+Novel");
+///
+/// assert_eq!(
+///     summary.to_string(),
+///     "+Novel open:2 done:1 due:2021-08-15 estimate:210m"
+/// );
+/// ```
+pub fn summarize_project<'a, I>(tasks: I, project: &str) -> Task
+where
+	I: IntoIterator<Item = &'a Task>,
+{
+	let project = project.strip_prefix('+').unwrap_or(project);
+
+	let mut open = 0usize;
+	let mut done = 0usize;
+	let mut due: Option<Date> = None;
+	let mut estimate: Option<u64> = None;
+
+	for task in tasks
+		.into_iter()
+		.filter(|task| task.description.projects().any(|p| p == project))
+	{
+		if task.state == State::Done {
+			done += 1;
+			continue;
+		}
+
+		open += 1;
+
+		for (key, value) in task.description.custom() {
+			match key {
+				DUE_TAG => {
+					if let Ok(date) = Date::from_str(value) {
+						due = Some(due.map_or(date, |due| due.min(date)));
+					}
+				}
+				ESTIMATE_TAG => {
+					if let Some(minutes) = parse_estimate(value) {
+						estimate = Some(
+							estimate.unwrap_or(0).saturating_add(minutes),
+						);
+					}
+				}
+				_ => {}
+			}
+		}
+	}
+
+	let mut line = format!("+{} open:{} done:{}", project, open, done);
+
+	if let Some(due) = due {
+		line.push_str(&format!(" {}:{}", DUE_TAG, due));
+	}
+
+	if let Some(minutes) = estimate {
+		line.push_str(&format!(
+			" {}:{}",
+			ESTIMATE_TAG,
+			format_estimate(minutes)
+		));
+	}
+
+	Task::build().build(line)
+}
+
+/// Parses an estimate (e.g. `2h`, `1.5h`, `90m` or `2`) into minutes.
+///
+/// Returns `None` for negative estimates and estimates which do not fit
+/// into a `u64`.
+fn parse_estimate(value: &str) -> Option<u64> {
+	let (number, factor) = if let Some(number) = value.strip_suffix('m') {
+		(number, 1.0)
+	} else if let Some(number) = value.strip_suffix('h') {
+		(number, 60.0)
+	} else {
+		(value, 60.0)
+	};
+
+	let minutes = (number.parse::<f64>().ok()? * factor).round();
+
+	// `u64::MAX as f64` rounds up to 2^64, which is itself out of range
+	if minutes >= 0.0 && minutes < u64::MAX as f64 {
+		Some(minutes as u64)
+	} else {
+		None
+	}
+}
+
+/// Formats an estimate in minutes.
+fn format_estimate(minutes: u64) -> String {
+	if minutes.is_multiple_of(60) {
+		format!("{}h", minutes / 60)
+	} else {
+		format!("{}m", minutes)
+	}
+}
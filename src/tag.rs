@@ -0,0 +1,488 @@
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::date::{Date, DUE_TAG, THRESHOLD_TAG};
+#[cfg(feature = "datetime")]
+use crate::datetime::DateTimeTag;
+use crate::dependency::DEPENDENCY_TAG;
+use crate::description::Description;
+use crate::id::ID_TAG;
+use crate::priority::{Priority, PRIORITY_TAG};
+use crate::recurrence::Recurrence;
+use crate::span::ByteSpan;
+use crate::task::{HIDDEN_TAG, RECURRENCE_TAG};
+
+/// Decides which value is used if a custom tag appears multiple times within
+/// a description (e.g. `due:2024-01-01 ... due:2024-06-01`).
+///
+/// Clients of the todo.txt format disagree on how such tags are handled,
+/// this policy makes the choice explicit. It is used by the accessors like
+/// [`Description::tag_with`](`crate::Description::tag_with`) and
+/// [`Task::due_date_with`](`crate::Task::due_date_with`).
+///
+/// # Examples
+///
+/// ```rust
+/// use tdtxt::{Description, DuplicateTagPolicy};
+///
+/// let description = Description::new("Pay rent due:2024-01-01 due:2024-06-01");
+///
+/// assert_eq!(
+///     description.tag_with("due", DuplicateTagPolicy::First),
+///     Ok(Some("2024-01-01"))
+/// );
+/// assert_eq!(
+///     description.tag_with("due", DuplicateTagPolicy::Last),
+///     Ok(Some("2024-06-01"))
+/// );
+/// assert!(description.tag_with("due", DuplicateTagPolicy::Error).is_err());
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum DuplicateTagPolicy {
+	/// Uses the first value (default).
+	#[default]
+	First,
+
+	/// Uses the last value.
+	Last,
+
+	/// Fails with a [`DuplicateTagError`] if there is more than one value.
+	Error,
+}
+
+impl DuplicateTagPolicy {
+	/// Selects a single value of the tag `key` from `values` according to
+	/// the policy.
+	///
+	/// # Errors
+	///
+	/// Returns an error if the policy is [`DuplicateTagPolicy::Error`] and
+	/// `values` yields more than one value.
+	pub fn resolve<T, I>(
+		self,
+		key: &str,
+		values: I,
+	) -> Result<Option<T>, DuplicateTagError>
+	where
+		I: IntoIterator<Item = T>,
+	{
+		let mut values = values.into_iter();
+
+		match self {
+			Self::First => Ok(values.next()),
+			Self::Last => Ok(values.last()),
+			Self::Error => {
+				let first = values.next();
+
+				if first.is_some() && values.next().is_some() {
+					Err(DuplicateTagError { key: key.to_string() })
+				} else {
+					Ok(first)
+				}
+			}
+		}
+	}
+}
+
+/// An error which is returned if a custom tag appears multiple times while
+/// [`DuplicateTagPolicy::Error`] is used.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DuplicateTagError {
+	key: String,
+}
+
+impl DuplicateTagError {
+	/// Returns the key of the duplicated tag.
+	pub fn key(&self) -> &str {
+		&self.key
+	}
+}
+
+impl fmt::Display for DuplicateTagError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "the tag `{}` appears multiple times", self.key)
+	}
+}
+
+impl std::error::Error for DuplicateTagError {}
+
+/// The value of a custom tag interpreted as the most specific matching type.
+///
+/// It is returned by
+/// [`Description::typed_tag`](`crate::Description::typed_tag`) and parsed
+/// on demand with [`TagValue::parse`].
+///
+/// # Examples
+///
+/// ```rust
+/// use std::time::Duration;
+///
+/// use tdtxt::{Date, TagValue};
+///
+/// assert_eq!(TagValue::parse("2021-08-01"), TagValue::Date(Date::from_ymd(2021, 8, 1)));
+/// assert_eq!(TagValue::parse("42"), TagValue::Integer(42));
+/// assert_eq!(TagValue::parse("90m"), TagValue::Duration(Duration::from_secs(90 * 60)));
+/// assert_eq!(TagValue::parse("urgent"), TagValue::Text("urgent"));
+/// ```
+///
+/// # Notes
+///
+/// The enum is non-exhaustive, as some variants (e.g. `DateTime`) depend on
+/// features which can be enabled by any crate in the dependency graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum TagValue<'a> {
+	/// A date (e.g. `2021-08-01`).
+	Date(Date),
+
+	/// A date with a time of day (e.g. `2021-08-01T14:00`).
+	#[cfg(feature = "datetime")]
+	DateTime(DateTimeTag),
+
+	/// A whole number (e.g. `42` or `-1`).
+	Integer(i64),
+
+	/// A non-negative number followed by one of the units `m` (minutes),
+	/// `h` (hours), `d` (days) or `w` (weeks), e.g. `90m` or `1.5h`.
+	Duration(Duration),
+
+	/// Any other value.
+	Text(&'a str),
+}
+
+impl<'a> TagValue<'a> {
+	/// Interprets `value` as the first matching type in the order date,
+	/// date with time (feature `datetime`), integer, duration and text.
+	pub fn parse(value: &'a str) -> Self {
+		if let Ok(date) = Date::from_str(value) {
+			return Self::Date(date);
+		}
+
+		#[cfg(feature = "datetime")]
+		if let Ok(datetime) = DateTimeTag::from_str(value) {
+			return Self::DateTime(datetime);
+		}
+
+		if let Ok(integer) = value.parse::<i64>() {
+			Self::Integer(integer)
+		} else if let Some(duration) = parse_duration(value) {
+			Self::Duration(duration)
+		} else {
+			Self::Text(value)
+		}
+	}
+
+	/// Returns the date if the value is a [`TagValue::Date`].
+	pub const fn as_date(&self) -> Option<Date> {
+		match self {
+			Self::Date(date) => Some(*date),
+			_ => None,
+		}
+	}
+
+	/// Returns the date and time if the value is a [`TagValue::DateTime`].
+	#[cfg(feature = "datetime")]
+	pub const fn as_datetime(&self) -> Option<DateTimeTag> {
+		match self {
+			Self::DateTime(datetime) => Some(*datetime),
+			_ => None,
+		}
+	}
+
+	/// Returns the number if the value is a [`TagValue::Integer`].
+	pub const fn as_integer(&self) -> Option<i64> {
+		match self {
+			Self::Integer(integer) => Some(*integer),
+			_ => None,
+		}
+	}
+
+	/// Returns the duration if the value is a [`TagValue::Duration`].
+	pub const fn as_duration(&self) -> Option<Duration> {
+		match self {
+			Self::Duration(duration) => Some(*duration),
+			_ => None,
+		}
+	}
+
+	/// Returns the text if the value is a [`TagValue::Text`].
+	pub const fn as_text(&self) -> Option<&'a str> {
+		match self {
+			Self::Text(text) => Some(text),
+			_ => None,
+		}
+	}
+}
+
+/// A well-known custom tag of the todo.txt community together with its
+/// typed value.
+///
+/// These tags are not part of the todo.txt format but are supported by many
+/// clients. They are returned by
+/// [`Description::known_tags`](`crate::Description::known_tags`).
+///
+/// # Examples
+///
+/// ```rust
+/// use tdtxt::{Date, KnownTag, Priority};
+///
+/// assert_eq!(
+///     KnownTag::parse("due", "2021-08-01"),
+///     Some(KnownTag::Due(Date::from_ymd(2021, 8, 1)))
+/// );
+/// assert_eq!(KnownTag::parse("pri", "B"), Some(KnownTag::Priority(Priority::B)));
+/// assert_eq!(KnownTag::parse("h", "1").map(|tag| tag.key()), Some("h"));
+///
+/// // Unknown keys and invalid values are rejected
+/// assert_eq!(KnownTag::parse("estimate", "2h"), None);
+/// assert_eq!(KnownTag::parse("due", "tomorrow"), None);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KnownTag<'a> {
+	/// The due date ([`DUE_TAG`](`crate::DUE_TAG`), e.g. `due:2021-08-01`).
+	Due(Date),
+
+	/// The threshold date before which a task should not be shown
+	/// ([`THRESHOLD_TAG`](`crate::THRESHOLD_TAG`), e.g. `t:2021-07-25`).
+	Threshold(Date),
+
+	/// The recurrence ([`RECURRENCE_TAG`](`crate::RECURRENCE_TAG`), e.g.
+	/// `rec:1w`).
+	Recurrence(Recurrence),
+
+	/// The priority of a done task ([`PRIORITY_TAG`](`crate::PRIORITY_TAG`),
+	/// e.g. `pri:A`).
+	Priority(Priority),
+
+	/// The id of the task ([`ID_TAG`](`crate::ID_TAG`), e.g. `id:42`).
+	Id(&'a str),
+
+	/// The id of a task this task depends on
+	/// ([`DEPENDENCY_TAG`](`crate::DEPENDENCY_TAG`), e.g. `dep:41`).
+	Dependency(&'a str),
+
+	/// Whether the task is hidden ([`HIDDEN_TAG`](`crate::HIDDEN_TAG`), `h:1`
+	/// or `h:0`).
+	Hidden(bool),
+}
+
+impl<'a> KnownTag<'a> {
+	/// Interprets the custom tag `key:value` as a known tag.
+	///
+	/// Returns `None` if `key` is not a known key or `value` is not valid for
+	/// it.
+	pub fn parse(key: &str, value: &'a str) -> Option<Self> {
+		match key {
+			DUE_TAG => Date::from_str(value).ok().map(Self::Due),
+			THRESHOLD_TAG => Date::from_str(value).ok().map(Self::Threshold),
+			RECURRENCE_TAG => {
+				Recurrence::from_str(value).ok().map(Self::Recurrence)
+			}
+			PRIORITY_TAG => {
+				let mut chars = value.chars();
+
+				match (chars.next(), chars.next()) {
+					(Some(c), None) => {
+						Priority::try_from(c).ok().map(Self::Priority)
+					}
+					_ => None,
+				}
+			}
+			ID_TAG => Some(Self::Id(value)),
+			DEPENDENCY_TAG => Some(Self::Dependency(value)),
+			HIDDEN_TAG => match value {
+				"1" => Some(Self::Hidden(true)),
+				"0" => Some(Self::Hidden(false)),
+				_ => None,
+			},
+			_ => None,
+		}
+	}
+
+	/// Returns the key of the tag (e.g. `due`).
+	pub const fn key(&self) -> &'static str {
+		match self {
+			Self::Due(_) => DUE_TAG,
+			Self::Threshold(_) => THRESHOLD_TAG,
+			Self::Recurrence(_) => RECURRENCE_TAG,
+			Self::Priority(_) => PRIORITY_TAG,
+			Self::Id(_) => ID_TAG,
+			Self::Dependency(_) => DEPENDENCY_TAG,
+			Self::Hidden(_) => HIDDEN_TAG,
+		}
+	}
+}
+
+/// A callback validating the value of a custom tag, returning a message
+/// describing the problem if the value is invalid.
+pub type TagValidator = Box<dyn Fn(&str) -> Result<(), String> + Send + Sync>;
+
+/// A set of custom tag keys with callbacks validating their values, e.g. to
+/// enforce the conventions of a team.
+///
+/// Validation runs on demand with [`Self::validate`] (or
+/// [`TodoFile::validate_tags`](`crate::TodoFile::validate_tags`)), parsing
+/// itself never fails because of an invalid tag value.
+///
+/// # Examples
+///
+/// ```rust
+/// use tdtxt::{Description, TagRegistry, TagValue};
+///
+/// let mut registry = TagRegistry::with_known_tags();
+/// registry.register("estimate", |value| match TagValue::parse(value) {
+///     TagValue::Duration(_) => Ok(()),
+///     _ => Err(String::from("expected a duration, e.g. `2h`")),
+/// });
+///
+/// let description = Description::new("Write report estimate:soon due:2021-08-01");
+/// let warnings = registry.validate(&description);
+///
+/// assert_eq!(warnings.len(), 1);
+/// assert_eq!(warnings[0].key, "estimate");
+/// assert_eq!(
+///     warnings[0].to_string(),
+///     "invalid value `soon` for tag `estimate`: expected a duration, e.g. `2h`"
+/// );
+/// ```
+#[derive(Default)]
+pub struct TagRegistry {
+	validators: BTreeMap<String, TagValidator>,
+}
+
+impl TagRegistry {
+	/// Creates an empty registry.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Creates a registry which validates all well-known tags (see
+	/// [`KnownTag`]).
+	pub fn with_known_tags() -> Self {
+		let mut registry = Self::new();
+
+		for key in &[
+			DUE_TAG,
+			THRESHOLD_TAG,
+			RECURRENCE_TAG,
+			PRIORITY_TAG,
+			ID_TAG,
+			DEPENDENCY_TAG,
+			HIDDEN_TAG,
+		] {
+			registry.register(*key, move |value| {
+				KnownTag::parse(key, value).map(|_| ()).ok_or_else(|| {
+					format!("not a valid value for the known tag `{}`", key)
+				})
+			});
+		}
+
+		registry
+	}
+
+	/// Registers `validator` for the tag key `key`, replacing any previous
+	/// validator of the key.
+	pub fn register<K, F>(&mut self, key: K, validator: F) -> &mut Self
+	where
+		K: Into<String>,
+		F: Fn(&str) -> Result<(), String> + Send + Sync + 'static,
+	{
+		let _ = self.validators.insert(key.into(), Box::new(validator));
+		self
+	}
+
+	/// Removes the validator of the tag key `key` and returns `true` if
+	/// there was one.
+	pub fn unregister(&mut self, key: &str) -> bool {
+		self.validators.remove(key).is_some()
+	}
+
+	/// Returns `true` if a validator is registered for the tag key `key`.
+	pub fn is_registered(&self, key: &str) -> bool {
+		self.validators.contains_key(key)
+	}
+
+	/// Validates all custom tags of `description` with a registered key and
+	/// returns a warning for each invalid value, in the order they appear.
+	pub fn validate(&self, description: &Description) -> Vec<TagWarning> {
+		description
+			.custom_ranges()
+			.iter()
+			.filter_map(|range| {
+				let key = &description[range.key().to_range_usize()];
+				let value = &description[range.value().to_range_usize()];
+				let validator = self.validators.get(key)?;
+
+				validator(value).err().map(|message| TagWarning {
+					key: key.to_string(),
+					value: value.to_string(),
+					span: *range.full(),
+					message,
+				})
+			})
+			.collect()
+	}
+}
+
+impl fmt::Debug for TagRegistry {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_set().entries(self.validators.keys()).finish()
+	}
+}
+
+/// A custom tag whose value was rejected by the validator of a
+/// [`TagRegistry`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TagWarning {
+	/// The key of the tag.
+	pub key: String,
+
+	/// The rejected value.
+	pub value: String,
+
+	/// The location of the whole tag within the description.
+	pub span: ByteSpan,
+
+	/// The message returned by the validator.
+	pub message: String,
+}
+
+impl fmt::Display for TagWarning {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"invalid value `{}` for tag `{}`: {}",
+			self.value, self.key, self.message
+		)
+	}
+}
+
+/// Parses a duration like `90m`, `1.5h`, `2d` or `1w`.
+fn parse_duration(value: &str) -> Option<Duration> {
+	const MINUTE: f64 = 60.0;
+
+	let unit = match value.as_bytes().last()? {
+		b'm' => MINUTE,
+		b'h' => 60.0 * MINUTE,
+		b'd' => 24.0 * 60.0 * MINUTE,
+		b'w' => 7.0 * 24.0 * 60.0 * MINUTE,
+		_ => return None,
+	};
+
+	let number = &value[..value.len() - 1];
+
+	if number.is_empty()
+		|| !number.bytes().all(|b| b.is_ascii_digit() || b == b'.')
+	{
+		return None;
+	}
+
+	let seconds = number.parse::<f64>().ok()? * unit;
+
+	Duration::try_from_secs_f64(seconds).ok()
+}
@@ -1,14 +1,36 @@
+use std::convert::TryFrom;
 use std::fmt;
+use std::str::FromStr;
 
-use crate::date::DateCompound;
-use crate::description::Description;
+use crate::clock::{Clock, SystemClock};
+use crate::date::{
+	Date, DateCompound, DateOrderError, Days, DUE_TAG, THRESHOLD_TAG,
+};
+use crate::decode::DecodeOptions;
+use crate::description::{
+	is_valid_tag_value, Description, DescriptionOptions,
+};
 use crate::parse::{Parse, Parser};
-use crate::priority::Priority;
+use crate::priority::{Priority, PriorityStep, PRIORITY_TAG};
+use crate::recurrence::Recurrence;
 use crate::state::State;
+use crate::tag::{DuplicateTagError, DuplicateTagPolicy};
+use crate::task_ref::TaskRef;
+
+/// The key of the custom tag holding the recurrence of a task (e.g.
+/// `rec:1w`).
+pub const RECURRENCE_TAG: &str = "rec";
+
+/// The key of the custom tag marking a task as hidden (`h:1`).
+///
+/// Hidden tasks are used by some clients to keep projects and contexts
+/// available without an actual task, they should not be shown to the user.
+pub const HIDDEN_TAG: &str = "h";
 
 /// Represents the whole task.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Task {
 	/// Optional state of the task.
 	pub state: State,
@@ -31,12 +53,74 @@ pub struct Task {
 	pub description: Description,
 }
 
+/// The exact line a task was parsed from (e.g. `"x\t(A)  Hello"`), without
+/// the line ending.
+///
+/// It is kept by the [`Description`] of the task if the line differs from
+/// the canonical formatting, so that formatting an unmodified task
+/// reproduces the input. Like the revision of a
+/// [`TodoFile`](`crate::TodoFile`) it is not part of the value of the task,
+/// e.g. it is ignored when comparing tasks.
+#[derive(Debug, Clone)]
+pub struct SourceLine {
+	/// The whole line.
+	line: Box<str>,
+
+	/// The byte index at which the description starts.
+	description_start: usize,
+
+	/// The state parsed from the line.
+	state: State,
+
+	/// The priority parsed from the line.
+	priority: Option<Priority>,
+
+	/// The date compound parsed from the line.
+	date_compound: Option<DateCompound>,
+}
+
+impl SourceLine {
+	/// Returns the whole line if it still matches `task`, i.e. if neither
+	/// the state, priority and date compound nor the description were
+	/// changed.
+	fn line(&self, task: &Task) -> Option<&str> {
+		let description = &self.line[self.description_start..];
+
+		(description == task.description.description()
+			&& self.matches_prefix(task))
+		.then_some(&*self.line)
+	}
+
+	/// Returns the part of the line in front of the description if it still
+	/// matches the state, priority and date compound of `task`.
+	fn prefix(&self, task: &Task) -> Option<&str> {
+		self.matches_prefix(task).then(|| &self.line[..self.description_start])
+	}
+
+	/// Returns `true` if the state, priority and date compound of `task`
+	/// are the ones parsed from the line.
+	fn matches_prefix(&self, task: &Task) -> bool {
+		self.state == task.state
+			&& self.priority == task.priority
+			&& self.date_compound == task.date_compound
+	}
+}
+
 impl Task {
 	/// Creates a new builder for a task.
 	pub fn build() -> TaskBuilder {
 		TaskBuilder::default()
 	}
 
+	/// Creates a builder with the state, priority and date compound of the
+	/// task.
+	///
+	/// This can be used to create a modified copy of the task. See
+	/// [`TaskBuilder::from_task`].
+	pub const fn to_builder(&self) -> TaskBuilder {
+		TaskBuilder::from_task(self)
+	}
+
 	/// Returns the state of the task.
 	pub const fn state(&self) -> &State {
 		&self.state
@@ -47,6 +131,85 @@ impl Task {
 		self.priority.as_ref()
 	}
 
+	/// Sets the priority of the task and returns the previous one.
+	pub fn set_priority<P>(&mut self, priority: P) -> Option<Priority>
+	where
+		P: Into<Priority>,
+	{
+		self.priority.replace(priority.into())
+	}
+
+	/// Removes the priority of the task and returns it.
+	pub const fn clear_priority(&mut self) -> Option<Priority> {
+		self.priority.take()
+	}
+
+	/// Raises or lowers the priority of the task by one (see
+	/// [`Priority::step`]) and returns the new priority.
+	///
+	/// Tasks without priority are left untouched.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::str::FromStr;
+	///
+	/// use tdtxt::{Priority, PriorityStep, Task};
+	///
+	/// let mut task = Task::from_str("(B) Call Mom").unwrap();
+	///
+	/// assert_eq!(task.bump_priority(PriorityStep::Higher), Some(Priority::A));
+	/// assert_eq!(task.bump_priority(PriorityStep::Higher), Some(Priority::A));
+	/// assert_eq!(task.to_string(), "(A) Call Mom");
+	///
+	/// assert_eq!(task.clear_priority(), Some(Priority::A));
+	/// assert_eq!(task.bump_priority(PriorityStep::Lower), None);
+	/// assert_eq!(task.to_string(), "Call Mom");
+	/// ```
+	pub fn bump_priority(&mut self, step: PriorityStep) -> Option<Priority> {
+		self.priority = self.priority.map(|priority| priority.step(step));
+		self.priority
+	}
+
+	/// Returns the priority of the task or, if it has none, the priority
+	/// stored in its first valid [`PRIORITY_TAG`](`crate::PRIORITY_TAG`) tag
+	/// (e.g. `pri:A`).
+	///
+	/// The tag is written when completing a task with
+	/// [`CompletionPriority::Tag`], so done tasks keep their original
+	/// priority, e.g. for sorting with
+	/// [`SortKey::Priority`](`crate::SortKey::Priority`).
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::str::FromStr;
+	///
+	/// use tdtxt::{Priority, Task};
+	///
+	/// let task =
+	///     Task::from_str("x 2021-08-01 2021-07-30 Call Mom pri:B").unwrap();
+	///
+	/// assert_eq!(task.priority(), None);
+	/// assert_eq!(task.effective_priority(), Some(Priority::B));
+	/// ```
+	pub fn effective_priority(&self) -> Option<Priority> {
+		self.priority.or_else(|| self.tag_priority())
+	}
+
+	/// Returns the priority stored in the first valid
+	/// [`PRIORITY_TAG`](`crate::PRIORITY_TAG`) tag.
+	fn tag_priority(&self) -> Option<Priority> {
+		self.description.tags(PRIORITY_TAG).find_map(|value| {
+			let mut chars = value.chars();
+
+			match (chars.next(), chars.next()) {
+				(Some(c), None) => Priority::try_from(c).ok(),
+				_ => None,
+			}
+		})
+	}
+
 	/// Returns the date compound of the task.
 	pub const fn date_compound(&self) -> Option<&DateCompound> {
 		self.date_compound.as_ref()
@@ -56,10 +219,649 @@ impl Task {
 	pub const fn description(&self) -> &Description {
 		&self.description
 	}
+
+	/// Lets `f` edit the text of the description in place and returns the
+	/// result of `f`.
+	///
+	/// The projects, contexts and custom tags are located again after `f`
+	/// returns, so they always reflect the edited text.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::str::FromStr;
+	///
+	/// use tdtxt::Task;
+	///
+	/// let mut task = Task::from_str("(A) Call Mom @phone").unwrap();
+	///
+	/// task.map_description(|text| text.push_str(" +family"));
+	///
+	/// assert_eq!(task.to_string(), "(A) Call Mom @phone +family");
+	/// assert_eq!(task.description().projects().collect::<Vec<_>>(), ["family"]);
+	/// ```
+	pub fn map_description<F, R>(&mut self, f: F) -> R
+	where
+		F: FnOnce(&mut String) -> R,
+	{
+		self.description.edit(f)
+	}
+
+	/// Returns the task with its description replaced by `description`.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::str::FromStr;
+	///
+	/// use tdtxt::Task;
+	///
+	/// let task = Task::from_str("x (A) Call Mom").unwrap();
+	///
+	/// let task = task.with_description("Call Dad @phone");
+	///
+	/// assert_eq!(task.to_string(), "x (A) Call Dad @phone");
+	/// ```
+	pub fn with_description<D>(mut self, description: D) -> Self
+	where
+		D: Into<Description>,
+	{
+		self.description = description.into();
+		self
+	}
+
+	/// Normalizes the description of the task into a canonical form and
+	/// returns `true` if it changed.
+	///
+	/// The canonical form is defined by the following rules:
+	///
+	/// - All words are separated by a single space, there is no leading or
+	///   trailing whitespace.
+	/// - Components within the text (followed by at least one text word)
+	///   keep their position, as they are part of the sentence.
+	/// - The trailing components are ordered by their kind: projects first,
+	///   then contexts and then custom tags. Components of the same kind
+	///   keep their relative order.
+	///
+	/// Two tasks which only differ in whitespace or in the order of their
+	/// trailing components are equal after normalization.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::str::FromStr;
+	///
+	/// use tdtxt::Task;
+	///
+	/// let mut a = Task::from_str("Call  Mom  @phone due:2021-08-01 +Family ").unwrap();
+	/// let mut b = Task::from_str("Call Mom +Family @phone\tdue:2021-08-01").unwrap();
+	///
+	/// assert!(a.normalize());
+	/// assert!(b.normalize());
+	/// assert_eq!(a.to_string(), "Call Mom +Family @phone due:2021-08-01");
+	/// assert_eq!(a, b);
+	///
+	/// assert!(!a.normalize());
+	/// ```
+	pub fn normalize(&mut self) -> bool {
+		let normalized = self.description.normalized();
+		let had_source = self.description.source().is_some();
+
+		self.description.set_source(None);
+
+		if normalized == self.description.description() {
+			had_source
+		} else {
+			self.description.edit(|raw| *raw = normalized);
+			true
+		}
+	}
+
+	/// Returns `true` if both tasks have the same state, priority and dates
+	/// and their descriptions are semantically equal (see
+	/// [`Description::eq_semantic`]), i.e. they only differ in the order of
+	/// their components or in redundant whitespace.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::str::FromStr;
+	///
+	/// use tdtxt::Task;
+	///
+	/// let a = Task::from_str("(A) Call Mom +Family @phone").unwrap();
+	/// let b = Task::from_str("(A) Call Mom @phone  +Family").unwrap();
+	///
+	/// assert!(a.eq_semantic(&b));
+	/// assert!(!a.eq_semantic(&Task::from_str("(B) Call Mom +Family @phone").unwrap()));
+	/// ```
+	pub fn eq_semantic(&self, other: &Self) -> bool {
+		self.state == other.state
+			&& self.priority == other.priority
+			&& self.date_compound == other.date_compound
+			&& self.description.eq_semantic(&other.description)
+	}
+
+	/// Returns the number of days since the creation of the task, or `None`
+	/// if it has no creation date.
+	///
+	/// The age is negative if the task was created after `today`.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::str::FromStr;
+	///
+	/// use tdtxt::{Date, Days, Task};
+	///
+	/// let task = Task::from_str("2021-07-30 Call Mom").unwrap();
+	///
+	/// assert_eq!(task.age(Date::from_ymd(2021, 8, 1)), Some(Days(2)));
+	/// assert_eq!(Task::from_str("Call Mom").unwrap().age(Date::from_ymd(2021, 8, 1)), None);
+	/// ```
+	pub fn age(&self, today: Date) -> Option<Days> {
+		let created = *self.date_compound?.date_created();
+
+		Some(Days(created.days_until(&today)))
+	}
+
+	/// Returns the earliest valid due date (`due:` tag) of the task.
+	///
+	/// Tag values which are not valid dates are ignored.
+	pub fn due_date(&self) -> Option<Date> {
+		self.tag_date(DUE_TAG)
+	}
+
+	/// Returns the valid due date (`due:` tag) of the task selected
+	/// according to `policy`.
+	///
+	/// Unlike [`Task::due_date`], which uses the earliest date, the policy
+	/// decides which date is used if the tag appears multiple times. Tag
+	/// values which are not valid dates are ignored.
+	///
+	/// # Errors
+	///
+	/// Returns an error if the task has multiple valid due dates and
+	/// `policy` is [`DuplicateTagPolicy::Error`].
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::str::FromStr;
+	///
+	/// use tdtxt::{Date, DuplicateTagPolicy, Task};
+	///
+	/// let task = Task::from_str("Pay rent due:2024-06-01 due:2024-01-01").unwrap();
+	///
+	/// assert_eq!(
+	///     task.due_date_with(DuplicateTagPolicy::First),
+	///     Ok(Some(Date::from_ymd(2024, 6, 1)))
+	/// );
+	/// assert_eq!(
+	///     task.due_date_with(DuplicateTagPolicy::Last),
+	///     Ok(Some(Date::from_ymd(2024, 1, 1)))
+	/// );
+	/// assert!(task.due_date_with(DuplicateTagPolicy::Error).is_err());
+	/// ```
+	pub fn due_date_with(
+		&self,
+		policy: DuplicateTagPolicy,
+	) -> Result<Option<Date>, DuplicateTagError> {
+		policy.resolve(
+			DUE_TAG,
+			self.description
+				.tags(DUE_TAG)
+				.filter_map(|value| Date::from_str(value).ok()),
+		)
+	}
+
+	/// Returns the earliest valid date in a custom tag with the key `key`.
+	fn tag_date(&self, key: &str) -> Option<Date> {
+		self.description
+			.tags(key)
+			.filter_map(|value| Date::from_str(value).ok())
+			.min()
+	}
+
+	/// Returns the recurrence of the task read from the first valid
+	/// [`RECURRENCE_TAG`] tag (e.g. `rec:1w`).
+	pub fn recurrence(&self) -> Option<Recurrence> {
+		self.description
+			.tags(RECURRENCE_TAG)
+			.find_map(|value| Recurrence::from_str(value).ok())
+	}
+
+	/// Returns the valid recurrence of the task (`rec:` tag) selected
+	/// according to `policy`.
+	///
+	/// Tag values which are not valid recurrences are ignored.
+	///
+	/// # Errors
+	///
+	/// Returns an error if the task has multiple valid recurrences and
+	/// `policy` is [`DuplicateTagPolicy::Error`].
+	pub fn recurrence_with(
+		&self,
+		policy: DuplicateTagPolicy,
+	) -> Result<Option<Recurrence>, DuplicateTagError> {
+		policy.resolve(
+			RECURRENCE_TAG,
+			self.description
+				.tags(RECURRENCE_TAG)
+				.filter_map(|value| Recurrence::from_str(value).ok()),
+		)
+	}
+
+	/// Returns `true` if the task is open and its due date (see
+	/// [`Task::due_date`]) is before `today`.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::str::FromStr;
+	///
+	/// use tdtxt::{Date, Task};
+	///
+	/// let today = Date::from_ymd(2021, 8, 1);
+	///
+	/// assert!(Task::from_str("Pay rent due:2021-07-31").unwrap().is_overdue(today));
+	/// assert!(!Task::from_str("Pay rent due:2021-08-01").unwrap().is_overdue(today));
+	/// assert!(!Task::from_str("x Pay rent due:2021-07-31").unwrap().is_overdue(today));
+	/// ```
+	pub fn is_overdue(&self, today: Date) -> bool {
+		self.state.is_open() && self.due_date().is_some_and(|due| due < today)
+	}
+
+	/// Returns `true` if the task is open and its due date (see
+	/// [`Task::due_date`]) is between `today` and `days` after `today`
+	/// (inclusive).
+	///
+	/// Overdue tasks are not included (see [`Task::is_overdue`]).
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::str::FromStr;
+	///
+	/// use tdtxt::{Date, Days, Task};
+	///
+	/// let today = Date::from_ymd(2021, 8, 1);
+	/// let task = Task::from_str("Pay rent due:2021-08-03").unwrap();
+	///
+	/// assert!(task.is_due_within(today, Days(2)));
+	/// assert!(!task.is_due_within(today, Days(1)));
+	/// ```
+	pub fn is_due_within(&self, today: Date, days: Days) -> bool {
+		self.state.is_open()
+			&& self.due_date().is_some_and(|due| {
+				(0..=days.0).contains(&today.days_until(&due))
+			})
+	}
+
+	/// Returns `true` if the task is hidden, i.e. it contains the tag `h:1`
+	/// (see [`HIDDEN_TAG`]).
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::str::FromStr;
+	///
+	/// use tdtxt::Task;
+	///
+	/// assert!(Task::from_str("+GarageSale @phone h:1").unwrap().is_hidden());
+	/// assert!(!Task::from_str("Call Mom h:0").unwrap().is_hidden());
+	/// ```
+	pub fn is_hidden(&self) -> bool {
+		self.description.tags(HIDDEN_TAG).any(|value| value == "1")
+	}
+
+	/// Marks the task as done on `today`.
+	///
+	/// The completion date is set to `today` while the creation date is kept.
+	/// As the todo.txt format can not represent a completion date without a
+	/// creation date, `today` is used for both if the task has no creation
+	/// date. The priority is handled according to `priority`.
+	///
+	/// Returns `false` and leaves the task untouched if it is already done.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::str::FromStr;
+	///
+	/// use tdtxt::{CompletionPriority, Date, Task};
+	///
+	/// let mut task =
+	///     Task::from_str("(A) 2021-07-30 Call Mom +Family").unwrap();
+	///
+	/// assert!(task.complete(Date::from_ymd(2021, 8, 1), CompletionPriority::Tag));
+	/// assert_eq!(
+	///     task.to_string(),
+	///     "x 2021-08-01 2021-07-30 Call Mom +Family pri:A"
+	/// );
+	///
+	/// assert!(!task.complete(Date::from_ymd(2021, 8, 2), CompletionPriority::Tag));
+	/// ```
+	pub fn complete(
+		&mut self,
+		today: Date,
+		priority: CompletionPriority,
+	) -> bool {
+		if self.state.is_done() {
+			return false;
+		}
+
+		self.mark_done(Some(today), priority);
+
+		true
+	}
+
+	/// Marks the task as done, stamping the completion date if `today` is
+	/// given.
+	fn mark_done(
+		&mut self,
+		today: Option<Date>,
+		priority: CompletionPriority,
+	) {
+		self.state = State::Done;
+
+		if let Some(today) = today {
+			let created = self
+				.date_compound
+				.map_or(today, |date_compound| *date_compound.date_created());
+
+			self.date_compound = Some(DateCompound::completed(created, today));
+		}
+
+		match priority {
+			CompletionPriority::Keep => {}
+			CompletionPriority::Remove => self.priority = None,
+			CompletionPriority::Tag => {
+				if let Some(priority) = self.priority.take() {
+					self.description.write_tag(
+						PRIORITY_TAG,
+						priority.as_char().encode_utf8(&mut [0; 4]),
+					);
+				}
+			}
+		}
+	}
+
+	/// Reopens a done task, reverting [`Task::complete`].
+	///
+	/// The completion date is removed while the creation date is kept. If
+	/// the task has no priority but a valid
+	/// [`PRIORITY_TAG`](`crate::PRIORITY_TAG`) tag (e.g. `pri:A`), the
+	/// priority is restored from it and the tag is removed.
+	///
+	/// Returns `false` and leaves the task untouched if it is not done.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::str::FromStr;
+	///
+	/// use tdtxt::Task;
+	///
+	/// let mut task =
+	///     Task::from_str("x 2021-08-01 2021-07-30 Call Mom pri:A +Family")
+	///         .unwrap();
+	///
+	/// assert!(task.reopen());
+	/// assert_eq!(task.to_string(), "(A) 2021-07-30 Call Mom +Family");
+	///
+	/// assert!(!task.reopen());
+	/// ```
+	pub fn reopen(&mut self) -> bool {
+		if !self.state.is_done() {
+			return false;
+		}
+
+		self.state = State::Open;
+		self.date_compound = self.date_compound.map(|date_compound| {
+			DateCompound::created(*date_compound.date_created())
+		});
+
+		if self.priority.is_none() {
+			if let Some(priority) = self.tag_priority() {
+				self.priority = Some(priority);
+				self.description.remove_tags(PRIORITY_TAG, None);
+			}
+		}
+
+		true
+	}
+
+	/// Toggles the state of the task and returns the new state.
+	///
+	/// Open tasks are completed according to `policy` (see
+	/// [`Task::complete`]), done tasks are reopened (see [`Task::reopen`]).
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::str::FromStr;
+	///
+	/// use tdtxt::{
+	///     CompletionPolicy, CompletionPriority, Date, FixedClock, State, Task,
+	/// };
+	///
+	/// let clock = FixedClock(Date::from_ymd(2021, 8, 1));
+	/// let policy =
+	///     CompletionPolicy::new(clock).priority(CompletionPriority::Remove);
+	///
+	/// let mut task = Task::from_str("(A) 2021-07-30 Call Mom").unwrap();
+	///
+	/// assert_eq!(task.toggle_state(&policy), State::Done);
+	/// assert_eq!(task.to_string(), "x 2021-08-01 2021-07-30 Call Mom");
+	///
+	/// assert_eq!(task.toggle_state(&policy), State::Open);
+	/// assert_eq!(task.to_string(), "2021-07-30 Call Mom");
+	/// ```
+	pub fn toggle_state<C>(&mut self, policy: &CompletionPolicy<C>) -> State
+	where
+		C: Clock,
+	{
+		match self.state {
+			State::Open => {
+				let today = if policy.stamp_dates {
+					Some(policy.clock.today())
+				} else {
+					None
+				};
+
+				self.mark_done(today, policy.priority);
+			}
+			State::Done => {
+				let _ = self.reopen();
+			}
+		}
+
+		self.state
+	}
+
+	/// Creates the follow-up task of a recurring task (see
+	/// [`Task::recurrence`]), e.g. after completing it on `today`.
+	///
+	/// The follow-up task is an open copy of the task (see [`Task::reopen`])
+	/// with the following dates:
+	///
+	/// - The due date (`due:` tag) is set to the base date advanced by the
+	///   recurrence. It is added if the task has neither a due date nor a
+	///   threshold date.
+	/// - The threshold date (`t:` tag) keeps its distance to the due date.
+	///   Without a due date it is set to the base date advanced by the
+	///   recurrence.
+	/// - The creation date is set to `today` if the task has one.
+	///
+	/// The base date of a relative recurrence (e.g. `rec:1w`) is `today`.
+	/// The base date of a strict recurrence (e.g. `rec:+1w`) is the due date
+	/// or, without a due date, the threshold date. A strict recurrence
+	/// without either date falls back to `today`.
+	///
+	/// Returns `None` if the task has no recurrence or a date would be out
+	/// of range.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::str::FromStr;
+	///
+	/// use tdtxt::{Date, Task};
+	///
+	/// let task = Task::from_str(
+	///     "x 2021-08-01 2021-07-25 Pay rent due:2021-08-01 t:2021-07-29 \
+	///      rec:1m pri:A",
+	/// )
+	/// .unwrap();
+	///
+	/// let next = task.next_occurrence(Date::from_ymd(2021, 8, 2)).unwrap();
+	///
+	/// assert_eq!(
+	///     next.to_string(),
+	///     "(A) 2021-08-02 Pay rent due:2021-09-02 t:2021-08-30 rec:1m"
+	/// );
+	///
+	/// let task =
+	///     Task::from_str("x Pay rent due:2021-08-01 rec:+1m").unwrap();
+	///
+	/// let next = task.next_occurrence(Date::from_ymd(2021, 8, 2)).unwrap();
+	///
+	/// assert_eq!(next.to_string(), "Pay rent due:2021-09-01 rec:+1m");
+	/// ```
+	pub fn next_occurrence(&self, today: Date) -> Option<Self> {
+		let recurrence = self.recurrence()?;
+		let due = self.due_date();
+		let threshold = self.tag_date(THRESHOLD_TAG);
+
+		let mut next = self.clone();
+		next.reopen();
+
+		if next.date_compound.is_some() {
+			next.date_compound = Some(DateCompound::created(today));
+		}
+
+		let base = if recurrence.strict {
+			due.or(threshold).unwrap_or(today)
+		} else {
+			today
+		};
+		let next_date = recurrence.apply(base)?;
+
+		match (due, threshold) {
+			(Some(due), Some(threshold)) => {
+				let distance = Days(due.days_until(&threshold));
+				let threshold = next_date.checked_add_days(distance)?;
+
+				next.description.write_tag(DUE_TAG, &next_date.to_string());
+				next.description
+					.write_tag(THRESHOLD_TAG, &threshold.to_string());
+			}
+			(None, Some(_)) => {
+				next.description
+					.write_tag(THRESHOLD_TAG, &next_date.to_string());
+			}
+			(_, None) => {
+				next.description.write_tag(DUE_TAG, &next_date.to_string());
+			}
+		}
+
+		Some(next)
+	}
+
+	/// Parses a task from raw bytes.
+	///
+	/// The bytes are decoded according to `options` first. With
+	/// [`InvalidUtf8Policy::SkipLine`](`crate::InvalidUtf8Policy::SkipLine`)
+	/// an invalid line results in an error, as there is nothing left to
+	/// parse.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::{DecodeOptions, InvalidUtf8Policy, Task};
+	///
+	/// let task = Task::from_bytes(
+	///     b"\xEF\xBB\xBFBuy milk \xFF",
+	///     DecodeOptions::new().invalid_utf8(InvalidUtf8Policy::Replace),
+	/// )
+	/// .unwrap();
+	///
+	/// assert_eq!(task.description.description(), "Buy milk \u{FFFD}");
+	/// ```
+	pub fn from_bytes(
+		bytes: &[u8],
+		options: DecodeOptions,
+	) -> Result<Self, ParseTaskError> {
+		let line = options.decode(bytes).map_err(|_| ParseTaskError)?;
+		let mut task = Self::from_str(&line)?;
+		task.description.set_options(options.description);
+
+		if options.validate_dates {
+			task.check_date_order().map_err(|_| ParseTaskError)?;
+		}
+
+		Ok(task)
+	}
+
+	/// Checks that the completion date of the task (if any) is not before
+	/// its creation date (see [`DateCompound::check_order`]).
+	///
+	/// # Errors
+	///
+	/// Returns an error if the completion date is before the creation date.
+	pub fn check_date_order(&self) -> Result<(), DateOrderError> {
+		self.date_compound.as_ref().map_or(Ok(()), DateCompound::check_order)
+	}
 }
 
+impl Task {
+	/// Creates a task from its parsed parts.
+	///
+	/// `prefix` is the exact input preceding the description if it differs
+	/// from the canonical formatting, in which case the input line is kept.
+	pub(crate) fn from_parts(
+		state: State,
+		priority: Option<Priority>,
+		date_compound: Option<DateCompound>,
+		mut description: Description,
+		prefix: Option<&str>,
+	) -> Self {
+		if let Some(prefix) = prefix {
+			let line = format!("{}{}", prefix, description.description());
+
+			description.set_source(Some(SourceLine {
+				line: line.into_boxed_str(),
+				description_start: prefix.len(),
+				state,
+				priority,
+				date_compound,
+			}));
+		}
+
+		Self { state, priority, date_compound, description }
+	}
+}
+
+/// Formats the task.
+///
+/// An unmodified parsed task is formatted as the line it was parsed from
+/// (e.g. `"x\t(A)  Hello"`). The original spacing in front of the
+/// description is kept as long as the state, priority and date compound are
+/// unchanged. Otherwise single spaces are used.
 impl fmt::Display for Task {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if let Some(source) = self.description.source() {
+			if let Some(line) = source.line(self) {
+				return f.write_str(line);
+			}
+
+			if let Some(prefix) = source.prefix(self) {
+				f.write_str(prefix)?;
+				return fmt::Display::fmt(&self.description, f);
+			}
+		}
+
 		let mut s: Vec<String> = Vec::with_capacity(4);
 
 		if self.state != State::Open {
@@ -80,6 +882,69 @@ impl fmt::Display for Task {
 	}
 }
 
+/// Decides what happens with the priority of a task when it is completed
+/// (see [`Task::complete`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CompletionPriority {
+	/// Keeps the priority, e.g. `x (A) 2021-08-01 2021-07-30 Call Mom`.
+	Keep,
+
+	/// Removes the priority, e.g. `x 2021-08-01 2021-07-30 Call Mom`.
+	Remove,
+
+	/// Moves the priority into a [`PRIORITY_TAG`](`crate::PRIORITY_TAG`)
+	/// tag, e.g. `x 2021-08-01 2021-07-30 Call Mom pri:A`.
+	///
+	/// An existing tag is overwritten.
+	Tag,
+}
+
+/// Controls how tasks are completed by [`Task::toggle_state`].
+///
+/// # Defaults
+///
+/// The policy implements [`Default`], which stamps completion dates with the
+/// current date (see [`SystemClock`]) and moves the priority into a
+/// [`PRIORITY_TAG`](`crate::PRIORITY_TAG`) tag.
+#[derive(Debug, Clone, Copy)]
+pub struct CompletionPolicy<C = SystemClock> {
+	/// Stamp the completion date when completing a task.
+	pub stamp_dates: bool,
+
+	/// What happens with the priority when completing a task.
+	pub priority: CompletionPriority,
+
+	/// Supplies the current date for stamping completion dates.
+	pub clock: C,
+}
+
+impl<C> CompletionPolicy<C> {
+	/// Creates a policy which stamps completion dates with the date returned
+	/// by `clock` and moves the priority into a tag.
+	pub const fn new(clock: C) -> Self {
+		Self { stamp_dates: true, priority: CompletionPriority::Tag, clock }
+	}
+
+	/// Sets whether completion dates are stamped.
+	pub const fn stamp_dates(mut self, stamp_dates: bool) -> Self {
+		self.stamp_dates = stamp_dates;
+		self
+	}
+
+	/// Sets what happens with the priority.
+	pub const fn priority(mut self, priority: CompletionPriority) -> Self {
+		self.priority = priority;
+		self
+	}
+}
+
+impl Default for CompletionPolicy {
+	fn default() -> Self {
+		Self::new(SystemClock)
+	}
+}
+
 /// This struct represents errors which may occur during the parsing of a
 /// [`Task`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -97,67 +962,244 @@ impl Parse for Task {
 	type Error = ParseTaskError;
 
 	fn parse(parser: &mut Parser<'_>) -> Result<Self, Self::Error> {
-		macro_rules! try_parse {
-			( $parser:ident : $ty:ty ) => {{
-				let mut p_copy = *parser;
-
-				if let Some(ty) = <$ty>::parse_opt(&mut p_copy) {
-					if p_copy.is_eof() || p_copy.expect_whitespace().is_some()
-					{
-						*parser = p_copy;
-						Some(ty)
-					} else {
-						None
-					}
-				} else {
-					None
-				}
-			}};
+		TaskRef::parse_from(parser, DescriptionOptions::default())
+			.map(TaskRef::into_owned)
+	}
+}
+
+impl FromStr for Task {
+	type Err = ParseTaskError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let mut parser = Parser::new(s.as_bytes());
+		Self::parse(&mut parser)
+	}
+}
+
+/// This struct represents a [`Task`] which does not survive a format/parse
+/// round-trip.
+///
+/// It is returned by [`check_format_parse_identity`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoundTripError {
+	/// The formatted task.
+	formatted: String,
+
+	/// The result of parsing [`Self::formatted`].
+	parsed: Box<Result<Task, ParseTaskError>>,
+}
+
+impl RoundTripError {
+	/// Returns the formatted task.
+	pub fn formatted(&self) -> &str {
+		&self.formatted
+	}
+
+	/// Returns the result of parsing the formatted task.
+	pub const fn parsed(&self) -> &Result<Task, ParseTaskError> {
+		&self.parsed
+	}
+}
+
+impl fmt::Display for RoundTripError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match &*self.parsed {
+			Ok(task) => write!(
+				f,
+				"task `{}` was parsed back as `{:?}`",
+				self.formatted, task
+			),
+			Err(err) => write!(f, "task `{}`: {}", self.formatted, err),
 		}
+	}
+}
 
-		let state = try_parse!(parser: State).unwrap_or_default();
-		let priority = try_parse!(parser: Priority);
-		let date_compound = try_parse!(parser: DateCompound);
+impl std::error::Error for RoundTripError {}
 
-		let description =
-			Description::parse(parser).map_err(|_| ParseTaskError)?;
+/// This enum represents errors which may occur when building a [`Task`] with
+/// [`TaskBuilder::build_checked`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildError {
+	/// The task has a completion date but is not done.
+	CompletedNotDone,
 
-		let task = Self { state, priority, date_compound, description };
+	/// The completion date is before the creation date.
+	CompletedBeforeCreated,
 
-		Ok(task)
+	/// The description contains a line break.
+	MultilineDescription,
+
+	/// The recurrence set with [`TaskBuilder::rec`] is empty or contains
+	/// whitespace or a `:`.
+	InvalidRecurrence(String),
+
+	/// The task is not parsed back to an equal task after formatting it, e.g.
+	/// because the description of an open task starts with `x `.
+	RoundTrip(RoundTripError),
+}
+
+impl fmt::Display for BuildError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::CompletedNotDone => {
+				f.write_str("task has a completion date but is not done")
+			}
+			Self::CompletedBeforeCreated => {
+				f.write_str("completion date is before the creation date")
+			}
+			Self::MultilineDescription => {
+				f.write_str("description contains a line break")
+			}
+			Self::InvalidRecurrence(recurrence) => {
+				write!(f, "invalid recurrence `{}`", recurrence)
+			}
+			Self::RoundTrip(err) => fmt::Display::fmt(err, f),
+		}
 	}
 }
 
-impl std::str::FromStr for Task {
-	type Err = ParseTaskError;
+impl std::error::Error for BuildError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Self::RoundTrip(err) => Some(err),
+			_ => None,
+		}
+	}
+}
 
-	fn from_str(s: &str) -> Result<Self, Self::Err> {
-		let mut parser = Parser::new(s.as_bytes());
-		Self::parse(&mut parser)
+/// Checks that `task` is parsed back to an equal task after formatting it.
+///
+/// This can be used to detect tasks which can not be represented in the
+/// todo.txt format, e.g. a description containing a new line or an open task
+/// whose description starts with `x `.
+///
+/// # Examples
+///
+/// ```rust
+/// use tdtxt::{check_format_parse_identity, Priority, Task};
+///
+/// let task = Task::build().priority(Priority::A).build("Call Mom");
+/// assert!(check_format_parse_identity(&task).is_ok());
+///
+/// let task = Task::build().build("x marks the spot");
+/// assert!(check_format_parse_identity(&task).is_err());
+/// ```
+pub fn check_format_parse_identity(task: &Task) -> Result<(), RoundTripError> {
+	let formatted = task.to_string();
+
+	match formatted.parse::<Task>() {
+		Ok(parsed) if &parsed == task => Ok(()),
+		parsed => Err(RoundTripError { formatted, parsed: Box::new(parsed) }),
+	}
+}
+
+/// Creates a [`Task`] from a string literal.
+///
+/// Literals which are empty or span multiple lines are rejected at compile
+/// time. All other validation happens when the expression is evaluated, which
+/// will panic if the literal is not a valid task.
+///
+/// # Examples
+///
+/// ```rust
+/// use tdtxt::{task, Priority, State};
+///
+/// let task = task!("x (A) 2011-03-02 Call Mom @phone");
+///
+/// assert_eq!(task.state(), &State::Done);
+/// assert_eq!(task.priority(), Some(&Priority::A));
+/// assert_eq!(task.description().contexts().collect::<Vec<_>>(), vec!["phone"]);
+/// ```
+///
+/// ```rust,compile_fail
+/// let task = tdtxt::task!("first line\nsecond line");
+/// ```
+#[macro_export]
+macro_rules! task {
+	( $line:literal ) => {{
+		const _: () = ::std::assert!(
+			$crate::__is_task_literal($line),
+			"task literal must be a single non empty line"
+		);
+
+		<$crate::Task as ::std::str::FromStr>::from_str($line)
+			.expect(concat!("invalid task literal: ", $line))
+	}};
+}
+
+/// Returns `true` if `s` is not empty and does not contain a line break.
+///
+/// Used by [`task!`] to validate literals at compile time.
+#[doc(hidden)]
+pub const fn __is_task_literal(s: &str) -> bool {
+	let bytes = s.as_bytes();
+	let mut idx = 0;
+
+	while idx < bytes.len() {
+		if bytes[idx] == b'\n' || bytes[idx] == b'\r' {
+			return false;
+		}
+
+		idx += 1;
 	}
+
+	!bytes.is_empty()
 }
 
 /// A builder for a task.
 ///
-/// All components implement `Copy`, meaning the builder can be used to build
-/// multiple tasks without being consumed.
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+/// The builder is not consumed by [`TaskBuilder::build`], meaning it can be
+/// used to build multiple tasks.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
 pub struct TaskBuilder {
 	state: Option<State>,
 	priority: Option<Priority>,
 	date_compound: Option<DateCompound>,
+	due: Option<Date>,
+	threshold: Option<Date>,
+	recurrence: Option<String>,
 }
 
 impl TaskBuilder {
 	/// Creates a new instance of the builder.
 	pub fn new() -> Self {
-		let (state, priority, date_compound) = <_>::default();
+		Self::default()
+	}
 
-		Self { state, priority, date_compound }
+	/// Creates a builder with the state, priority and date compound of
+	/// `task`.
+	///
+	/// The description is not part of the builder and has to be passed to
+	/// [`TaskBuilder::build`] again.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::str::FromStr;
+	///
+	/// use tdtxt::{Priority, Task, TaskBuilder};
+	///
+	/// let task = Task::from_str("x (A) 2021-07-30 Call Mom").unwrap();
+	///
+	/// let lowered = TaskBuilder::from_task(&task)
+	///     .priority(Priority::C)
+	///     .build(task.description().clone());
+	///
+	/// assert_eq!(lowered.to_string(), "x (C) 2021-07-30 Call Mom");
+	/// ```
+	pub const fn from_task(task: &Task) -> Self {
+		Self {
+			state: Some(task.state),
+			priority: task.priority,
+			date_compound: task.date_compound,
+			due: None,
+			threshold: None,
+			recurrence: None,
+		}
 	}
 
 	/// Sets the state for the task.
-	pub fn state(&mut self, state: State) -> &mut Self {
+	pub const fn state(&mut self, state: State) -> &mut Self {
 		self.state = Some(state);
 		self
 	}
@@ -180,20 +1222,155 @@ impl TaskBuilder {
 		self
 	}
 
+	/// Sets the due date (`due:` tag) for the task.
+	pub fn due<D>(&mut self, due: D) -> &mut Self
+	where
+		D: Into<Date>,
+	{
+		self.due = Some(due.into());
+		self
+	}
+
+	/// Sets the threshold date (`t:` tag) for the task.
+	pub fn threshold<D>(&mut self, threshold: D) -> &mut Self
+	where
+		D: Into<Date>,
+	{
+		self.threshold = Some(threshold.into());
+		self
+	}
+
+	/// Sets the recurrence (`rec:` tag, e.g. `1w` or `+2d`) for the task.
+	///
+	/// The recurrence must not be empty or contain whitespace or a `:`,
+	/// which is checked by [`TaskBuilder::build_checked`].
+	pub fn rec<S>(&mut self, recurrence: S) -> &mut Self
+	where
+		S: Into<String>,
+	{
+		self.recurrence = Some(recurrence.into());
+		self
+	}
+
 	/// Creates a task from the builder.
 	///
+	/// The tags set with [`TaskBuilder::due`], [`TaskBuilder::threshold`] and
+	/// [`TaskBuilder::rec`] are appended to the description. Existing tags
+	/// with the same key are overwritten instead.
+	///
 	/// # Notes
 	///
 	/// If no priority was set it will use the default implementation for it.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::{Date, Task};
+	///
+	/// let task = Task::build()
+	///     .due(Date::from_ymd(2021, 8, 1))
+	///     .threshold(Date::from_ymd(2021, 7, 25))
+	///     .rec("1w")
+	///     .build("Pay rent");
+	///
+	/// assert_eq!(
+	///     task.to_string(),
+	///     "Pay rent due:2021-08-01 t:2021-07-25 rec:1w"
+	/// );
+	/// ```
 	pub fn build<D>(&mut self, description: D) -> Task
 	where
 		D: Into<Description>,
 	{
+		let mut description = description.into();
+
+		let tags = [
+			(DUE_TAG, self.due.map(|date| date.to_string())),
+			(THRESHOLD_TAG, self.threshold.map(|date| date.to_string())),
+			(RECURRENCE_TAG, self.recurrence.clone()),
+		];
+
+		for (key, value) in &tags {
+			if let Some(value) = value {
+				description.write_tag(key, value);
+			}
+		}
+
 		Task {
 			state: self.state.unwrap_or_default(),
 			priority: self.priority,
 			date_compound: self.date_compound,
-			description: description.into(),
+			description,
 		}
 	}
+
+	/// Creates a task from the builder and validates it.
+	///
+	/// # Errors
+	///
+	/// Returns an error if the task is inconsistent or can not be
+	/// represented in the todo.txt format (see [`BuildError`]).
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::{BuildError, Date, DateCompound, State, Task};
+	///
+	/// let dates = DateCompound::completed(
+	///     Date::from_ymd(2021, 7, 30),
+	///     Date::from_ymd(2021, 8, 1),
+	/// );
+	///
+	/// assert!(Task::build()
+	///     .state(State::Done)
+	///     .date_compound(dates)
+	///     .build_checked("Call Mom")
+	///     .is_ok());
+	///
+	/// assert_eq!(
+	///     Task::build().date_compound(dates).build_checked("Call Mom"),
+	///     Err(BuildError::CompletedNotDone)
+	/// );
+	/// ```
+	pub fn build_checked<D>(
+		&mut self,
+		description: D,
+	) -> Result<Task, BuildError>
+	where
+		D: Into<Description>,
+	{
+		let task = self.build(description);
+
+		if let Some(recurrence) = &self.recurrence {
+			if !is_valid_tag_value(recurrence, task.description.options()) {
+				return Err(BuildError::InvalidRecurrence(recurrence.clone()));
+			}
+		}
+
+		if task.description.contains(['\n', '\r']) {
+			return Err(BuildError::MultilineDescription);
+		}
+
+		if let Some(DateCompound::Completed { created, completed }) =
+			task.date_compound
+		{
+			if !task.state.is_done() {
+				return Err(BuildError::CompletedNotDone);
+			}
+
+			if completed < created {
+				return Err(BuildError::CompletedBeforeCreated);
+			}
+		}
+
+		check_format_parse_identity(&task).map_err(BuildError::RoundTrip)?;
+
+		Ok(task)
+	}
+}
+
+impl From<&Task> for TaskBuilder {
+	fn from(task: &Task) -> Self {
+		Self::from_task(task)
+	}
 }
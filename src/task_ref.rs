@@ -0,0 +1,197 @@
+use std::fmt;
+
+use crate::date::DateCompound;
+use crate::description::DescriptionOptions;
+use crate::description_ref::DescriptionRef;
+use crate::parse::{Parse, Parser};
+use crate::priority::Priority;
+use crate::state::State;
+use crate::task::{ParseTaskError, Task};
+
+/// A borrowed counterpart of [`Task`] whose description points directly into
+/// the parsed input.
+///
+/// Parsing a [`TaskRef`] does not allocate, which makes it well suited for
+/// scanning large files (e.g. a `done.txt`). Use [`Self::into_owned`] to get
+/// a [`Task`] which can be modified.
+///
+/// # Examples
+///
+/// ```rust
+/// use tdtxt::{Priority, TaskRef};
+///
+/// let input = "x 2021-08-01 Call Mom +Family\n(A) Pay rent due:2021-08-05\n";
+///
+/// let tasks = input
+///     .lines()
+///     .map(TaskRef::parse)
+///     .collect::<Result<Vec<_>, _>>()
+///     .unwrap();
+///
+/// assert!(tasks[0].state.is_done());
+/// assert!(tasks[0].description.is_borrowed());
+/// assert_eq!(tasks[1].priority, Some(Priority::A));
+/// assert_eq!(tasks[1].description.tag("due"), Some("2021-08-05"));
+///
+/// let task = tasks[1].clone().into_owned();
+/// assert_eq!(task.to_string(), "(A) Pay rent due:2021-08-05");
+/// ```
+#[derive(Debug, Clone)]
+pub struct TaskRef<'a> {
+	/// Optional state of the task.
+	pub state: State,
+
+	/// Optional priority of the task.
+	pub priority: Option<Priority>,
+
+	/// Optional associated special dates for the task.
+	pub date_compound: Option<DateCompound>,
+
+	/// Description of the task.
+	pub description: DescriptionRef<'a>,
+
+	/// The exact input preceding the description.
+	prefix: &'a str,
+
+	/// Whether [`Self::prefix`] is formatted canonically, i.e. every part
+	/// of it is followed by a single space.
+	canonical_prefix: bool,
+}
+
+impl<'a> TaskRef<'a> {
+	/// Parses a single task from `line`.
+	///
+	/// # Errors
+	///
+	/// Returns an error if `line` is not a valid task.
+	pub fn parse(line: &'a str) -> Result<Self, ParseTaskError> {
+		Self::parse_with_options(line, DescriptionOptions::default())
+	}
+
+	/// Parses a single task from `line` and locates the components of its
+	/// description according to `options`.
+	///
+	/// # Errors
+	///
+	/// Returns an error if `line` is not a valid task.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use tdtxt::{DescriptionOptions, TaskRef};
+	///
+	/// let options = DescriptionOptions::new().colons_in_values(true);
+	/// let task =
+	///     TaskRef::parse_with_options("Read link:https://example.com", options)
+	///         .unwrap();
+	///
+	/// assert_eq!(task.description.tag("link"), Some("https://example.com"));
+	/// ```
+	pub fn parse_with_options(
+		line: &'a str,
+		options: DescriptionOptions,
+	) -> Result<Self, ParseTaskError> {
+		let mut parser = Parser::new(line.as_bytes());
+		Self::parse_from(&mut parser, options)
+	}
+
+	/// Parses a single task from `parser`.
+	pub(crate) fn parse_from(
+		parser: &mut Parser<'a>,
+		options: DescriptionOptions,
+	) -> Result<Self, ParseTaskError> {
+		let start = parser.index();
+		let mut canonical_prefix = true;
+
+		macro_rules! try_parse {
+			( $parser:ident : $ty:ty ) => {{
+				let mut p_copy = *parser;
+
+				if let Some(ty) = <$ty>::parse_opt(&mut p_copy) {
+					// A date compound contains a separator itself
+					let component =
+						p_copy.slice(parser.index(), p_copy.index());
+					let separator = p_copy.peek();
+
+					if p_copy.is_eof() || p_copy.expect_whitespace().is_some()
+					{
+						canonical_prefix &= separator == Some(b' ')
+							&& !component.iter().any(|&byte| {
+								byte != b' ' && byte.is_ascii_whitespace()
+							});
+
+						*parser = p_copy;
+						Some(ty)
+					} else {
+						crate::trace_event!(
+							trace,
+							component = stringify!($ty),
+							"component not followed by whitespace, parsing \
+							 it as description"
+						);
+
+						None
+					}
+				} else {
+					None
+				}
+			}};
+		}
+
+		let state = try_parse!(parser: State).unwrap_or_default();
+		let priority = try_parse!(parser: Priority);
+		let date_compound = try_parse!(parser: DateCompound);
+
+		let prefix = std::str::from_utf8(parser.slice(start, parser.index()))
+			.map_err(|_| ParseTaskError)?;
+
+		let description = crate::description::parse_text(parser)
+			.map_err(|_| ParseTaskError)?;
+
+		Ok(Self {
+			state,
+			priority,
+			date_compound,
+			description: DescriptionRef::with_options(description, options),
+			prefix,
+			canonical_prefix,
+		})
+	}
+
+	/// Converts the task into an owned [`Task`], copying the description.
+	pub fn into_owned(self) -> Task {
+		Task::from_parts(
+			self.state,
+			self.priority,
+			self.date_compound,
+			self.description.into_owned(),
+			(!self.canonical_prefix).then_some(self.prefix),
+		)
+	}
+}
+
+impl From<TaskRef<'_>> for Task {
+	fn from(task: TaskRef<'_>) -> Self {
+		task.into_owned()
+	}
+}
+
+impl PartialEq for TaskRef<'_> {
+	fn eq(&self, other: &Self) -> bool {
+		// Like `Task` the spacing of the input is insignificant
+		self.state == other.state
+			&& self.priority == other.priority
+			&& self.date_compound == other.date_compound
+			&& self.description == other.description
+	}
+}
+
+impl Eq for TaskRef<'_> {}
+
+/// Formats the task exactly as it was parsed.
+impl fmt::Display for TaskRef<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.prefix)?;
+		fmt::Display::fmt(&self.description, f)
+	}
+}
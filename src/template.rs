@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::task::{ParseTaskError, Task};
+
+/// A part of a [`TaskTemplate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+	/// Literal text which is copied as is.
+	Text(String),
+
+	/// The name of a placeholder (e.g. `date` for `{date}`).
+	Placeholder(String),
+}
+
+/// A task skeleton with placeholders which can be instantiated into concrete
+/// [`Task`]s.
+///
+/// Placeholders are written as `{name}` and can appear anywhere in the line,
+/// e.g. `{priority} {date} Water plants +{project}`. Literal braces are
+/// escaped by doubling them (`{{` and `}}`).
+///
+/// # Examples
+///
+/// ```rust
+/// use std::str::FromStr;
+///
+/// use tdtxt::{Date, TaskTemplate};
+///
+/// let template =
+///     TaskTemplate::from_str("{date} Water plants +{project}").unwrap();
+///
+/// let task = template
+///     .instantiate(|name| match name {
+///         "date" => Some(Date::from_ymd(2021, 7, 1).to_string()),
+///         "project" => Some("Garden".to_string()),
+///         _ => None,
+///     })
+///     .unwrap();
+///
+/// assert_eq!(task.to_string(), "2021-07-01 Water plants +Garden");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskTemplate {
+	segments: Vec<Segment>,
+}
+
+impl TaskTemplate {
+	fn parse(s: &str) -> Result<Self, ParseTemplateError> {
+		let mut segments = Vec::new();
+		let mut text = String::new();
+		let mut chars = s.chars();
+
+		while let Some(c) = chars.next() {
+			match c {
+				'{' if chars.as_str().starts_with('{') => {
+					let _ = chars.next();
+					text.push('{');
+				}
+				'}' if chars.as_str().starts_with('}') => {
+					let _ = chars.next();
+					text.push('}');
+				}
+				'{' => {
+					let rest = chars.as_str();
+					let end = rest.find('}').ok_or_else(|| {
+						ParseTemplateError::with_msg("unclosed placeholder")
+					})?;
+					let (name, rest) = rest.split_at(end);
+
+					if name.is_empty()
+						|| name
+							.contains(|c: char| c == '{' || c.is_whitespace())
+					{
+						return Err(ParseTemplateError::with_msg(format!(
+							"invalid placeholder name `{}`",
+							name
+						)));
+					}
+
+					if !text.is_empty() {
+						segments
+							.push(Segment::Text(std::mem::take(&mut text)));
+					}
+					segments.push(Segment::Placeholder(name.to_owned()));
+					chars = rest.chars();
+					let _ = chars.next();
+				}
+				'}' => {
+					return Err(ParseTemplateError::with_msg("unmatched `}`"));
+				}
+				c => text.push(c),
+			}
+		}
+
+		if !text.is_empty() {
+			segments.push(Segment::Text(text));
+		}
+
+		Ok(Self { segments })
+	}
+
+	/// Returns the names of all placeholders in the order they appear.
+	///
+	/// Placeholders which appear more than once are returned once per
+	/// occurrence.
+	pub fn placeholders(&self) -> impl Iterator<Item = &str> + '_ {
+		self.segments.iter().filter_map(|segment| match segment {
+			Segment::Placeholder(name) => Some(name.as_str()),
+			Segment::Text(_) => None,
+		})
+	}
+
+	/// Substitutes every placeholder with the value returned by `values` and
+	/// returns the resulting line.
+	///
+	/// # Errors
+	///
+	/// Returns an error if `values` returns `None` for a placeholder.
+	pub fn render<F, V>(
+		&self,
+		mut values: F,
+	) -> Result<String, InstantiateError>
+	where
+		F: FnMut(&str) -> Option<V>,
+		V: fmt::Display,
+	{
+		let mut line = String::new();
+
+		for segment in &self.segments {
+			match segment {
+				Segment::Text(text) => line.push_str(text),
+				Segment::Placeholder(name) => {
+					let value = values(name).ok_or_else(|| {
+						InstantiateError::MissingValue(name.clone())
+					})?;
+					line.push_str(&value.to_string());
+				}
+			}
+		}
+
+		Ok(line)
+	}
+
+	/// Substitutes every placeholder with the value returned by `values` and
+	/// parses the result as a [`Task`].
+	///
+	/// Surrounding whitespace, e.g. left over from an empty `{priority}`, is
+	/// trimmed before parsing.
+	///
+	/// # Errors
+	///
+	/// Returns an error if `values` returns `None` for a placeholder or if the
+	/// resulting line is not a valid task.
+	pub fn instantiate<F, V>(
+		&self,
+		values: F,
+	) -> Result<Task, InstantiateError>
+	where
+		F: FnMut(&str) -> Option<V>,
+		V: fmt::Display,
+	{
+		let line = self.render(values)?;
+
+		Task::from_str(line.trim()).map_err(InstantiateError::Task)
+	}
+
+	/// Like [`Self::instantiate`] but looks up the placeholder values in a
+	/// list of name/value pairs.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::str::FromStr;
+	///
+	/// use tdtxt::TaskTemplate;
+	///
+	/// let template = TaskTemplate::from_str("{priority} Call {who}").unwrap();
+	///
+	/// let task = template
+	///     .instantiate_with(vec![("priority", "(A)"), ("who", "Mom")])
+	///     .unwrap();
+	/// assert_eq!(task.to_string(), "(A) Call Mom");
+	///
+	/// let task = template
+	///     .instantiate_with(vec![("priority", ""), ("who", "Dad")])
+	///     .unwrap();
+	/// assert_eq!(task.to_string(), "Call Dad");
+	/// ```
+	///
+	/// # Errors
+	///
+	/// See [`Self::instantiate`].
+	pub fn instantiate_with<I, K, V>(
+		&self,
+		values: I,
+	) -> Result<Task, InstantiateError>
+	where
+		I: IntoIterator<Item = (K, V)>,
+		K: Into<String>,
+		V: fmt::Display,
+	{
+		let values = values
+			.into_iter()
+			.map(|(key, value)| (key.into(), value))
+			.collect::<HashMap<String, V>>();
+
+		self.instantiate(|name| values.get(name))
+	}
+}
+
+impl fmt::Display for TaskTemplate {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for segment in &self.segments {
+			match segment {
+				Segment::Text(text) => {
+					f.write_str(&text.replace('{', "{{").replace('}', "}}"))?
+				}
+				Segment::Placeholder(name) => write!(f, "{{{}}}", name)?,
+			}
+		}
+
+		Ok(())
+	}
+}
+
+crate::parse_error!(ParseTemplateError: "task template");
+
+impl FromStr for TaskTemplate {
+	type Err = ParseTemplateError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Self::parse(s)
+	}
+}
+
+/// This enum represents errors which may occur when instantiating a
+/// [`TaskTemplate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InstantiateError {
+	/// No value was given for the placeholder with this name.
+	MissingValue(String),
+
+	/// The instantiated line is not a valid task.
+	Task(ParseTaskError),
+}
+
+impl fmt::Display for InstantiateError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::MissingValue(name) => {
+				write!(f, "missing value for placeholder `{}`", name)
+			}
+			Self::Task(err) => fmt::Display::fmt(err, f),
+		}
+	}
+}
+
+impl std::error::Error for InstantiateError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Self::Task(err) => Some(err),
+			Self::MissingValue(_) => None,
+		}
+	}
+}
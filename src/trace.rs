@@ -0,0 +1,13 @@
+/// Emits a [`tracing`](https://docs.rs/tracing) event with the given level
+/// (e.g. `debug`) if the feature `tracing` is enabled.
+///
+/// Without the feature the macro expands to nothing, so the arguments must
+/// not have side effects.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! trace_event {
+	( $level:ident, $( $args:tt )+ ) => {
+		#[cfg(feature = "tracing")]
+		::tracing::$level!($( $args )+);
+	};
+}
@@ -0,0 +1,145 @@
+use crate::date::{Date, Days};
+use crate::task::Task;
+
+/// Calculates an urgency score for [`Task`]s, similar to the urgency of
+/// taskwarrior.
+///
+/// The score is the sum of the following terms, each consisting of a
+/// coefficient multiplied with a factor between `0.0` and `1.0`:
+///
+/// | Coefficient | Factor                                                   |
+/// |-------------|----------------------------------------------------------|
+/// | `priority`  | `1.0` for `A` down to `1/26` for `Z`                     |
+/// | `due`       | `0.2` if due in 14+ days up to `1.0` if 7+ days overdue  |
+/// | `age`       | days since creation divided by `max_age` (at most `1.0`) |
+/// | `project`   | `1.0` if the task has a project                          |
+/// | `context`   | `1.0` if the task has a context                          |
+///
+/// Missing values (e.g. no priority or due date) have a factor of `0.0`.
+///
+/// Done tasks always have a score of `0.0`.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::str::FromStr;
+///
+/// use tdtxt::{Date, Task, Urgency};
+///
+/// let today = Date::from_ymd(2021, 8, 1);
+/// let urgency = Urgency::default();
+///
+/// let mut tasks = [
+///     "(C) Buy milk",
+///     "Pay rent due:2021-07-30",
+///     "(A) Call Mom +Family",
+/// ]
+/// .iter()
+/// .map(|line| Task::from_str(line).unwrap())
+/// .collect::<Vec<_>>();
+///
+/// // Most urgent first
+/// tasks.sort_by(|a, b| {
+///     urgency.score(b, today).total_cmp(&urgency.score(a, today))
+/// });
+///
+/// assert_eq!(tasks[0].to_string(), "Pay rent due:2021-07-30");
+/// assert_eq!(tasks[1].to_string(), "(A) Call Mom +Family");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Urgency {
+	/// Coefficient of the priority.
+	pub priority: f64,
+
+	/// Coefficient of the due date (`due:` tag).
+	pub due: f64,
+
+	/// Coefficient of the age (see [`Task::age`]).
+	pub age: f64,
+
+	/// The age at which the age factor reaches `1.0`.
+	pub max_age: Days,
+
+	/// Coefficient for having at least one project.
+	pub project: f64,
+
+	/// Coefficient for having at least one context.
+	pub context: f64,
+}
+
+impl Urgency {
+	/// Creates a calculator with the default coefficients.
+	///
+	/// # Defaults
+	///
+	/// The defaults are the same as taskwarrior uses: `priority` is `6.0`,
+	/// `due` is `12.0`, `age` is `2.0` with a `max_age` of `365` days and
+	/// `project` and `context` are `1.0`.
+	pub const fn new() -> Self {
+		Self {
+			priority: 6.0,
+			due: 12.0,
+			age: 2.0,
+			max_age: Days(365),
+			project: 1.0,
+			context: 1.0,
+		}
+	}
+
+	/// Calculates the urgency score of `task`.
+	pub fn score(&self, task: &Task, today: Date) -> f64 {
+		if task.state.is_done() {
+			return 0.0;
+		}
+
+		let description = &task.description;
+
+		self.priority * priority_factor(task)
+			+ self.due * due_factor(task, today)
+			+ self.age * self.age_factor(task, today)
+			+ self.project * flag(description.projects().next().is_some())
+			+ self.context * flag(description.contexts().next().is_some())
+	}
+
+	fn age_factor(&self, task: &Task, today: Date) -> f64 {
+		match task.age(today) {
+			Some(Days(age)) if self.max_age.0 > 0 => {
+				(age as f64 / self.max_age.0 as f64).clamp(0.0, 1.0)
+			}
+			Some(_) => 1.0,
+			None => 0.0,
+		}
+	}
+}
+
+impl Default for Urgency {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// Returns `1.0` for `A` down to `1/26` for `Z` and `0.0` for no priority.
+fn priority_factor(task: &Task) -> f64 {
+	task.priority.map_or(0.0, |priority| {
+		f64::from(b'Z' - priority.as_char() as u8 + 1) / 26.0
+	})
+}
+
+/// Returns `0.2` for tasks due in 14 or more days, rising linearly to `1.0`
+/// for tasks overdue by 7 or more days and `0.0` for tasks without due date.
+fn due_factor(task: &Task, today: Date) -> f64 {
+	task.due_date().map_or(0.0, |due| {
+		let overdue = due.days_until(&today).clamp(-14, 7);
+
+		(overdue + 14) as f64 * 0.8 / 21.0 + 0.2
+	})
+}
+
+const fn flag(value: bool) -> f64 {
+	if value {
+		1.0
+	} else {
+		0.0
+	}
+}